@@ -0,0 +1,58 @@
+// clean_content.rs - Benchmarks for content cleanup
+// Run with `cargo bench --bench clean_content`. Cleanup runs once per stored
+// unit, so a large import pays for it thousands of times. That a faster
+// version still stores the same text is checked by the unit tests in
+// content.rs, which clean the same samples.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use tellme::{ContentUnit, Topic};
+
+/// Units cleaned per iteration, about the size of a large import
+const IMPORT_UNITS: usize = 5_000;
+
+/// Raw intros shaped like what the fetcher and importer see
+const SAMPLES: &[&str] = &[
+    "The Roman Empire[1] was the post-Republican state of ancient Rome.[2][3]\n\nIt ruled the Mediterranean[4] for centuries.",
+    "  Leading and trailing spaces  \n\n\n\n   around lines [12]  \r\n\tand tabs\t",
+    "[1]\n[2] \n   [345]   \nOnly citations above this line.",
+    "Brackets that aren't citations stay: [a], [1a], [ 1 ], [] and [١].",
+    "No citations at all, just one long line of text about the Vikings raiding Lindisfarne in 793.",
+    "",
+    "\n\n\n",
+    "Unicode text — the Byzantine Empire (Βυζάντιο)[5] lasted until 1453.[6]\n\nÜber alles[7].",
+    "Nested [[1]] and split [1\n] markers, and a number in brackets at the end [2024]",
+];
+
+fn unit(text: &str) -> ContentUnit {
+    ContentUnit::new(
+        Topic::AncientRome,
+        "Sample".to_string(),
+        text.to_string(),
+        "https://en.wikipedia.org/wiki/Sample".to_string(),
+    )
+}
+
+fn bench_clean_content(c: &mut Criterion) {
+    let units: Vec<ContentUnit> = SAMPLES
+        .iter()
+        .cycle()
+        .take(IMPORT_UNITS)
+        .map(|sample| unit(&sample.repeat(4)))
+        .collect();
+
+    c.bench_function("clean_content/import", |b| {
+        b.iter_batched(
+            || units.clone(),
+            |mut units| {
+                for unit in &mut units {
+                    unit.clean_content();
+                }
+                units
+            },
+            criterion::BatchSize::LargeInput,
+        )
+    });
+}
+
+criterion_group!(benches, bench_clean_content);
+criterion_main!(benches);
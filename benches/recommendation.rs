@@ -0,0 +1,118 @@
+// recommendation.rs - Benchmarks for the content recommender
+// Run with `cargo bench --bench recommendation`. Each benchmark seeds an
+// in-memory database with a realistic library and interaction history so
+// regressions in selection cost show up as the history grows.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::collections::{HashMap, HashSet};
+use tellme::{
+    config::RecommendationConfig,
+    selection::{score_topics, SelectionInputs},
+    ContentUnit, Database, Topic, UserInteraction,
+};
+
+/// Content units created per topic
+const UNITS_PER_TOPIC: usize = 25;
+
+/// Interaction history sizes to benchmark against
+const HISTORY_SIZES: [usize; 3] = [0, 100, 10_000];
+
+/// Build an in-memory database with a full library and `interactions` random interactions
+fn seeded_database(interactions: usize) -> Database {
+    let db = Database::new_in_memory().expect("Failed to create in-memory database");
+    let mut ids = Vec::new();
+
+    for &topic in Topic::all() {
+        for i in 0..UNITS_PER_TOPIC {
+            let mut unit = ContentUnit::new(
+                topic,
+                format!("{} article {}", topic, i),
+                "An engaging historical story about battles, empires and discoveries. ".repeat(8),
+                format!("https://en.wikipedia.org/wiki/{}_{}", topic, i),
+            );
+            db.insert_content(&mut unit).expect("Failed to insert content");
+            ids.push(unit.id);
+        }
+    }
+
+    // Fixed seed so every run benchmarks the same history
+    let mut rng = StdRng::seed_from_u64(42);
+    for _ in 0..interactions {
+        let content_id = ids[rng.gen_range(0..ids.len())];
+        let interaction = if rng.gen_bool(0.6) {
+            UserInteraction::fully_read(content_id, rng.gen_range(5..120))
+        } else {
+            UserInteraction::skipped(content_id, rng.gen_range(0..5))
+        };
+        db.record_interaction(&interaction).expect("Failed to record interaction");
+    }
+
+    db
+}
+
+fn bench_weighted_selection(c: &mut Criterion) {
+    let mut group = c.benchmark_group("get_weighted_random_content");
+    for size in HISTORY_SIZES {
+        let db = seeded_database(size);
+        group.bench_with_input(BenchmarkId::from_parameter(size), &db, |b, db| {
+            b.iter(|| db.get_weighted_random_content().unwrap())
+        });
+    }
+    group.finish();
+}
+
+fn bench_topic_preferences(c: &mut Criterion) {
+    let mut group = c.benchmark_group("get_topic_preferences");
+    for size in HISTORY_SIZES {
+        let db = seeded_database(size);
+        group.bench_with_input(BenchmarkId::from_parameter(size), &db, |b, db| {
+            b.iter(|| db.get_topic_preferences().unwrap())
+        });
+    }
+    group.finish();
+}
+
+fn bench_score_topics(c: &mut Criterion) {
+    let settings = RecommendationConfig::default();
+    let mut rng = StdRng::seed_from_u64(7);
+    let topics = Topic::all();
+
+    // Random libraries and histories
+    let cases: Vec<_> = (0..200)
+        .map(|_| {
+            let mut preferences = HashMap::new();
+            for &topic in topics.iter() {
+                if rng.gen_bool(0.7) {
+                    preferences.insert(topic, rng.gen::<f64>());
+                }
+            }
+            let interaction_counts: HashMap<Topic, i64> =
+                topics.iter().map(|&topic| (topic, rng.gen_range(0..20))).collect();
+            let recent_topics: Vec<Topic> =
+                (0..rng.gen_range(0..8)).map(|_| topics[rng.gen_range(0..topics.len())]).collect();
+            let selectable: HashSet<Topic> =
+                topics.iter().copied().filter(|_| rng.gen_bool(0.5)).collect();
+            let balance = [0.0, 0.2, 0.5, 0.8, 1.0][rng.gen_range(0..5)];
+            (preferences, interaction_counts, recent_topics, selectable, balance)
+        })
+        .collect();
+    let inputs: Vec<SelectionInputs> = cases
+        .iter()
+        .map(|(preferences, interaction_counts, recent_topics, selectable, balance)| SelectionInputs {
+            preferences,
+            interaction_counts,
+            recent_topics,
+            selectable,
+            settings: &settings,
+            balance: *balance,
+        })
+        .collect();
+
+    c.bench_function("score_topics", |b| {
+        b.iter(|| inputs.iter().map(|input| score_topics(input).len()).sum::<usize>())
+    });
+}
+
+criterion_group!(benches, bench_weighted_selection, bench_topic_preferences, bench_score_topics);
+criterion_main!(benches);
@@ -7,7 +7,7 @@ use serde::{Deserialize, Serialize};
 use semver::Version;
 use std::time::Duration;
 
-const GITHUB_REPO: &str = "xeij/tellme"; // Replace with actual repo
+pub const GITHUB_REPO: &str = "xeij/tellme"; // Replace with actual repo
 const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
 const UPDATE_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
 
@@ -91,6 +91,14 @@ pub struct UpdateInfo {
 }
 
 impl UpdateInfo {
+    /// One-line notice for the status bar
+    pub fn summary(&self) -> String {
+        format!(
+            "Update available: {} → {} ({})",
+            self.current_version, self.latest_version, self.release_url
+        )
+    }
+
     pub fn display_notification(&self) -> String {
         format!(
             "📢 Update Available!\n\n\
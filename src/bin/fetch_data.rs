@@ -3,352 +3,305 @@
 // and data processing in Rust
 
 use anyhow::Result;
-use rand::seq::SliceRandom;
-use reqwest::Client;
-use serde_json::Value;
-use std::time::Duration;
+use serde::Serialize;
+use std::{collections::HashMap, time::Duration};
 use tellme::{
-    content::{ContentUnit, Topic},
+    content::Topic,
     database::Database,
-    ensure_data_dir, DB_FILE,
+    ensure_data_dir,
+    fetch::{
+        run_fetch, Checkpoint, FetchEvent, TitleBlocklist, TopicFetchStats, WikipediaClient,
+        LONG_INTRO_MAX_UNITS, SEARCH_LIMIT, UNITS_PER_TOPIC,
+    },
+    Config, DB_FILE, FETCH_CHECKPOINT_FILE,
 };
 
-/// Wikipedia API client for fetching articles
-/// This struct demonstrates HTTP client usage and rate limiting
-struct WikipediaClient {
-    client: Client,
-    base_url: String,
+/// Rough round-trip time of one API request, used only for dry-run estimates
+const ESTIMATED_REQUEST_TIME: Duration = Duration::from_millis(400);
+
+/// Exit code when more than half the requests failed with network errors
+const EXIT_MOSTLY_OFFLINE: i32 = 2;
+
+/// Exit code when the run stored no units at all
+const EXIT_NOTHING_STORED: i32 = 3;
+
+/// How `--dry-run` behaves
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DryRun {
+    /// Print the plan without any network calls
+    PlanOnly,
+    /// Also run the search queries to show candidate titles, without fetching articles
+    SearchOnly,
 }
 
-impl WikipediaClient {
-    /// Create a new Wikipedia client
-    fn new() -> Self {
-        let client = Client::builder()
-            .user_agent("tellme/0.1.0 (https://github.com/example/tellme)")
-            .timeout(Duration::from_secs(30))
-            .build()
-            .expect("Failed to create HTTP client");
-
-        Self {
-            client,
-            base_url: "https://en.wikipedia.org/w/api.php".to_string(),
-        }
-    }
+/// What a fetch run would do, per topic
+#[derive(Debug, Serialize)]
+struct FetchPlan {
+    units_per_topic: usize,
+    topics: Vec<TopicPlan>,
+}
 
-    /// Search for articles on a given topic
-    /// This demonstrates async HTTP requests and JSON parsing
-    async fn search_articles(&self, query: &str, limit: usize) -> Result<Vec<String>> {
-        let url = format!(
-            "{}?action=opensearch&search={}&limit={}&namespace=0&format=json",
-            self.base_url,
-            urlencoding::encode(query),
-            limit
-        );
+/// Planned work for one topic
+#[derive(Debug, Serialize)]
+struct TopicPlan {
+    topic: Topic,
+    queries: Vec<String>,
+    existing_units: i64,
+    units_to_fetch: usize,
+    search_requests: usize,
+    /// Fewest article requests needed, if every article yields a unit
+    min_article_requests: usize,
+    /// Most article requests the run may make, if every search fills up
+    max_article_requests: usize,
+}
 
-        println!("Searching for: {} (limit: {})", query, limit);
+impl FetchPlan {
+    fn total_requests(&self) -> (usize, usize) {
+        self.topics.iter().fold((0, 0), |(min, max), t| {
+            (
+                min + t.search_requests + t.min_article_requests,
+                max + t.search_requests + t.max_article_requests,
+            )
+        })
+    }
 
-        let response = self.client.get(&url).send().await?;
-        let text = response.text().await?;
-        
-        // Parse the OpenSearch JSON response
-        let json: Value = serde_json::from_str(&text)?;
-        
-        if let Some(titles) = json.get(1).and_then(|v| v.as_array()) {
-            let article_titles: Vec<String> = titles
-                .iter()
-                .filter_map(|v| v.as_str())
-                .map(|s| s.to_string())
-                .collect();
-            
-            Ok(article_titles)
-        } else {
-            Ok(Vec::new())
-        }
+    /// Estimated wall-clock time for the fewest and most requests, sent no more often
+    /// than once per `request_interval`, with up to `concurrency` article requests at once
+    fn estimated_duration(&self, request_interval: Duration, concurrency: usize) -> (Duration, Duration) {
+        let per_search = ESTIMATED_REQUEST_TIME.max(request_interval);
+        let per_article = (ESTIMATED_REQUEST_TIME / concurrency as u32).max(request_interval);
+        let estimate =
+            |searches: usize, articles: usize| per_search * searches as u32 + per_article * articles as u32;
+        self.topics.iter().fold((Duration::ZERO, Duration::ZERO), |(min, max), t| {
+            (
+                min + estimate(t.search_requests, t.min_article_requests),
+                max + estimate(t.search_requests, t.max_article_requests),
+            )
+        })
     }
 
-    /// Get the content of a Wikipedia article
-    /// This demonstrates error handling and HTML parsing
-    async fn get_article_content(&self, title: &str) -> Result<Option<(String, String)>> {
-        let url = format!(
-            "{}?action=query&format=json&titles={}&prop=extracts&exintro=&explaintext=&exsectionformat=plain",
-            self.base_url,
-            urlencoding::encode(title)
+    /// Print the plan as a table followed by totals, timed as `client` would send the requests
+    fn print(&self, client: &WikipediaClient) {
+        println!(
+            "{:<22} {:>8} {:>8} {:>8} {:>14}",
+            "Topic", "Existing", "Fetch", "Searches", "Articles"
         );
-
-        println!("Fetching article: {}", title);
-
-        let response = self.client.get(&url).send().await?;
-        let text = response.text().await?;
-        
-        let json: Value = serde_json::from_str(&text)?;
-        
-        // Navigate the complex Wikipedia API response structure
-        if let Some(pages) = json.get("query").and_then(|q| q.get("pages")) {
-            if let Some(page) = pages.as_object().and_then(|obj| obj.values().next()) {
-                if let Some(extract) = page.get("extract").and_then(|e| e.as_str()) {
-                    let page_url = format!("https://en.wikipedia.org/wiki/{}", 
-                                         urlencoding::encode(title));
-                    return Ok(Some((extract.to_string(), page_url)));
-                }
-            }
+        for t in &self.topics {
+            println!(
+                "{:<22} {:>8} {:>8} {:>8} {:>14}",
+                t.topic.to_string(),
+                t.existing_units,
+                t.units_to_fetch,
+                t.search_requests,
+                format!("{}-{}", t.min_article_requests, t.max_article_requests)
+            );
         }
-        
-        Ok(None)
-    }
 
-    /// Add a small delay between requests to be respectful to Wikipedia
-    async fn rate_limit(&self) {
-        tokio::time::sleep(Duration::from_millis(500)).await;
+        let (min_requests, max_requests) = self.total_requests();
+        let (min_time, max_time) = self.estimated_duration(client.request_interval(), client.concurrency());
+        println!();
+        println!("Requests: {} to {}", min_requests, max_requests);
+        println!(
+            "Estimated time: {} to {} minutes",
+            min_time.as_secs() / 60,
+            max_time.as_secs().div_ceil(60)
+        );
     }
 }
 
-/// Process article content into suitable units
-/// This demonstrates text processing and content validation with QUALITY SCORING
-fn process_article_content(
+/// How a fetch run went, written with `--report-json`
+#[derive(Debug, Serialize)]
+struct FetchReport<'a> {
+    units_per_topic: usize,
+    topics: Vec<TopicReport<'a>>,
+    totals: TopicFetchStats,
+    exit_code: i32,
+}
+
+/// One topic's results in a fetch report
+#[derive(Debug, Serialize)]
+struct TopicReport<'a> {
     topic: Topic,
-    title: &str,
-    content: &str,
-    source_url: &str,
-) -> Vec<ContentUnit> {
-    let mut units = Vec::new();
-    
-    // First, check content quality score
-    let quality_score = calculate_content_quality_score(content, title);
-    
-    // Only process decent quality, engaging content (score > 0, lowered from 3)
-    if quality_score < 0 {
-        return units; // Skip truly boring content
-    }
-    
-    // First, try to use the full content if it's not too long
-    if content.len() > 100 && content.len() < 3000 {
-        let mut full_unit = ContentUnit::new(
-            topic,
-            title.to_string(),
-            content.to_string(),
-            source_url.to_string(),
-        );
-        
-        full_unit.clean_content();
-        
-        if full_unit.is_suitable_length() {
-            units.push(full_unit);
-            return units; // Return the full content if it's suitable
-        }
+    #[serde(flatten)]
+    stats: &'a TopicFetchStats,
+}
+
+/// Exit code for a finished run: 0, unless most requests hit network errors or
+/// nothing was stored
+fn exit_code(totals: &TopicFetchStats) -> i32 {
+    if totals.failures.network * 2 > totals.requests {
+        EXIT_MOSTLY_OFFLINE
+    } else if totals.units == 0 {
+        EXIT_NOTHING_STORED
+    } else {
+        0
     }
-    
-    // If full content is too long, split into sections
-    let sections: Vec<&str> = content
-        .split("\n\n")
-        .map(|s| s.trim())
-        .filter(|s| !s.is_empty() && s.len() > 30)
+}
+
+/// Work out what a fetch run would do from the existing per-topic content counts
+/// Every topic gets `units_per_topic` new units on top of what it already has
+fn build_plan(existing: &HashMap<Topic, i64>, units_per_topic: usize) -> FetchPlan {
+    let topics = Topic::all()
+        .iter()
+        .map(|&topic| {
+            let queries: Vec<String> = topic.search_queries().iter().map(|q| q.to_string()).collect();
+            TopicPlan {
+                topic,
+                existing_units: existing.get(&topic).copied().unwrap_or(0),
+                units_to_fetch: units_per_topic,
+                search_requests: queries.len(),
+                min_article_requests: units_per_topic.min(queries.len() * SEARCH_LIMIT),
+                max_article_requests: queries.len() * SEARCH_LIMIT,
+                queries,
+            }
+        })
         .collect();
 
-    // Try to create content units from sections
-    let mut i = 0;
-    while i < sections.len() {
-        let mut unit_content = sections[i].to_string();
-        
-        // If current section is short, try to combine with next sections
-        let mut j = i + 1;
-        while j < sections.len() && unit_content.len() < 400 {
-            unit_content.push_str("\n\n");
-            unit_content.push_str(sections[j]);
-            j += 1;
-        }
-        
-        // Check quality of this specific unit content
-        let unit_quality = calculate_content_quality_score(&unit_content, title);
-        if unit_quality < -1 {
-            i = if j > i + 1 { j } else { i + 1 };
-            continue; // Skip very low-quality sections
-        }
-        
-        let mut content_unit = ContentUnit::new(
-            topic,
-            title.to_string(),
-            unit_content,
-            source_url.to_string(),
-        );
-        
-        content_unit.clean_content();
-        
-        if content_unit.is_suitable_length() {
-            units.push(content_unit);
-        }
-        
-        // Move to the next unprocessed section
-        i = if j > i + 1 { j } else { i + 1 };
+    FetchPlan {
+        units_per_topic,
+        topics,
     }
-    
-    units
 }
 
-/// Calculate content quality score based on engaging keywords and patterns
-/// Higher scores = more interesting, engaging content
-fn calculate_content_quality_score(content: &str, title: &str) -> i32 {
-    let content_lower = content.to_lowercase();
-    let title_lower = title.to_lowercase();
-    let combined = format!("{} {}", title_lower, content_lower);
-    
-    let mut score = 0;
-    
-    // BASE SCORE for any historical content (be more generous)
-    if content.len() > 50 {
-        score += 1; // Base point for having actual content
-    }
-    
-    // POSITIVE INDICATORS - Fascinating, engaging content
-    let fascinating_words = [
-        // Discovery & Mystery
-        "discovered", "mystery", "secret", "hidden", "revealed", "uncovered", "found",
-        "breakthrough", "revelation", "shocking", "amazing", "incredible", "extraordinary",
-        
-        // Drama & Intrigue  
-        "betrayal", "conspiracy", "scandal", "plot", "intrigue", "assassination", "murder",
-        "rebellion", "revolution", "war", "battle", "siege", "conquest", "victory", "defeat",
-        
-        // Human Interest
-        "heroic", "courage", "brave", "survival", "escape", "rescue", "adventure",
-        "legend", "myth", "story", "tale", "epic", "dramatic", "tragic", "romance",
-        
-        // Unusual & Bizarre
-        "strange", "bizarre", "unusual", "weird", "odd", "peculiar", "unique", "rare",
-        "first", "last", "only", "never", "always", "forbidden", "lost", "ancient",
-        
-        // Innovation & Achievement
-        "invented", "created", "built", "achieved", "accomplished", "succeeded", "triumph",
-        "genius", "brilliant", "innovative", "revolutionary", "groundbreaking",
-        
-        // Superlatives & Records
-        "largest", "smallest", "fastest", "strongest", "richest", "most", "greatest",
-        "best", "worst", "famous", "notorious", "legendary", "record", "unprecedented"
-    ];
-    
-    for word in &fascinating_words {
-        if combined.contains(word) {
-            score += 1;
-        }
-    }
-    
-    // BONUS for multiple engaging elements
-    if combined.contains("emperor") || combined.contains("king") || combined.contains("queen") {
-        score += 1;
-    }
-    if combined.contains("treasure") || combined.contains("gold") || combined.contains("wealth") {
-        score += 1;
-    }
-    if combined.contains("died") || combined.contains("killed") || combined.contains("death") {
-        score += 1;
-    }
-    if combined.contains("empire") || combined.contains("kingdom") || combined.contains("civilization") {
-        score += 1;
-    }
-    
-    // BONUS for historical periods and dates
-    if content.contains("BCE") || content.contains("CE") || content.contains("century") || content.contains("AD") {
-        score += 2; // Historical content gets bonus points
-    }
-    
-    // BONUS for people and places (historical names)
-    if combined.contains("dynasty") || combined.contains("pharaoh") || combined.contains("caesar") {
-        score += 1;
-    }
-    
-    // NEGATIVE INDICATORS - Boring, dry content (less harsh)
-    let boring_indicators = [
-        "list of", "disambiguation", "stub", "citation needed",
-        "clarification needed", "template", "infobox", "navbox"
-    ];
-    
-    for indicator in &boring_indicators {
-        if combined.contains(indicator) {
-            score -= 3; // Still penalize but less harshly
-        }
+/// Print the plan (and with `search-only`, the candidate titles) without fetching articles
+async fn run_dry_run(
+    client: &WikipediaClient,
+    db: &Database,
+    blocklist: &TitleBlocklist,
+    mode: DryRun,
+    plan_json: Option<&str>,
+) -> Result<()> {
+    let plan = build_plan(&db.get_content_count_by_topic()?, UNITS_PER_TOPIC);
+    println!("=== Fetch plan (dry run) ===\n");
+    plan.print(client);
+
+    if let Some(path) = plan_json {
+        std::fs::write(path, serde_json::to_string_pretty(&plan)?)?;
+        println!("Plan written to {}", path);
     }
-    
-    // MILD penalty for overly technical language
-    let technical_words = ["according to", "it is believed", "scholars suggest"];
-    for word in &technical_words {
-        if combined.contains(word) {
-            score -= 1;
+
+    if mode == DryRun::SearchOnly {
+        for topic_plan in &plan.topics {
+            println!("\n=== Candidates for {} ===", topic_plan.topic);
+            for query in &topic_plan.queries {
+                println!("Searching for: {} (limit: {})", query, SEARCH_LIMIT);
+                let titles = client.search_articles(query, SEARCH_LIMIT).await?;
+                for title in titles {
+                    let marker = if blocklist.is_blocked(&title) { "✗" } else { " " };
+                    println!("  {} {}", marker, title);
+                }
+            }
         }
     }
-    
-    score
+
+    Ok(())
 }
 
-/// Fetch content for a specific topic
-/// This demonstrates error handling and progress reporting
-async fn fetch_topic_content(
-    client: &WikipediaClient,
-    db: &Database,
-    topic: Topic,
-    target_count: usize,
-) -> Result<usize> {
-    println!("\n=== Fetching content for {} ===", topic);
-    
-    let mut total_units = 0;
-    let queries = topic.search_queries();
-    
-    for query in queries {
-        if total_units >= target_count {
-            break;
-        }
-        
-        // Search for articles (massive limit increase for 10x content variety)
-        let article_titles = client.search_articles(query, 50).await?;
-        
-        for title in article_titles {
-            if total_units >= target_count {
-                break;
-            }
-            
-            // Skip disambiguation and list pages
-            if title.contains("disambiguation") || title.contains("List of") {
-                continue;
-            }
-            
-            client.rate_limit().await;
-            
-            match client.get_article_content(&title).await {
-                Ok(Some((content, url))) => {
-                    let units = process_article_content(topic, &title, &content, &url);
-                    
-                    for mut unit in units {
-                        match db.insert_content(&mut unit) {
-                            Ok(()) => {
-                                total_units += 1;
-                                println!("  ✓ Added unit {} from '{}'", total_units, title);
-                            }
-                            Err(e) => {
-                                eprintln!("  ✗ Failed to save unit from '{}': {}", title, e);
-                            }
-                        }
-                        
-                        if total_units >= target_count {
-                            break;
-                        }
-                    }
-                }
-                Ok(None) => {
-                    println!("  - No content found for '{}'", title);
-                }
-                Err(e) => {
-                    eprintln!("  ✗ Error fetching '{}': {}", title, e);
-                }
+/// Print a fetch event as a line of console output
+fn print_event(event: &FetchEvent) {
+    match event {
+        FetchEvent::Started { .. } => {}
+        FetchEvent::TopicStarted { topic, units } => {
+            if *units > 0 {
+                println!("Resuming {} with {} units already fetched", topic, units);
             }
         }
+        FetchEvent::Searching { query, .. } => {
+            println!("Searching for: {} (limit: {})", query, SEARCH_LIMIT);
+        }
+        FetchEvent::TopicQueued { topic, titles } => {
+            println!("Queued {} articles for {}\n", titles, topic);
+        }
+        FetchEvent::Fetching { topic, query, title } => {
+            println!("Fetching article: {} ({}, from '{}')", title, topic, query);
+        }
+        FetchEvent::Redirected { from, to } => println!("  ↳ '{}' redirects to '{}'", from, to),
+        FetchEvent::FromBody { title, chars } => {
+            println!("  ⚠ No intro for '{}', using its body ({} chars)", title, chars);
+        }
+        FetchEvent::LongIntro { title, chars } => println!(
+            "  ⚠ Long intro for '{}' ({} chars), keeping the best {} units",
+            title, chars, LONG_INTRO_MAX_UNITS
+        ),
+        FetchEvent::UnitAdded {
+            title,
+            units,
+            quality,
+            hook,
+            ..
+        } => println!(
+            "  ✓ Added unit {} from '{}' (quality {}, hook {})",
+            units, title, quality, hook
+        ),
+        FetchEvent::FunFactAdded { text, .. } => println!("    ✓ Added fun fact: {}", text),
+        FetchEvent::DuplicateSkipped { title, .. } => {
+            println!("  = Skipped a unit from '{}' that is already stored", title);
+        }
+        FetchEvent::NoContent { title } => println!("  - No content found for '{}'", title),
+        FetchEvent::Rejected { title } => println!("  - Nothing in '{}' passed the quality checks", title),
+        FetchEvent::ArticleFailed { title, kind, error } => {
+            eprintln!("  ✗ Error with '{}' ({}): {}", title, kind.as_str(), error);
+        }
+        FetchEvent::TopicFinished {
+            topic,
+            units,
+            blocked_titles,
+        } => println!(
+            "Fetched {} units for {} ({} titles blocked)",
+            units, topic, blocked_titles
+        ),
+        FetchEvent::TopicFailed { topic, error } => {
+            eprintln!("Error fetching content for {}: {}", topic, error);
+        }
+        FetchEvent::Finished { .. } => {}
+        FetchEvent::Failed { error } => eprintln!("Fetch stopped: {}", error),
     }
-    
-    println!("Fetched {} units for {}", total_units, topic);
-    Ok(total_units)
 }
 
 /// Main entry point for the data fetcher
 /// This demonstrates the main async function pattern and comprehensive error handling
 #[tokio::main]
 async fn main() -> Result<()> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let dry_run = match args.iter().find(|a| a.starts_with("--dry-run")).map(|a| a.as_str()) {
+        None => None,
+        Some("--dry-run") => Some(DryRun::PlanOnly),
+        Some("--dry-run=search-only") => Some(DryRun::SearchOnly),
+        Some(other) => anyhow::bail!("Unknown option {} (expected --dry-run or --dry-run=search-only)", other),
+    };
+    let plan_json = args
+        .iter()
+        .position(|a| a == "--plan-json")
+        .map(|i| {
+            args.get(i + 1)
+                .map(|s| s.as_str())
+                .ok_or_else(|| anyhow::anyhow!("--plan-json needs a file name"))
+        })
+        .transpose()?;
+    if plan_json.is_some() && dry_run.is_none() {
+        anyhow::bail!("--plan-json only works together with --dry-run");
+    }
+    let report_json = args
+        .iter()
+        .position(|a| a == "--report-json")
+        .map(|i| {
+            args.get(i + 1)
+                .map(|s| s.as_str())
+                .ok_or_else(|| anyhow::anyhow!("--report-json needs a file name"))
+        })
+        .transpose()?;
+    if report_json.is_some() && dry_run.is_some() {
+        anyhow::bail!("--report-json can't be combined with --dry-run (use --plan-json)");
+    }
+    let resume = args.iter().any(|a| a == "--resume");
+    if resume && dry_run.is_some() {
+        anyhow::bail!("--resume can't be combined with --dry-run");
+    }
+    let reset_progress = args.iter().any(|a| a == "--reset-progress");
+    if reset_progress && dry_run.is_some() {
+        anyhow::bail!("--reset-progress can't be combined with --dry-run");
+    }
+
     println!("tellme Data Fetcher");
     println!("==================");
     println!("This will download and process Wikipedia articles for all topics.");
@@ -357,14 +310,41 @@ async fn main() -> Result<()> {
     // Ensure data directory exists
     ensure_data_dir()?;
     
+    // Load configuration and compile the title blocklist
+    let config = Config::load()?;
+    let blocklist = TitleBlocklist::from_config(&config.fetch)?;
+    
     // Initialize database
-    let db = Database::new(DB_FILE)?;
+    let db = Database::new(DB_FILE)?
+        .with_storage(config.storage.clone())
+        .with_sensitivity(config.sensitivity.clone());
+
+    // Forget which articles earlier runs processed, so they're all tried again
+    if reset_progress {
+        let forgotten = db.reset_fetch_progress()?;
+        println!("Cleared fetch progress ({} processed articles forgotten)", forgotten);
+    }
+
+    if let Some(mode) = dry_run {
+        return run_dry_run(&WikipediaClient::new(&config.fetch), &db, &blocklist, mode, plan_json).await;
+    }
     
+    // Pick up where an interrupted run stopped, if asked to
+    let checkpoint = match (Checkpoint::load()?, resume) {
+        (Some(checkpoint), true) => Some(checkpoint),
+        (None, true) => anyhow::bail!("No interrupted run to resume ({} not found)", FETCH_CHECKPOINT_FILE),
+        (Some(_), false) => {
+            println!("An earlier run was interrupted; pass --resume to continue it instead of starting over.");
+            None
+        }
+        (None, false) => None,
+    };
+
     // Check existing content
     let existing_count = db.get_content_count()?;
     println!("Current database contains {} content units", existing_count);
     
-    if existing_count > 0 {
+    if existing_count > 0 && checkpoint.is_none() {
         println!("Database already contains content. This will add more content to it.");
         println!("Continue? (y/N)");
         
@@ -378,35 +358,53 @@ async fn main() -> Result<()> {
     }
     
     // Create Wikipedia client
-    let client = WikipediaClient::new();
+    let client = WikipediaClient::new(&config.fetch);
     
-    // Target number of units per topic (REDUCED for focused historical content!)
-    // With 21 historical periods, this will give us ~525 total units (quality over quantity)
-    let units_per_topic = 25; // 21 topics × 25 units = ~525 total units
-    let mut total_fetched = 0;
-    
-    // Fetch content for each topic
-    let topics = Topic::all();
-    let mut rng = rand::thread_rng();
-    let mut shuffled_topics = topics.to_vec();
-    shuffled_topics.shuffle(&mut rng);
-    
-    for &topic in &shuffled_topics {
-        match fetch_topic_content(&client, &db, topic, units_per_topic).await {
-            Ok(count) => {
-                total_fetched += count;
-            }
-            Err(e) => {
-                eprintln!("Error fetching content for {}: {}", topic, e);
-            }
+    // Fetch content for each topic, in a random order that a resumed run keeps
+    let mut checkpoint = match checkpoint {
+        Some(checkpoint) => {
+            println!(
+                "Resuming an interrupted run ({} of {} topics done, {} units fetched so far)",
+                checkpoint.finished_topics(),
+                checkpoint.topics.len(),
+                checkpoint.totals().units
+            );
+            checkpoint
         }
-        
-        // Brief pause between topics
-        tokio::time::sleep(Duration::from_secs(1)).await;
-    }
+        None => Checkpoint::shuffled(),
+    };
+
+    // Print progress as it's reported
+    let (events, received) = std::sync::mpsc::channel();
+    let printer = std::thread::spawn(move || received.iter().for_each(|event| print_event(&event)));
+    let result = run_fetch(&client, &db, &blocklist, UNITS_PER_TOPIC, &mut checkpoint, &events).await;
+    drop(events);
+    printer.join().ok();
+    result?;
     
     println!("\n=== Summary ===");
-    println!("Total content units fetched: {}", total_fetched);
+    for queue in &checkpoint.topics {
+        println!(
+            "{:<22} {:>3}/{} ({:>3}%)",
+            queue.topic.to_string(),
+            queue.stats.units,
+            UNITS_PER_TOPIC,
+            queue.percent_of(UNITS_PER_TOPIC)
+        );
+    }
+    let totals = checkpoint.totals();
+    println!();
+    println!("Total content units fetched: {}", totals.units);
+    println!("Titles filtered by blocklist: {}", totals.blocked_titles);
+
+    println!("\n=== Failures ({} requests) ===", totals.requests);
+    println!("{:<28} {:>6}", "Network (DNS, timeouts)", totals.failures.network);
+    println!("{:<28} {:>6}", "HTTP error status", totals.failures.http_status);
+    println!("{:<28} {:>6}", "Unexpected response", totals.failures.bad_response);
+    println!("{:<28} {:>6}", "Rejected by quality checks", totals.failures.rejected);
+    println!("{:<28} {:>6}", "Duplicate units", totals.duplicates);
+    println!("{:<28} {:>6}", "Other", totals.failures.other);
+    println!();
     
     let final_count = db.get_content_count()?;
     println!("Total content units in database: {}", final_count);
@@ -417,8 +415,112 @@ async fn main() -> Result<()> {
         println!("⚠ Some topics may have limited content");
     }
     
-    println!("\nData fetching complete! You can now run:");
-    println!("cargo run --bin tellme");
-    
-    Ok(())
+    let code = exit_code(&totals);
+    if let Some(path) = report_json {
+        let report = FetchReport {
+            units_per_topic: UNITS_PER_TOPIC,
+            topics: checkpoint
+                .topics
+                .iter()
+                .map(|queue| TopicReport {
+                    topic: queue.topic,
+                    stats: &queue.stats,
+                })
+                .collect(),
+            totals,
+            exit_code: code,
+        };
+        std::fs::write(path, serde_json::to_string_pretty(&report)?)?;
+        println!("Report written to {}", path);
+    }
+
+    match code {
+        EXIT_MOSTLY_OFFLINE => eprintln!("\nMost requests failed with network errors; check your connection."),
+        EXIT_NOTHING_STORED => eprintln!("\nNo new content was stored."),
+        _ => {
+            println!("\nData fetching complete! You can now run:");
+            println!("cargo run --bin tellme");
+        }
+    }
+    std::process::exit(code);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plan_covers_every_topic_with_its_queries_and_existing_units() {
+        let existing = HashMap::from([(Topic::AncientRome, 12)]);
+        let plan = build_plan(&existing, 5);
+
+        assert_eq!(plan.topics.len(), Topic::all().len());
+        for t in &plan.topics {
+            assert_eq!(t.queries.len(), t.topic.search_queries().len());
+            assert_eq!(t.search_requests, t.queries.len());
+            assert_eq!(t.units_to_fetch, 5);
+            assert_eq!(t.min_article_requests, 5.min(t.max_article_requests));
+            assert_eq!(t.max_article_requests, t.queries.len() * SEARCH_LIMIT);
+            let expected = if t.topic == Topic::AncientRome { 12 } else { 0 };
+            assert_eq!(t.existing_units, expected);
+        }
+    }
+
+    #[test]
+    fn totals_add_up_searches_and_articles() {
+        let plan = build_plan(&HashMap::new(), 5);
+        let searches: usize = plan.topics.iter().map(|t| t.search_requests).sum();
+        let most: usize = plan.topics.iter().map(|t| t.max_article_requests).sum();
+
+        assert_eq!(plan.total_requests(), (searches + 5 * plan.topics.len(), searches + most));
+    }
+
+    #[test]
+    fn estimates_respect_the_request_interval_and_concurrency() {
+        let plan = FetchPlan {
+            units_per_topic: 2,
+            topics: vec![TopicPlan {
+                topic: Topic::AncientEgypt,
+                queries: vec!["pharaohs".to_string()],
+                existing_units: 0,
+                units_to_fetch: 2,
+                search_requests: 1,
+                min_article_requests: 2,
+                max_article_requests: 4,
+            }],
+        };
+
+        // Throttled: every request waits out the interval
+        let second = Duration::from_secs(1);
+        assert_eq!(plan.estimated_duration(second, 4), (second * 3, second * 5));
+
+        // Unthrottled: searches take a round trip, articles share it four ways
+        let (min, max) = plan.estimated_duration(Duration::ZERO, 4);
+        assert_eq!(min, ESTIMATED_REQUEST_TIME + ESTIMATED_REQUEST_TIME / 2);
+        assert_eq!(max, ESTIMATED_REQUEST_TIME * 2);
+    }
+
+    #[test]
+    fn exit_codes_tell_offline_runs_from_empty_ones() {
+        let run = |units: usize, requests: usize, network: usize, http_status: usize| TopicFetchStats {
+            units,
+            requests,
+            failures: tellme::fetch::FailureCounts {
+                network,
+                http_status,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        assert_eq!(exit_code(&run(12, 40, 5, 5)), 0);
+        // Exactly half is not "more than half"
+        assert_eq!(exit_code(&run(3, 40, 20, 0)), 0);
+        assert_eq!(exit_code(&run(3, 40, 21, 0)), EXIT_MOSTLY_OFFLINE);
+        // Offline takes precedence over an empty run
+        assert_eq!(exit_code(&run(0, 40, 40, 0)), EXIT_MOSTLY_OFFLINE);
+        // Other failures don't count as being offline
+        assert_eq!(exit_code(&run(0, 40, 0, 40)), EXIT_NOTHING_STORED);
+        assert_eq!(exit_code(&run(0, 0, 0, 0)), EXIT_NOTHING_STORED);
+    }
 }
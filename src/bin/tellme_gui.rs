@@ -2,13 +2,37 @@
 // Simple black background, white text, basic buttons
 
 use eframe::egui;
-use tellme::{database::Database, ContentUnit, UserInteraction, DB_FILE};
+use std::{
+    path::{Path, PathBuf},
+    sync::mpsc,
+    time::{Duration, Instant},
+};
+use tellme::{
+    database::Database, export, pending::PendingQueue, Config, ContentUnit, UserInteraction,
+    DB_FILE,
+};
+
+/// How long a toast message stays visible
+const TOAST_DURATION: Duration = Duration::from_secs(4);
 
 fn main() -> Result<(), eframe::Error> {
     // Initialize database
     tellme::ensure_data_dir().expect("Failed to create data directory");
-    let db = Database::new(DB_FILE).expect("Failed to open database");
+    let config = Config::load().expect("Failed to load configuration");
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let profile = tellme::requested_profile(&mut args).expect("Invalid arguments");
+    let mut db = Database::new_with_privacy(DB_FILE, config.privacy.mode)
+        .expect("Failed to open database")
+        .with_recommendation(config.recommendation)
+        .with_sensitivity(config.sensitivity);
+    if let Some(name) = profile {
+        db = db.with_profile(&name).expect("Failed to select profile");
+    }
     
+    if let Err(e) = PendingQueue::default().drain_into(&db) {
+        eprintln!("Warning: Failed to save queued interactions: {}", e);
+    }
+
     let content_count = db.get_content_count().unwrap_or(0);
     if content_count == 0 {
         eprintln!("No content in database. Run: cargo run --bin fetch_data");
@@ -28,10 +52,68 @@ fn main() -> Result<(), eframe::Error> {
     )
 }
 
+/// Data sets offered in the Export menu
+#[derive(Debug, Clone, Copy)]
+enum ExportKind {
+    InteractionsCsv,
+    BookmarksMarkdown,
+    MonthlyReport,
+}
+
+impl ExportKind {
+    fn label(&self) -> &'static str {
+        match self {
+            ExportKind::InteractionsCsv => "Reading history (CSV)",
+            ExportKind::BookmarksMarkdown => "Bookmarks (Markdown)",
+            ExportKind::MonthlyReport => "Monthly report (Markdown)",
+        }
+    }
+
+    fn default_file_name(&self) -> &'static str {
+        match self {
+            ExportKind::InteractionsCsv => "tellme-history.csv",
+            ExportKind::BookmarksMarkdown => "tellme-bookmarks.md",
+            ExportKind::MonthlyReport => "tellme-monthly-report.md",
+        }
+    }
+
+    fn extension(&self) -> &'static str {
+        match self {
+            ExportKind::InteractionsCsv => "csv",
+            ExportKind::BookmarksMarkdown | ExportKind::MonthlyReport => "md",
+        }
+    }
+
+    /// Write the export to `path` using its own database connection
+    /// Runs on a worker thread so the UI stays responsive
+    fn run(self, path: &Path, profile: &str) -> anyhow::Result<String> {
+        let db = Database::new(DB_FILE)?.with_profile(profile)?;
+        let file = std::io::BufWriter::new(std::fs::File::create(path)?);
+        let summary = match self {
+            ExportKind::InteractionsCsv => {
+                format!("{} interactions", export::export_interactions_csv(&db, file)?)
+            }
+            ExportKind::BookmarksMarkdown => {
+                format!("{} bookmarks", export::export_bookmarks_markdown(&db, file)?)
+            }
+            ExportKind::MonthlyReport => {
+                format!("{} months", export::export_monthly_report_markdown(&db, file)?)
+            }
+        };
+        Ok(format!("Exported {} to {}", summary, path.display()))
+    }
+}
+
 struct TellMeApp {
     db: Database,
     current_content: Option<ContentUnit>,
     start_time: std::time::Instant,
+    /// Result channel of the export currently running in the background
+    export_rx: Option<mpsc::Receiver<anyhow::Result<String>>>,
+    /// Short-lived feedback message and when it was shown
+    toast: Option<(String, Instant)>,
+    /// Error shown in a modal window until dismissed
+    error_modal: Option<String>,
 }
 
 impl TellMeApp {
@@ -40,6 +122,9 @@ impl TellMeApp {
             db,
             current_content: None,
             start_time: std::time::Instant::now(),
+            export_rx: None,
+            toast: None,
+            error_modal: None,
         };
         app.load_next_content();
         app
@@ -50,16 +135,81 @@ impl TellMeApp {
         if let Some(ref content) = self.current_content {
             let reading_time = self.start_time.elapsed().as_secs() as u32;
             let interaction = UserInteraction::fully_read(content.id, reading_time);
-            let _ = self.db.record_interaction(&interaction);
+            if let Err(e) = PendingQueue::default().record_or_queue(&self.db, &interaction) {
+                self.error_modal = Some(format!("Failed to record interaction: {}", e));
+            }
         }
 
         // Load new content
-        match self.db.get_weighted_random_content() {
-            Ok(Some(content)) => {
+        if let Ok(Some(content)) = self.db.get_weighted_random_content() {
+            let _ = self.db.mark_shown(content.id);
+            self.current_content = Some(content);
+            self.start_time = std::time::Instant::now();
+        }
+    }
+
+    /// Never show the current content again, and move on without recording a read
+    fn hide_current(&mut self) {
+        let Some(content) = self.current_content.take() else {
+            return;
+        };
+        match self.db.hide_content(content.id) {
+            Ok(_) => {
+                self.toast = Some(("Hidden — it won't be shown again".to_string(), Instant::now()));
+                self.load_next_content();
+            }
+            Err(e) => {
+                self.error_modal = Some(format!("Failed to hide content: {}", e));
                 self.current_content = Some(content);
-                self.start_time = std::time::Instant::now();
             }
-            _ => {}
+        }
+    }
+
+    /// Ask for a destination file and run the export on a worker thread
+    fn start_export(&mut self, kind: ExportKind) {
+        if self.export_rx.is_some() {
+            return; // One export at a time
+        }
+
+        let Some(path): Option<PathBuf> = rfd::FileDialog::new()
+            .set_file_name(kind.default_file_name())
+            .add_filter(kind.label(), &[kind.extension()])
+            .save_file()
+        else {
+            return; // Dialog cancelled
+        };
+
+        let (tx, rx) = mpsc::channel();
+        let profile = self.db.profile().name.clone();
+        std::thread::spawn(move || {
+            let _ = tx.send(kind.run(&path, &profile));
+        });
+        self.export_rx = Some(rx);
+    }
+
+    /// Check whether the background export has finished
+    fn poll_export(&mut self, ctx: &egui::Context) {
+        let Some(rx) = &self.export_rx else {
+            return;
+        };
+
+        match rx.try_recv() {
+            Ok(Ok(message)) => {
+                self.toast = Some((message, Instant::now()));
+                self.export_rx = None;
+            }
+            Ok(Err(e)) => {
+                self.error_modal = Some(format!("Export failed: {}", e));
+                self.export_rx = None;
+            }
+            Err(mpsc::TryRecvError::Empty) => {
+                // Keep repainting so we notice when the worker finishes
+                ctx.request_repaint_after(Duration::from_millis(100));
+            }
+            Err(mpsc::TryRecvError::Disconnected) => {
+                self.error_modal = Some("Export stopped unexpectedly".to_string());
+                self.export_rx = None;
+            }
         }
     }
 }
@@ -81,6 +231,55 @@ impl eframe::App for TellMeApp {
             ctx.send_viewport_cmd(egui::ViewportCommand::Close);
         }
 
+        self.poll_export(ctx);
+
+        // Menu bar
+        egui::TopBottomPanel::top("menu_bar")
+            .frame(egui::Frame::none().fill(egui::Color32::BLACK).inner_margin(6.0))
+            .show(ctx, |ui| {
+                egui::menu::bar(ui, |ui| {
+                    ui.add_enabled_ui(self.export_rx.is_none(), |ui| {
+                        ui.menu_button("Export…", |ui| {
+                            let kinds =
+                                [ExportKind::InteractionsCsv, ExportKind::BookmarksMarkdown, ExportKind::MonthlyReport];
+                            for kind in kinds {
+                                if ui.button(format!("{}…", kind.label())).clicked() {
+                                    ui.close_menu();
+                                    self.start_export(kind);
+                                }
+                            }
+                        });
+                    });
+
+                    if self.export_rx.is_some() {
+                        ui.spinner();
+                        ui.label(egui::RichText::new("Exporting…").color(egui::Color32::LIGHT_GRAY));
+                    } else if let Some((message, shown_at)) = &self.toast {
+                        if shown_at.elapsed() < TOAST_DURATION {
+                            ui.label(egui::RichText::new(message).color(egui::Color32::LIGHT_GREEN));
+                            ctx.request_repaint_after(Duration::from_millis(250));
+                        } else {
+                            self.toast = None;
+                        }
+                    }
+                });
+            });
+
+        // Error modal
+        if let Some(message) = self.error_modal.clone() {
+            egui::Window::new("Error")
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+                .show(ctx, |ui| {
+                    ui.label(message);
+                    ui.add_space(10.0);
+                    if ui.button("OK").clicked() {
+                        self.error_modal = None;
+                    }
+                });
+        }
+
         egui::CentralPanel::default()
             .frame(egui::Frame::none().fill(egui::Color32::BLACK))
             .show(ctx, |ui| {
@@ -101,7 +300,8 @@ impl eframe::App for TellMeApp {
 
                             if let Some(ref content) = self.current_content {
                                 // Topic badge
-                                ui.label(egui::RichText::new(format!("📚 {}", content.topic)).color(egui::Color32::LIGHT_GRAY));
+                                let (r, g, b) = content.topic.color();
+                                ui.label(egui::RichText::new(format!("{} {}", content.topic.icon(), content.topic)).color(egui::Color32::from_rgb(r, g, b)));
                                 
                                 ui.add_space(10.0);
                                 
@@ -110,8 +310,8 @@ impl eframe::App for TellMeApp {
                                 
                                 ui.add_space(15.0);
                                 
-                                // Content text
-                                ui.label(egui::RichText::new(&content.content).color(egui::Color32::WHITE).size(14.0));
+                                // Content text, one label per paragraph
+                                render_paragraphs(ui, &content.content);
                                 
                                 ui.add_space(40.0);
                             } else {
@@ -136,9 +336,43 @@ impl eframe::App for TellMeApp {
                         }
                         
                         ui.add_space(10.0);
+
+                        let can_hide = self.current_content.is_some();
+                        if ui.add_enabled(can_hide, egui::Button::new(egui::RichText::new("Hide").size(16.0))).clicked() {
+                            self.hide_current();
+                        }
+
+                        ui.add_space(10.0);
                     });
                     ui.add_space(10.0);
                 });
             });
     }
 }
+
+/// Render content as separate paragraphs, with the opening paragraph set apart as a lead
+/// Wikipedia-style section lines (`== Early life ==`) become small headings
+fn render_paragraphs(ui: &mut egui::Ui, text: &str) {
+    let paragraphs = text.split("\n\n").map(str::trim).filter(|p| !p.is_empty());
+    for (i, paragraph) in paragraphs.enumerate() {
+        if i > 0 {
+            ui.add_space(12.0);
+        }
+
+        let text = if let Some(heading) = section_heading(paragraph) {
+            egui::RichText::new(heading).color(egui::Color32::LIGHT_GRAY).size(16.0).strong()
+        } else if i == 0 {
+            egui::RichText::new(paragraph).color(egui::Color32::WHITE).size(15.5)
+        } else {
+            egui::RichText::new(paragraph).color(egui::Color32::from_gray(220)).size(14.0)
+        };
+        ui.label(text);
+    }
+}
+
+/// The heading text of a `== Heading ==` line, if that's what the paragraph is
+fn section_heading(paragraph: &str) -> Option<&str> {
+    let inner = paragraph.strip_prefix("==")?.strip_suffix("==")?;
+    let heading = inner.trim_matches('=').trim();
+    (!heading.is_empty() && !paragraph.contains('\n')).then_some(heading)
+}
@@ -0,0 +1,737 @@
+// config.rs - User configuration loaded from tellme_data/tellme.toml
+// Every field has a sensible default, so a missing file (or a file that
+// only sets a few keys) behaves exactly like the built-in configuration
+
+use crate::{Result, Topic, CONFIG_FILE};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, path::Path};
+
+/// Top-level configuration, one section per part of the application
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub fetch: FetchConfig,
+    pub recommendation: RecommendationConfig,
+    pub storage: StorageConfig,
+    pub privacy: PrivacyConfig,
+    pub ui: UiConfig,
+    pub moderation: ModerationConfig,
+    pub sensitivity: SensitivityConfig,
+}
+
+impl Config {
+    /// Load the configuration from the default location
+    pub fn load() -> Result<Self> {
+        Self::load_from(CONFIG_FILE)
+    }
+
+    /// Load the configuration from a specific file, falling back to defaults if it doesn't exist
+    pub fn load_from(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let text = std::fs::read_to_string(path)?;
+        let mut config: Self = toml::from_str(&text)
+            .map_err(|e| anyhow::anyhow!("Invalid config file {}: {}", path.display(), e))?;
+        config.recommendation.clamp_topic_filter_share();
+        Ok(config)
+    }
+
+    /// Save a single setting to the default config file
+    pub fn persist_value(section: &str, key: &str, value: impl Into<toml_edit::Value>) -> Result<()> {
+        Self::persist_value_to(CONFIG_FILE, section, key, value)
+    }
+
+    /// Save a single setting to a config file, creating the file if needed
+    /// Everything else in the file, including comments, is left as the user wrote it
+    pub fn persist_value_to(
+        path: impl AsRef<Path>,
+        section: &str,
+        key: &str,
+        value: impl Into<toml_edit::Value>,
+    ) -> Result<()> {
+        let path = path.as_ref();
+        let text = if path.exists() {
+            std::fs::read_to_string(path)?
+        } else {
+            String::new()
+        };
+
+        let mut doc: toml_edit::DocumentMut = text
+            .parse()
+            .map_err(|e| anyhow::anyhow!("Invalid config file {}: {}", path.display(), e))?;
+        let table = doc
+            .entry(section)
+            .or_insert_with(toml_edit::table)
+            .as_table_mut()
+            .ok_or_else(|| anyhow::anyhow!("[{}] in {} is not a table", section, path.display()))?;
+        table[key] = toml_edit::value(value);
+
+        std::fs::write(path, doc.to_string())?;
+        Ok(())
+    }
+}
+
+/// Settings for the Wikipedia fetcher
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FetchConfig {
+    /// Titles matching any of these patterns are skipped before their content is fetched.
+    /// Plain patterns match as case-insensitive substrings; patterns prefixed with
+    /// `regex:` are treated as regular expressions. Replacing this list drops the defaults.
+    pub blocked_title_patterns: Vec<String>,
+    /// Additional patterns appended to `blocked_title_patterns` (handy for extending the defaults)
+    pub extra_blocked_title_patterns: Vec<String>,
+    /// URL or email address sent in the user agent so Wikimedia can reach whoever runs
+    /// the fetcher; the project page is sent when unset
+    pub contact: Option<String>,
+    /// Most requests sent to Wikipedia per second, counted across everything a fetch does
+    /// at once; fractions are allowed (0.5 is one request every two seconds)
+    pub max_requests_per_second: f64,
+    /// Fetched text with a larger share of numbers than this (0.0-1.0) is left out, since
+    /// statistics and tables make poor reading; 1.0 keeps everything
+    pub max_numeric_ratio: f64,
+    /// Articles fetched at once (1-16). More overlaps the wait for each response, while
+    /// `max_requests_per_second` still caps what is sent
+    pub concurrency: usize,
+    /// MediaWiki Action API endpoint that searches and articles come from; point it at a
+    /// mirror or another language's Wikipedia
+    pub api_url: String,
+    /// REST API root that term explanations (page summaries) come from, matching `api_url`
+    pub rest_url: String,
+}
+
+/// Default cap on requests per second to Wikipedia
+pub const DEFAULT_MAX_REQUESTS_PER_SECOND: f64 = 2.0;
+
+/// Default share of numbers above which fetched text is left out
+pub const DEFAULT_MAX_NUMERIC_RATIO: f64 = 0.3;
+
+/// Lowest accepted request rate, so a typo can't stall a fetch for hours per request
+pub const MIN_REQUESTS_PER_SECOND: f64 = 0.1;
+
+/// Default number of articles fetched at once
+pub const DEFAULT_FETCH_CONCURRENCY: usize = 4;
+
+/// Most articles fetched at once, however many are configured
+pub const MAX_FETCH_CONCURRENCY: usize = 16;
+
+/// English Wikipedia's Action API endpoint
+pub const DEFAULT_API_URL: &str = "https://en.wikipedia.org/w/api.php";
+
+/// English Wikipedia's REST API root
+pub const DEFAULT_REST_URL: &str = "https://en.wikipedia.org/api/rest_v1";
+
+impl Default for FetchConfig {
+    fn default() -> Self {
+        Self {
+            blocked_title_patterns: [
+                "disambiguation",
+                "List of",
+                "Index of",
+                "Outline of",
+                "Glossary of",
+                "Bibliography of",
+                "(film)",
+                "(album)",
+                "(song)",
+                "(band)",
+                "(TV series)",
+                "(video game)",
+                "(novel)",
+                r"regex:^\d{4} in ",
+            ]
+            .iter()
+            .map(|s| s.to_string())
+            .collect(),
+            extra_blocked_title_patterns: Vec::new(),
+            contact: None,
+            max_requests_per_second: DEFAULT_MAX_REQUESTS_PER_SECOND,
+            max_numeric_ratio: DEFAULT_MAX_NUMERIC_RATIO,
+            concurrency: DEFAULT_FETCH_CONCURRENCY,
+            api_url: DEFAULT_API_URL.to_string(),
+            rest_url: DEFAULT_REST_URL.to_string(),
+        }
+    }
+}
+
+impl FetchConfig {
+    /// Shortest time between two requests under `max_requests_per_second`
+    pub fn request_interval(&self) -> std::time::Duration {
+        let rate = if self.max_requests_per_second.is_finite() {
+            self.max_requests_per_second.max(MIN_REQUESTS_PER_SECOND)
+        } else {
+            DEFAULT_MAX_REQUESTS_PER_SECOND
+        };
+        std::time::Duration::from_secs_f64(1.0 / rate)
+    }
+
+    /// Articles to fetch at once, within 1 and `MAX_FETCH_CONCURRENCY`
+    pub fn article_concurrency(&self) -> usize {
+        self.concurrency.clamp(1, MAX_FETCH_CONCURRENCY)
+    }
+
+    /// All blocklist patterns: the base list followed by the user's extra patterns
+    pub fn title_blocklist(&self) -> impl Iterator<Item = &str> {
+        self.blocked_title_patterns
+            .iter()
+            .chain(self.extra_blocked_title_patterns.iter())
+            .map(|s| s.as_str())
+    }
+}
+
+/// Settings for the content recommender
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RecommendationConfig {
+    /// Skipped content is withheld for this many hours after the skip, then becomes
+    /// eligible again. Set to 0 to let skipped content come back immediately.
+    pub skip_cooldown_hours: u64,
+    /// How strongly content with a strong opening sentence is favoured when picking
+    /// within a topic. Each hook point outweighs this many points of a 0-99 random
+    /// roll; 0 disables the nudge.
+    pub hook_weight: u32,
+    /// Starting weight (0.0 to 1.0) for topics you haven't read or skipped yet.
+    /// Topics with history use their share of full reads instead.
+    pub default_preference: f64,
+    /// Weight added to topics with fewer than `exploration_threshold` interactions.
+    /// Raise it to wander into unexplored eras more often.
+    pub exploration_bonus: f64,
+    /// Topics with fewer interactions than this count as under-explored
+    pub exploration_threshold: u32,
+    /// Lowest weight any topic can end up with, so even disliked or just-shown
+    /// topics keep a small chance of coming up
+    pub min_topic_weight: f64,
+    /// Chance (0.0 to 1.0) that the next story is one of your pinned favourites instead
+    /// of the usual pick; the pinned story shown longest ago comes up first
+    pub pinned_probability: f64,
+    /// Chance (0.0 to 1.0) that the next story is one you skipped most of the way through
+    /// in the last week, picked up where you stopped
+    pub resume_probability: f64,
+    /// Trade-off between sticking to your favourite topics and wandering (0.0 to 1.0).
+    /// At 0.0 the best-liked topic always wins; at 1.0 preferences are ignored and
+    /// recency penalties and exploration bonuses decide. 0.5 is the usual mix.
+    pub explore_exploit_balance: f64,
+    /// Keep pinned stories out of regular picks, so they only come back through
+    /// `pinned_probability`
+    pub exclude_pinned_from_stream: bool,
+    /// Leave out stories you fully read within this many days, 30 by default (0 = off)
+    pub exclude_read_within_days: u32,
+    /// Give every topic about the same airtime however much content it has. Topics are
+    /// normally weighed against each other, but before there's any reading history, and
+    /// when the chosen topic has nothing available, any story is picked at random, so
+    /// the biggest topics dominate. With this on, those picks go topic by topic too
+    pub even_topic_airtime: bool,
+    /// How the topic of the next story is chosen (`diversity`, `random` or `round_robin`)
+    pub strategy: StrategyKind,
+    /// Chance (0.0 to 1.0) that the `diversity` strategy picks any topic with something to
+    /// show, all equally likely, instead of weighing them, so even a strong favourite
+    /// never has the stream to itself
+    pub exploration_rate: f64,
+    /// Share of stories (0.0 to 1.0) taken from the topic locked with T or a number key.
+    /// The rest are picked as if no topic were locked, and can still land on it. 1.0
+    /// serves that topic only; below it the filter focuses on the topic without shutting
+    /// the others out, and falls back to them once the topic has nothing left
+    pub topic_filter_share: f64,
+}
+
+/// Built-in topic selection strategies (see `selection::SelectionStrategy`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StrategyKind {
+    /// Weigh topics by preference, recency and exploration, as described above
+    #[default]
+    Diversity,
+    /// Every topic with something to show is equally likely
+    Random,
+    /// Topics in turn, in their usual order
+    RoundRobin,
+}
+
+impl StrategyKind {
+    /// Name as written in the configuration
+    pub fn as_str(self) -> &'static str {
+        match self {
+            StrategyKind::Diversity => "diversity",
+            StrategyKind::Random => "random",
+            StrategyKind::RoundRobin => "round_robin",
+        }
+    }
+}
+
+/// Balance at which preferences, recency penalties and exploration bonuses apply as designed
+pub const DEFAULT_EXPLORE_EXPLOIT_BALANCE: f64 = 0.5;
+
+impl Default for RecommendationConfig {
+    fn default() -> Self {
+        Self {
+            skip_cooldown_hours: 72,
+            hook_weight: 5,
+            default_preference: 0.3,
+            exploration_bonus: 0.2,
+            exploration_threshold: 3,
+            min_topic_weight: 0.05,
+            pinned_probability: 0.1,
+            resume_probability: 0.1,
+            explore_exploit_balance: DEFAULT_EXPLORE_EXPLOIT_BALANCE,
+            exclude_pinned_from_stream: false,
+            exclude_read_within_days: 30,
+            even_topic_airtime: false,
+            strategy: StrategyKind::Diversity,
+            exploration_rate: 0.15,
+            topic_filter_share: 1.0,
+        }
+    }
+}
+
+impl RecommendationConfig {
+    /// The skip cooldown as a chrono duration
+    pub fn skip_cooldown(&self) -> chrono::Duration {
+        chrono::Duration::hours(self.skip_cooldown_hours as i64)
+    }
+
+    /// Bring `topic_filter_share` within 0.0 to 1.0; a value that isn't a number becomes
+    /// 0.0, which leaves locked topics without any focus
+    pub fn clamp_topic_filter_share(&mut self) {
+        self.topic_filter_share = if self.topic_filter_share.is_nan() {
+            0.0
+        } else {
+            self.topic_filter_share.clamp(0.0, 1.0)
+        };
+    }
+}
+
+/// What happens when adding content would go over `max_content_units`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LimitPolicy {
+    /// Permanently remove the lowest-value unread content to make room, and reject the
+    /// new content if there is none
+    #[default]
+    Evict,
+    /// Reject the new content
+    Refuse,
+}
+
+/// Limits on how much content is kept, for small devices
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StorageConfig {
+    /// Most content units to store (soft-deleted ones included); unset means no limit
+    pub max_content_units: Option<i64>,
+    /// What to do when the limit is reached (`evict` or `refuse`)
+    pub limit_policy: LimitPolicy,
+    /// Pruning of old, low-quality stories nobody has read
+    pub retention: RetentionPolicy,
+}
+
+/// Which stories `tellme maintain` prunes as not worth keeping
+/// A story is pruned only when it matches every rule: stored at least `min_age_days`
+/// ago, scored below `quality_below`, shown fewer than `shown_fewer_than` times, never
+/// fully read, and not pinned. Fun facts and stories without a stored
+/// quality score (run `maintain --backfill` first) are left alone.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetentionPolicy {
+    /// Whether plain `tellme maintain` applies the policy
+    pub enabled: bool,
+    pub min_age_days: u32,
+    pub quality_below: i32,
+    pub shown_fewer_than: u32,
+    /// Never prune a topic below this many stories, however poor they are
+    pub keep_per_topic: usize,
+    /// Soft-delete pruned stories, so they can be restored until the next purge
+    pub soft_delete: bool,
+}
+
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_age_days: 180,
+            quality_below: 2,
+            shown_fewer_than: 1,
+            keep_per_topic: 50,
+            soft_delete: true,
+        }
+    }
+}
+
+/// How much reading history is kept
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PrivacyMode {
+    /// Every interaction is logged with its content and time
+    #[default]
+    Full,
+    /// Only per-topic counts of reads, skips and dismissals are kept
+    Aggregate,
+}
+
+/// Settings for privacy-conscious reading
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PrivacyConfig {
+    /// `full` logs which story was read or skipped and when. `aggregate` only counts
+    /// reads, skips and dismissals per topic: topic weighting works the same, but skips
+    /// and dismissals only hide stories until the reader closes, stories you've seen can
+    /// come back in later sessions, and history, trends and the activity calendar stay
+    /// empty for anything read in this mode
+    pub mode: PrivacyMode,
+}
+
+/// A blocklist published centrally, e.g. by a teacher for every reader in a classroom
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ModerationConfig {
+    /// Newline-delimited list of title patterns (as in `blocked_title_patterns`) and content
+    /// hashes to keep out of reading; unset means no remote list
+    pub blocklist_url: Option<String>,
+    /// How often the reader checks the list for changes while it runs
+    pub refresh_hours: u32,
+}
+
+impl Default for ModerationConfig {
+    fn default() -> Self {
+        Self {
+            blocklist_url: None,
+            refresh_hours: 24,
+        }
+    }
+}
+
+impl ModerationConfig {
+    /// The refresh interval as a chrono duration
+    pub fn refresh_interval(&self) -> chrono::Duration {
+        chrono::Duration::hours(self.refresh_hours.max(1) as i64)
+    }
+}
+
+/// Scoring of graphic or distressing stories, one story at a time
+/// Every story is scored when it's stored; `tellme maintain --backfill` scores older ones
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SensitivityConfig {
+    /// Words and phrases, matched as whole words in any case, with what each occurrence
+    /// adds to a story's score (a term counts at most three times)
+    pub terms: BTreeMap<String, u32>,
+    /// Stories scoring at least this are sensitive: marked in the reader, and kept out of
+    /// reading in safe mode
+    pub threshold: u32,
+    /// Leave sensitive stories out of reading, whatever their topic
+    pub safe_mode: bool,
+}
+
+impl Default for SensitivityConfig {
+    fn default() -> Self {
+        let terms = [
+            (3, &["atrocity", "atrocities", "beheaded", "buried alive", "burned alive", "cannibalism",
+                  "decapitated", "disembowelled", "flayed", "genocide", "impaled", "massacre",
+                  "massacred", "mutilated", "mutilation", "rape", "raped", "torture", "tortured"][..]),
+            (2, &["corpses", "crucified", "executed", "lynched", "lynching", "mass grave",
+                  "slaughter", "slaughtered", "starved to death"][..]),
+            (1, &["blood", "bloody", "corpse", "killed", "murder", "murdered", "wounded"][..]),
+        ];
+        Self {
+            terms: terms
+                .into_iter()
+                .flat_map(|(weight, words)| words.iter().map(move |word| (word.to_string(), weight)))
+                .collect(),
+            threshold: 6,
+            safe_mode: false,
+        }
+    }
+}
+
+/// How content text is aligned within the content area
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TextAlignment {
+    #[default]
+    Left,
+    Center,
+}
+
+/// Typewriter reveal speed presets, cycled with a single key in the reader
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RevealSpeed {
+    Slow,
+    #[default]
+    Normal,
+    Fast,
+    Instant,
+    /// Nothing is revealed by itself; each key press shows the next sentence
+    Manual,
+}
+
+impl RevealSpeed {
+    /// The preset after this one, wrapping around
+    pub fn next(self) -> Self {
+        match self {
+            RevealSpeed::Slow => RevealSpeed::Normal,
+            RevealSpeed::Normal => RevealSpeed::Fast,
+            RevealSpeed::Fast => RevealSpeed::Instant,
+            RevealSpeed::Instant => RevealSpeed::Manual,
+            RevealSpeed::Manual => RevealSpeed::Slow,
+        }
+    }
+
+    /// One step faster along slow, normal, fast and instant; manual stays manual
+    pub fn faster(self) -> Self {
+        match self {
+            RevealSpeed::Slow => RevealSpeed::Normal,
+            RevealSpeed::Normal => RevealSpeed::Fast,
+            RevealSpeed::Fast | RevealSpeed::Instant => RevealSpeed::Instant,
+            RevealSpeed::Manual => RevealSpeed::Manual,
+        }
+    }
+
+    /// One step slower along instant, fast, normal and slow; manual stays manual
+    pub fn slower(self) -> Self {
+        match self {
+            RevealSpeed::Instant => RevealSpeed::Fast,
+            RevealSpeed::Fast => RevealSpeed::Normal,
+            RevealSpeed::Normal | RevealSpeed::Slow => RevealSpeed::Slow,
+            RevealSpeed::Manual => RevealSpeed::Manual,
+        }
+    }
+
+    /// Characters revealed per second, or None to show content all at once
+    /// Manual reveal has no rate; it's handled before this is asked
+    pub fn chars_per_second(self) -> Option<u32> {
+        match self {
+            RevealSpeed::Slow => Some(20),
+            RevealSpeed::Normal => Some(40),
+            RevealSpeed::Fast => Some(100),
+            RevealSpeed::Instant | RevealSpeed::Manual => None,
+        }
+    }
+
+    /// Name used in the config file
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RevealSpeed::Slow => "slow",
+            RevealSpeed::Normal => "normal",
+            RevealSpeed::Fast => "fast",
+            RevealSpeed::Instant => "instant",
+            RevealSpeed::Manual => "manual",
+        }
+    }
+}
+
+impl std::fmt::Display for RevealSpeed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            RevealSpeed::Slow => "Slow",
+            RevealSpeed::Normal => "Normal",
+            RevealSpeed::Fast => "Fast",
+            RevealSpeed::Instant => "Instant",
+            RevealSpeed::Manual => "Manual (one sentence per key)",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Reader actions that can be bound to a key as part of a macro (see `UiConfig::keys`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReaderAction {
+    /// Move on to the next story (or forward again after going back)
+    Next,
+    /// Go back to the previous story
+    Back,
+    /// Show the rest of the story without waiting for the typewriter
+    Reveal,
+    /// Pin or unpin the story (favourites, once kept apart, are pins now)
+    #[serde(alias = "favourite", alias = "favorite", alias = "bookmark")]
+    Pin,
+    Delete,
+    /// "Read elsewhere": skip the story and hide similar ones for the session
+    Dismiss,
+    /// Never show the story again
+    Hide,
+    Undo,
+    Faster,
+    Slower,
+    Typewriter,
+    QuickBites,
+    GuidedOrder,
+    Focus,
+}
+
+impl ReaderAction {
+    /// Name used in the config file
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ReaderAction::Next => "next",
+            ReaderAction::Back => "back",
+            ReaderAction::Reveal => "reveal",
+            ReaderAction::Pin => "pin",
+            ReaderAction::Delete => "delete",
+            ReaderAction::Dismiss => "dismiss",
+            ReaderAction::Hide => "hide",
+            ReaderAction::Undo => "undo",
+            ReaderAction::Faster => "faster",
+            ReaderAction::Slower => "slower",
+            ReaderAction::Typewriter => "typewriter",
+            ReaderAction::QuickBites => "quick_bites",
+            ReaderAction::GuidedOrder => "guided_order",
+            ReaderAction::Focus => "focus",
+        }
+    }
+}
+
+/// A key that can carry a macro: `F1` to `F12` or a single character (case matters)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum MacroKey {
+    Function(u8),
+    Char(char),
+}
+
+impl TryFrom<String> for MacroKey {
+    type Error = String;
+
+    fn try_from(name: String) -> std::result::Result<Self, Self::Error> {
+        let mut chars = name.chars();
+        if let (Some(c), None) = (chars.next(), chars.next()) {
+            return Ok(MacroKey::Char(c));
+        }
+        match name.strip_prefix('F').and_then(|n| n.parse::<u8>().ok()) {
+            Some(n @ 1..=12) => Ok(MacroKey::Function(n)),
+            _ => Err(format!("unknown key `{}`, expected F1 to F12 or a single character", name)),
+        }
+    }
+}
+
+impl From<MacroKey> for String {
+    fn from(key: MacroKey) -> Self {
+        match key {
+            MacroKey::Function(n) => format!("F{}", n),
+            MacroKey::Char(c) => c.to_string(),
+        }
+    }
+}
+
+/// Actions a macro key runs, in order; never empty
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "Vec<ReaderAction>", into = "Vec<ReaderAction>")]
+pub struct KeyMacro(Vec<ReaderAction>);
+
+impl KeyMacro {
+    pub fn actions(&self) -> &[ReaderAction] {
+        &self.0
+    }
+}
+
+impl TryFrom<Vec<ReaderAction>> for KeyMacro {
+    type Error = &'static str;
+
+    fn try_from(actions: Vec<ReaderAction>) -> std::result::Result<Self, Self::Error> {
+        if actions.is_empty() {
+            return Err("a key macro needs at least one action");
+        }
+        Ok(KeyMacro(actions))
+    }
+}
+
+impl From<KeyMacro> for Vec<ReaderAction> {
+    fn from(key_macro: KeyMacro) -> Self {
+        key_macro.0
+    }
+}
+
+/// Number keys 1-9 each select one topic slot; later slots are ignored
+pub const MAX_TOPIC_SLOTS: usize = 9;
+
+/// Settings for the terminal reader
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UiConfig {
+    /// Widest the content column may get, in terminal columns; wider terminals
+    /// get a centered column of this width
+    pub max_content_width: u16,
+    /// Blank lines inserted between paragraphs
+    pub paragraph_spacing: usize,
+    /// Alignment of the content text (`left` or `center`)
+    pub text_alignment: TextAlignment,
+    /// Typewriter reveal speed (`slow`, `normal`, `fast` or `instant`)
+    pub reveal_speed: RevealSpeed,
+    /// Topics reached with the number keys, in order: the first is on 1, the ninth on 9
+    pub topic_slots: Vec<Topic>,
+    /// Frames drawn per second by the reader. Input is handled as soon as it arrives either
+    /// way; lower rates wake the CPU less often (easier on a battery), higher ones make the
+    /// typewriter and status updates smoother
+    pub frame_rate: u32,
+    /// Length of a focus session in minutes; press O in the reader to start or stop one
+    pub focus_minutes: u64,
+    /// Length of the break that follows each focus session, in minutes
+    pub break_minutes: u64,
+    /// Start a focus session as soon as the reader opens
+    pub focus_on_start: bool,
+    /// While a topic is locked, read it in order instead of at random: the parts of an
+    /// article one after another, older articles first and better pieces first within them.
+    /// Press L in the reader to switch
+    pub guided_topic_order: bool,
+    /// When a story's title repeats one shown recently (as the parts of a split article
+    /// do), head it with its first sentence instead
+    pub title_from_first_line: bool,
+    /// Macro keys: each runs its actions in order, stopping at the first that can't run,
+    /// e.g. `F5 = ["pin", "next"]`. A key the reader already uses runs the macro instead
+    pub keys: BTreeMap<MacroKey, KeyMacro>,
+}
+
+impl Default for UiConfig {
+    fn default() -> Self {
+        Self {
+            max_content_width: 90,
+            paragraph_spacing: 1,
+            text_alignment: TextAlignment::Left,
+            reveal_speed: RevealSpeed::Normal,
+            topic_slots: Topic::all().iter().copied().take(MAX_TOPIC_SLOTS).collect(),
+            frame_rate: 30,
+            focus_minutes: 25,
+            break_minutes: 5,
+            focus_on_start: false,
+            guided_topic_order: false,
+            title_from_first_line: false,
+            keys: BTreeMap::new(),
+        }
+    }
+}
+
+impl UiConfig {
+    /// Focus session length, at least one minute
+    pub fn focus_duration(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.focus_minutes.max(1) * 60)
+    }
+
+    /// Break length, at least one minute
+    pub fn break_duration(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.break_minutes.max(1) * 60)
+    }
+
+    /// Time budget for one frame of the reader loop, from `frame_rate` clamped to 1-120
+    pub fn frame_interval(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(1) / self.frame_rate.clamp(1, 120)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn topic_filter_shares_are_clamped_when_the_config_is_loaded() {
+        let path = std::env::temp_dir().join(format!("tellme-config-share-{}.toml", std::process::id()));
+        for (written, loaded) in [("0.7", 0.7), ("1.5", 1.0), ("-0.5", 0.0), ("nan", 0.0), ("inf", 1.0)] {
+            std::fs::write(&path, format!("[recommendation]\ntopic_filter_share = {}\n", written)).unwrap();
+            let config = Config::load_from(&path).unwrap();
+            assert_eq!(config.recommendation.topic_filter_share, loaded, "{written}");
+        }
+        std::fs::remove_file(&path).unwrap();
+    }
+}
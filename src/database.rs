@@ -2,366 +2,5052 @@
 // This module demonstrates Rust's error handling, SQL operations,
 // and working with external crates like rusqlite
 
-use crate::{ContentUnit, Topic, UserInteraction, Result};
+use crate::{
+    config::{
+        LimitPolicy, PrivacyMode, RecommendationConfig, RetentionPolicy, SensitivityConfig,
+        StorageConfig, StrategyKind, DEFAULT_EXPLORE_EXPLOIT_BALANCE,
+    },
+    content::{
+        calculate_content_quality_score, hook_score, keyword_similarity, keywords, preview_text,
+        ContentPreview, SensitivityScorer, TermSummary, PREVIEW_CHARS,
+    },
+    moderation::{normalize_entry, BlockSource, BlockedEntry, BlocklistSync, ContentBlocklist},
+    pending::{QueuedInteraction, QueuedRecord},
+    selection::{self, Explanation, SelectionContext, SelectionInputs, SelectionStrategy},
+    ContentKind, ContentUnit, Result, Topic, UserInteraction,
+};
 use rusqlite::{params, Connection, Row, OptionalExtension};
-use std::collections::HashMap;
+use std::{
+    cell::{Cell, OnceCell, RefCell},
+    collections::{HashMap, HashSet},
+};
+
+/// SQL predicate that hides soft-deleted, hidden and blocked rows from the `content` table
+/// Every query that reads content for display or selection must include it
+const LIVE_CONTENT: &str = "deleted_at IS NULL AND blocked = 0 AND hidden = 0";
+
+/// Most hits `search_content` returns, whatever the caller asks for
+const MAX_SEARCH_RESULTS: usize = 100;
+
+/// How much more a match in the title counts than one in the text when ranking searches
+const SEARCH_TITLE_WEIGHT: f64 = 10.0;
+
+/// Columns read by `row_to_content_unit`, in order
+const CONTENT_COLUMNS: &str =
+    "id, topic, title, content, source_url, word_count, created_at, kind, parent_id, part_index";
+
+/// `ContentFilter` as an SQL predicate over `content c`, binding the topic, fewest words,
+/// most words, has-interactions flag and profile id as ?1 to ?5. Unset fields bind as
+/// NULL and match everything, so the SQL never changes shape
+const CONTENT_FILTER: &str = "(?1 IS NULL OR topic = ?1)
+    AND (?2 IS NULL OR word_count >= ?2)
+    AND (?3 IS NULL OR word_count < ?3)
+    AND (?4 IS NULL OR ?4 = EXISTS (
+        SELECT 1 FROM user_interactions ui
+        WHERE ui.content_id = c.id AND ui.profile_id = ?5
+    ))";
+
+/// Content units read per query by `iter_content`
+const ITER_CHUNK: usize = 500;
+
+/// SQL predicate that keeps fun facts out of regular reading; they are served on request
+const NOT_FUN_FACT: &str = "kind != 'funfact'";
+
+/// Skips with at least this much of the content revealed count as "almost read"
+const RESUME_MIN_PERCENT: u8 = 60;
+
+/// How long an "almost read" skip stays on offer
+const RESUME_WINDOW_DAYS: i64 = 7;
+
+/// A "read elsewhere" dismissal counts as this many skips towards topic preferences
+const DISMISS_SKIP_WEIGHT: i64 = 2;
+
+/// Keyword similarity at or above which two stories count as near-duplicates
+const NEAR_DUPLICATE_SIMILARITY: f64 = 0.25;
+
+/// Characters from the start of each story compared when looking for near-duplicates
+const NEAR_DUPLICATE_CHARS: usize = 600;
+
+/// How many of the last topics shown carry a recency penalty
+const RECENT_TOPIC_WINDOW: usize = 5;
+
+/// First SQLite release (3.35.0) that understands `INSERT ... RETURNING`
+const RETURNING_MIN_VERSION: i32 = 3_035_000;
+
+/// Rows updated per transaction when backfilling derived columns
+const BACKFILL_BATCH_SIZE: usize = 500;
+
+/// A `content` column whose value can be recomputed from data already stored
+/// Rows added before the column existed hold NULL until they are backfilled
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BackfillColumn {
+    /// First-sentence hook score (see `content::hook_score`)
+    HookScore,
+    /// Engagement quality score (see `content::calculate_content_quality_score`)
+    QualityScore,
+    /// Stable cross-database identifier (see `ContentUnit::content_hash`)
+    ContentHash,
+    /// Graphic or distressing content score (see `content::SensitivityScorer`)
+    Sensitivity,
+    /// Year the story is set in (see `ContentUnit::estimated_year`), 0 for undated stories
+    EstimatedYear,
+}
+
+impl BackfillColumn {
+    /// Every column that can be backfilled
+    pub const fn all() -> &'static [BackfillColumn] {
+        &[
+            BackfillColumn::HookScore,
+            BackfillColumn::QualityScore,
+            BackfillColumn::ContentHash,
+            BackfillColumn::Sensitivity,
+            BackfillColumn::EstimatedYear,
+        ]
+    }
+
+    /// Name of the column in the `content` table
+    pub fn column_name(self) -> &'static str {
+        match self {
+            BackfillColumn::HookScore => "hook_score",
+            BackfillColumn::QualityScore => "quality_score",
+            BackfillColumn::ContentHash => "content_hash",
+            BackfillColumn::Sensitivity => "sensitivity",
+            BackfillColumn::EstimatedYear => "estimated_year",
+        }
+    }
+
+    /// Compute the column value for a content unit
+    fn compute(self, content: &ContentUnit, scorer: &SensitivityScorer) -> rusqlite::types::Value {
+        match self {
+            BackfillColumn::HookScore => i64::from(hook_score(&content.content)).into(),
+            BackfillColumn::QualityScore => {
+                i64::from(calculate_content_quality_score(&content.content, &content.title)).into()
+            }
+            BackfillColumn::ContentHash => content.content_hash().into(),
+            BackfillColumn::Sensitivity => i64::from(sensitivity_score(scorer, content)).into(),
+            BackfillColumn::EstimatedYear => i64::from(stored_year(content)).into(),
+        }
+    }
+}
+
+/// Estimated year as stored: there is no year 0, so it marks stories without a date,
+/// keeping NULL for rows not looked at yet
+fn stored_year(content: &ContentUnit) -> i32 {
+    content.estimated_year().unwrap_or(0)
+}
+
+/// Sensitivity score of a story, from its title and text
+fn sensitivity_score(scorer: &SensitivityScorer, content: &ContentUnit) -> u32 {
+    scorer.score(&content.title) + scorer.score(&content.content)
+}
+
+/// Name of the profile every database starts with; it owns all pre-profile interactions
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Row id of the default profile
+const DEFAULT_PROFILE_ID: i64 = 1;
+
+/// How long soft-deleted content is kept before maintenance purges it for good
+pub const SOFT_DELETE_RETENTION_DAYS: i64 = 30;
+
+/// How far ahead of the clock an interaction can be stamped before it counts as recorded
+/// under a wrong clock. Such interactions can't be placed in time, so recency windows and
+/// the recent-topics list leave them out instead of treating them as the latest
+pub const CLOCK_SKEW_TOLERANCE_MINUTES: i64 = 10;
+
+/// Whether SQLite `version` (as from `rusqlite::version_number`, e.g. 3_035_000 for
+/// 3.35.0) understands `INSERT ... RETURNING`
+fn supports_returning(version: i32) -> bool {
+    version >= RETURNING_MIN_VERSION
+}
+
+/// Latest interaction timestamp that recency queries trust, as stored
+fn latest_trusted_timestamp() -> String {
+    (chrono::Utc::now() + chrono::Duration::minutes(CLOCK_SKEW_TOLERANCE_MINUTES)).to_rfc3339()
+}
+
+/// A recorded interaction joined with the content it refers to
+#[derive(Debug, Clone)]
+pub struct InteractionRecord {
+    pub content_id: i64,
+    pub topic: String,
+    pub title: String,
+    pub interaction_type: String,
+    pub timestamp: String,
+    pub duration_seconds: u32,
+}
+
+/// Reading totals for one profile, as shown by `tellme stats`
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct InteractionStats {
+    /// Reads, skips and dismissals
+    pub total: i64,
+    pub fully_read: i64,
+    pub skipped: i64,
+    /// Mean time spent on stories read to the end; unset until one has been logged.
+    /// Only the full interaction log has times, so reads counted in aggregate mode don't
+    /// contribute
+    pub avg_reading_time_seconds: Option<f64>,
+}
+
+/// Reading activity aggregated over one calendar month
+#[derive(Debug, Clone)]
+pub struct MonthlySummary {
+    /// Month in `YYYY-MM` form
+    pub month: String,
+    pub fully_read: i64,
+    /// Skips, including "read elsewhere" dismissals
+    pub skipped: i64,
+    pub reading_seconds: i64,
+}
+
+/// Which per-topic counter an interaction adds to in aggregate privacy mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TopicCounter {
+    FullyRead,
+    Skipped,
+    Dismissed,
+}
+
+impl TopicCounter {
+    /// The counter `interaction` adds to
+    pub fn of(interaction: &UserInteraction) -> Self {
+        match interaction {
+            UserInteraction::FullyRead { .. } => Self::FullyRead,
+            UserInteraction::Skipped { .. } => Self::Skipped,
+            UserInteraction::Dismissed { .. } => Self::Dismissed,
+        }
+    }
+
+    /// Column of `topic_counters` holding this count
+    fn column(self) -> &'static str {
+        match self {
+            Self::FullyRead => "fully_read",
+            Self::Skipped => "skipped",
+            Self::Dismissed => "dismissed",
+        }
+    }
+}
+
+/// A reader sharing the content library, with their own interaction history
+#[derive(Debug, Clone)]
+pub struct Profile {
+    pub id: i64,
+    pub name: String,
+    pub created_at: String,
+}
+
+/// Rough story length, for browsing by how long a story takes to read
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LengthBucket {
+    /// Under two minutes
+    Short,
+    /// Two to five minutes
+    Medium,
+    /// Five minutes or more
+    Long,
+}
+
+impl LengthBucket {
+    /// Average reading speed used for time estimates, in words per minute
+    pub const WORDS_PER_MINUTE: usize = 200;
+
+    /// Word count range covered by the bucket, as (min, max) with max exclusive
+    pub fn word_range(self) -> (Option<usize>, Option<usize>) {
+        let wpm = Self::WORDS_PER_MINUTE;
+        match self {
+            LengthBucket::Short => (None, Some(2 * wpm)),
+            LengthBucket::Medium => (Some(2 * wpm), Some(5 * wpm)),
+            LengthBucket::Long => (Some(5 * wpm), None),
+        }
+    }
+}
+
+/// Which content a page of previews, or `iter_content`, should include
+/// Every field is optional; the default matches all live content
+#[derive(Debug, Clone, Default)]
+pub struct ContentFilter {
+    pub topic: Option<Topic>,
+    /// Fewest words, inclusive
+    pub min_words: Option<usize>,
+    /// Most words, exclusive
+    pub max_words: Option<usize>,
+    /// Only content the active profile has (or hasn't) read or skipped
+    pub has_interactions: Option<bool>,
+}
+
+impl ContentFilter {
+    /// Only content from this topic, or any topic for None
+    pub fn with_topic(mut self, topic: Option<Topic>) -> Self {
+        self.topic = topic;
+        self
+    }
+
+    /// Only content whose length falls in `bucket`
+    pub fn with_length(mut self, bucket: LengthBucket) -> Self {
+        (self.min_words, self.max_words) = bucket.word_range();
+        self
+    }
+
+    /// Only content the active profile has interacted with (true) or never has (false)
+    pub fn with_interactions(mut self, has_interactions: bool) -> Self {
+        self.has_interactions = Some(has_interactions);
+        self
+    }
+}
+
+/// Content skipped most of the way through, offered again from where the reader stopped
+#[derive(Debug, Clone)]
+pub struct ResumeCandidate {
+    pub content: ContentUnit,
+    /// Characters revealed when it was skipped, where the typewriter should pick up
+    pub displayed_chars: usize,
+    /// Share of the content revealed when it was skipped, 0-100
+    pub percent_displayed: u8,
+}
+
+/// An installed content pack
+#[derive(Debug, Clone)]
+pub struct Pack {
+    pub id: i64,
+    pub name: String,
+    pub description: String,
+    pub version: String,
+    /// Path or URL the pack was installed from
+    pub source: String,
+    pub checksum: String,
+    pub installed_at: String,
+    /// Content units still linked to the pack
+    pub content_count: i64,
+}
+
+/// Error for operations that name a content id that isn't in the database
+/// It travels inside `anyhow::Error`; check for it with `err.is::<ContentNotFound>()`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContentNotFound(pub i64);
+
+impl std::fmt::Display for ContentNotFound {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "No content with id {}", self.0)
+    }
+}
+
+impl std::error::Error for ContentNotFound {}
+
+/// Error for inserting content whose hash is already stored (see `ContentUnit::content_hash`)
+/// Holds the id of the stored copy; check for it with `err.is::<DuplicateContent>()`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DuplicateContent(pub i64);
+
+impl std::fmt::Display for DuplicateContent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Already stored as content {}", self.0)
+    }
+}
+
+impl std::error::Error for DuplicateContent {}
+
+/// Time bucket size for topic trends
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TrendBucket {
+    /// ISO weeks, starting on Monday
+    Weekly,
+    /// Calendar months
+    #[default]
+    Monthly,
+}
+
+impl TrendBucket {
+    /// First day of the bucket containing `date`
+    fn start_of(self, date: chrono::NaiveDate) -> chrono::NaiveDate {
+        use chrono::Datelike;
+        match self {
+            TrendBucket::Weekly => {
+                date - chrono::Duration::days(i64::from(date.weekday().num_days_from_monday()))
+            }
+            TrendBucket::Monthly => date.with_day(1).unwrap_or(date),
+        }
+    }
+
+    /// First day of the bucket after the one starting on `start`
+    fn next(self, start: chrono::NaiveDate) -> chrono::NaiveDate {
+        match self {
+            TrendBucket::Weekly => start + chrono::Duration::days(7),
+            TrendBucket::Monthly => start + chrono::Months::new(1),
+        }
+    }
+
+    /// Label for the bucket starting on `start`, like `2026-W07` or `2026-02`
+    fn label(self, start: chrono::NaiveDate) -> String {
+        match self {
+            TrendBucket::Weekly => start.format("%G-W%V").to_string(),
+            TrendBucket::Monthly => start.format("%Y-%m").to_string(),
+        }
+    }
+}
+
+impl std::fmt::Display for TrendBucket {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TrendBucket::Weekly => write!(f, "weekly"),
+            TrendBucket::Monthly => write!(f, "monthly"),
+        }
+    }
+}
+
+/// Full reads per topic over time
+#[derive(Debug, Clone, Default)]
+pub struct TopicTrend {
+    pub bucket: TrendBucket,
+    /// Bucket labels, oldest first, with no gaps between the first and last read
+    pub labels: Vec<String>,
+    /// Read counts per bucket for every topic that has been read, in topic order
+    pub topics: Vec<(Topic, Vec<u64>)>,
+}
+
+/// Full reads per local day over one calendar year, laid out as a week-by-weekday grid
+#[derive(Debug, Clone)]
+pub struct ActivityCalendar {
+    pub year: i32,
+    /// Read counts per day, indexed by day of the year (0 is 1 January)
+    pub days: Vec<u64>,
+}
+
+impl ActivityCalendar {
+    /// Date of the day at `index`
+    pub fn date(&self, index: usize) -> Option<chrono::NaiveDate> {
+        chrono::NaiveDate::from_yo_opt(self.year, index as u32 + 1)
+    }
+
+    /// Grid position `(week column, weekday row)` of the day at `index`
+    /// Rows run Monday to Sunday; column 0 is the week containing 1 January
+    pub fn cell(&self, index: usize) -> (usize, usize) {
+        use chrono::Datelike;
+        let offset = self
+            .date(0)
+            .map_or(0, |first| first.weekday().num_days_from_monday() as usize);
+        ((index + offset) / 7, (index + offset) % 7)
+    }
+
+    /// Number of week columns the grid needs (53, or 54 for a leap year starting on a Sunday)
+    pub fn weeks(&self) -> usize {
+        self.cell(self.days.len().saturating_sub(1)).0 + 1
+    }
+
+    /// Shading level 0-4 for a day's count, relative to the busiest day of the year
+    pub fn intensity(&self, count: u64) -> usize {
+        let max = self.days.iter().copied().max().unwrap_or(0);
+        if count == 0 || max == 0 {
+            0
+        } else {
+            (count * 4).div_ceil(max).clamp(1, 4) as usize
+        }
+    }
+
+    /// Total full reads in the year
+    pub fn total(&self) -> u64 {
+        self.days.iter().sum()
+    }
+
+    /// Days of the year with at least one full read
+    pub fn active_days(&self) -> usize {
+        self.days.iter().filter(|count| **count > 0).count()
+    }
+}
+
+/// Everything the recommender works from when it picks a topic, for debugging its choices
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct RecommenderState {
+    pub profile: String,
+    pub generated_at: String,
+    /// Whether topics are weighted at all; until something has been read, stories are
+    /// picked at random from the whole library
+    pub weighted: bool,
+    /// Settings in effect, including the current explore/exploit balance
+    pub settings: RecommendationConfig,
+    /// Topics of the last stories shown, most recent first
+    pub recent_topics: Vec<Topic>,
+    /// Topic weights as the diversity strategy sees them; other strategies don't weigh
+    /// topics (see `settings.strategy`)
+    pub topics: Vec<TopicState>,
+}
+
+/// One topic's part in `RecommenderState`
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct TopicState {
+    pub topic: Topic,
+    /// Share of interactions that were full reads; unset when the topic has no history
+    /// and `default_preference` stands in
+    pub preference: Option<f64>,
+    pub interactions: i64,
+    /// Whether the topic has anything left to show right now
+    pub selectable: bool,
+    /// Weight after recency penalties, exploration bonus and balance (0 when not selectable)
+    pub weight: f64,
+    /// Chance of the topic being picked next
+    pub probability: f64,
+    /// How the weight was reached; unset for topics that weren't scored
+    pub explanation: Option<Explanation>,
+}
+
+/// What applying a `RetentionPolicy` did, or would do on a dry run
+#[derive(Debug, Clone, Default)]
+pub struct RetentionReport {
+    pub dry_run: bool,
+    /// Whether pruned stories were soft-deleted rather than removed
+    pub soft_deleted: bool,
+    /// Topics with at least one story matching the policy
+    pub topics: Vec<TopicRetention>,
+}
+
+impl RetentionReport {
+    /// Stories pruned across all topics
+    pub fn pruned(&self) -> usize {
+        self.topics.iter().map(|topic| topic.pruned.len()).sum()
+    }
+
+    /// Stories that matched the policy but were kept to stay at `keep_per_topic`
+    pub fn kept_by_floor(&self) -> usize {
+        self.topics.iter().map(|topic| topic.kept_by_floor).sum()
+    }
+}
+
+/// One topic's part in `RetentionReport`
+#[derive(Debug, Clone)]
+pub struct TopicRetention {
+    pub topic: Topic,
+    /// Live stories in the topic before pruning
+    pub stored: usize,
+    /// Pruned stories as (id, title), poorest first
+    pub pruned: Vec<(i64, String)>,
+    pub kept_by_floor: usize,
+}
+
+/// Per-topic interaction aggregates used by the recommender
+/// Computed from one GROUP BY query and cached until the next interaction is recorded
+#[derive(Debug, Clone, Default)]
+struct TopicStats {
+    /// Share of interactions that were full reads, per topic (0.0 to 1.0)
+    preferences: HashMap<Topic, f64>,
+    /// Total number of interactions per topic
+    interaction_counts: HashMap<Topic, i64>,
+}
+
+/// One row of `migrations_log`
+#[derive(Debug, Clone)]
+pub struct MigrationRecord {
+    pub version: u32,
+    pub description: String,
+    pub applied_at: String,
+    pub duration_ms: i64,
+}
+
+/// A change to a table that already exists
+#[derive(Debug, Clone, Copy)]
+enum SchemaChange {
+    AddColumn {
+        table: &'static str,
+        column: &'static str,
+        definition: &'static str,
+    },
+    CreateIndex {
+        name: &'static str,
+        table: &'static str,
+        columns: &'static str,
+    },
+    /// A unique index on `content.content_hash`, once missing hashes are filled in and
+    /// duplicates merged (see `merge_duplicate_content`)
+    UniqueContentHash { name: &'static str },
+    /// An FTS5 index over the title and text of `content`, kept up to date by triggers
+    /// and filled from the rows already there
+    FullTextIndex { name: &'static str },
+    /// A `profile_id` column, which rows from before profiles existed fill with the
+    /// default profile
+    ProfileColumn { table: &'static str },
+    /// A table of what each profile pinned and when it last saw each story, filled from
+    /// the old `content.pinned` and `content.last_shown` columns, which the default
+    /// profile inherits
+    ProfileContent { name: &'static str },
+    /// Favourites, once a table of their own, become pins in `profile_content` and the
+    /// table is dropped, so a schema without it has this change
+    FavoritesAsPins { name: &'static str },
+}
+
+/// A schema change made after the initial tables, applied when a database is opened
+/// Whether it is pending is read from the schema itself, so databases that picked up a
+/// change before migrations were logged aren't migrated twice
+#[derive(Debug, Clone, Copy)]
+pub struct Migration {
+    pub version: u32,
+    pub description: &'static str,
+    change: SchemaChange,
+}
+
+/// Every migration, oldest first
+const MIGRATIONS: &[Migration] = &[
+    Migration::add_column(1, "Soft deletion time of content", "content", "deleted_at", "TEXT"),
+    Migration::add_column(2, "Original topic label of imported content", "content", "imported_topic", "TEXT"),
+    Migration::add_column(3, "How often content has been shown", "content", "times_shown", "INTEGER NOT NULL DEFAULT 0"),
+    Migration::add_column(4, "When content was last shown", "content", "last_shown", "TEXT"),
+    Migration::add_column(5, "Stored hook score", "content", "hook_score", "INTEGER"),
+    Migration::add_column(6, "Stored quality score", "content", "quality_score", "INTEGER"),
+    Migration::add_column(7, "Content pack that installed the content", "content", "pack_id", "INTEGER"),
+    Migration::add_column(8, "Hash for spotting duplicate content", "content", "content_hash", "TEXT"),
+    Migration::add_column(9, "Pinned content", "content", "pinned", "INTEGER NOT NULL DEFAULT 0"),
+    Migration::add_column(10, "Content kind (article, quote, fun fact)", "content", "kind", "TEXT NOT NULL DEFAULT 'article'"),
+    Migration::add_column(11, "Article a fun fact was taken from", "content", "parent_id", "INTEGER"),
+    Migration::create_index(12, "Index content by hash", "idx_content_hash", "content", "content_hash"),
+    Migration::profile_column(13, "Profile of each interaction", "user_interactions"),
+    Migration::create_index(14, "Index interactions by profile", "idx_interactions_profile", "user_interactions", "profile_id"),
+    Migration::create_index(
+        15,
+        "Index interactions by content for cooldown and recently-read lookups",
+        "idx_interactions_content",
+        "user_interactions",
+        "content_id, profile_id, interaction_type, timestamp",
+    ),
+    Migration::add_column(16, "Characters shown before a skip", "user_interactions", "displayed_chars", "INTEGER"),
+    Migration::add_column(17, "Share of content shown before a skip", "user_interactions", "percent_displayed", "INTEGER"),
+    Migration::add_column(18, "Position of a part within a split article", "content", "part_index", "INTEGER"),
+    Migration::add_column(19, "Content matching a block", "content", "blocked", "INTEGER NOT NULL DEFAULT 0"),
+    Migration::unique_content_hash(
+        20,
+        "Unique content hashes (fills in missing hashes and merges duplicated content)",
+        "idx_content_hash_unique",
+    ),
+    Migration::add_column(21, "Stored sensitivity score", "content", "sensitivity", "INTEGER"),
+    Migration::full_text_index(22, "Full-text search over titles and text", "content_fts"),
+    Migration::add_column(23, "Estimated year a story is set in", "content", "estimated_year", "INTEGER"),
+    Migration::create_index(24, "Index content by estimated year", "idx_content_estimated_year", "content", "estimated_year"),
+    Migration::add_column(25, "Content never to be shown again", "content", "hidden", "INTEGER NOT NULL DEFAULT 0"),
+    Migration::profile_content(26, "Pins and last-shown times per profile", "profile_content"),
+    Migration::favorites_as_pins(27, "Favourites become pins", "favorites"),
+];
+
+impl Migration {
+    const fn add_column(
+        version: u32,
+        description: &'static str,
+        table: &'static str,
+        column: &'static str,
+        definition: &'static str,
+    ) -> Self {
+        Self {
+            version,
+            description,
+            change: SchemaChange::AddColumn {
+                table,
+                column,
+                definition,
+            },
+        }
+    }
+
+    const fn create_index(
+        version: u32,
+        description: &'static str,
+        name: &'static str,
+        table: &'static str,
+        columns: &'static str,
+    ) -> Self {
+        Self {
+            version,
+            description,
+            change: SchemaChange::CreateIndex { name, table, columns },
+        }
+    }
+
+    const fn unique_content_hash(version: u32, description: &'static str, name: &'static str) -> Self {
+        Self {
+            version,
+            description,
+            change: SchemaChange::UniqueContentHash { name },
+        }
+    }
+
+    const fn full_text_index(version: u32, description: &'static str, name: &'static str) -> Self {
+        Self {
+            version,
+            description,
+            change: SchemaChange::FullTextIndex { name },
+        }
+    }
+
+    const fn profile_column(version: u32, description: &'static str, table: &'static str) -> Self {
+        Self {
+            version,
+            description,
+            change: SchemaChange::ProfileColumn { table },
+        }
+    }
+
+    const fn profile_content(version: u32, description: &'static str, name: &'static str) -> Self {
+        Self {
+            version,
+            description,
+            change: SchemaChange::ProfileContent { name },
+        }
+    }
+
+    const fn favorites_as_pins(version: u32, description: &'static str, name: &'static str) -> Self {
+        Self {
+            version,
+            description,
+            change: SchemaChange::FavoritesAsPins { name },
+        }
+    }
+
+    /// The SQL statement this migration runs
+    pub fn sql(&self) -> String {
+        match self.change {
+            SchemaChange::AddColumn {
+                table,
+                column,
+                definition,
+            } => format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition),
+            SchemaChange::ProfileColumn { table } => format!(
+                "ALTER TABLE {} ADD COLUMN profile_id INTEGER NOT NULL DEFAULT {}",
+                table, DEFAULT_PROFILE_ID
+            ),
+            SchemaChange::CreateIndex { name, table, columns } => {
+                format!("CREATE INDEX IF NOT EXISTS {} ON {} ({})", name, table, columns)
+            }
+            SchemaChange::UniqueContentHash { name } => {
+                format!("CREATE UNIQUE INDEX IF NOT EXISTS {} ON content (content_hash)", name)
+            }
+            SchemaChange::FullTextIndex { name } => format!(
+                "CREATE VIRTUAL TABLE {name} USING fts5(title, content, content = 'content', content_rowid = 'id');
+                 CREATE TRIGGER {name}_insert AFTER INSERT ON content BEGIN
+                     INSERT INTO {name} (rowid, title, content) VALUES (new.id, new.title, new.content);
+                 END;
+                 CREATE TRIGGER {name}_delete AFTER DELETE ON content BEGIN
+                     INSERT INTO {name} ({name}, rowid, title, content) VALUES ('delete', old.id, old.title, old.content);
+                 END;
+                 CREATE TRIGGER {name}_update AFTER UPDATE OF title, content ON content BEGIN
+                     INSERT INTO {name} ({name}, rowid, title, content) VALUES ('delete', old.id, old.title, old.content);
+                     INSERT INTO {name} (rowid, title, content) VALUES (new.id, new.title, new.content);
+                 END;
+                 INSERT INTO {name} ({name}) VALUES ('rebuild')"
+            ),
+            SchemaChange::ProfileContent { name } => format!(
+                "CREATE TABLE {name} (
+                     profile_id INTEGER NOT NULL,
+                     content_id INTEGER NOT NULL,
+                     pinned INTEGER NOT NULL DEFAULT 0,
+                     last_shown TEXT,
+                     PRIMARY KEY (profile_id, content_id)
+                 );
+                 INSERT INTO {name} (profile_id, content_id, pinned, last_shown)
+                     SELECT {profile}, id, pinned, last_shown FROM content
+                     WHERE pinned = 1 OR last_shown IS NOT NULL",
+                profile = DEFAULT_PROFILE_ID
+            ),
+            SchemaChange::FavoritesAsPins { name } => format!(
+                "INSERT INTO profile_content (profile_id, content_id, pinned)
+                     SELECT profile_id, content_id, 1 FROM {name} WHERE true
+                     ON CONFLICT (profile_id, content_id) DO UPDATE SET pinned = 1;
+                 DROP TABLE {name}"
+            ),
+        }
+    }
+
+    /// Whether the schema already has this change
+    fn is_applied(&self, conn: &Connection) -> Result<bool> {
+        match self.change {
+            SchemaChange::AddColumn { table, column, .. } => has_column(conn, table, column),
+            SchemaChange::ProfileColumn { table } => has_column(conn, table, "profile_id"),
+            SchemaChange::CreateIndex { name, .. } | SchemaChange::UniqueContentHash { name } => {
+                let count: i64 = conn.query_row(
+                    "SELECT COUNT(*) FROM sqlite_master WHERE type = 'index' AND name = ?1",
+                    params![name],
+                    |row| row.get(0),
+                )?;
+                Ok(count > 0)
+            }
+            SchemaChange::FullTextIndex { name } | SchemaChange::ProfileContent { name } => has_table(conn, name),
+            SchemaChange::FavoritesAsPins { name } => Ok(!has_table(conn, name)?),
+        }
+    }
+
+    /// Run the migration and log it in `migrations_log`
+    fn apply(&self, conn: &Connection) -> Result<()> {
+        let started = std::time::Instant::now();
+        let tx = conn.unchecked_transaction()?;
+        if let SchemaChange::UniqueContentHash { .. } = self.change {
+            merge_duplicate_content(&tx)?;
+        }
+        tx.execute_batch(&self.sql())?;
+        tx.execute(
+            "INSERT OR REPLACE INTO migrations_log (version, description, applied_at, duration_ms)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![
+                self.version,
+                self.description,
+                chrono::Utc::now().to_rfc3339(),
+                started.elapsed().as_millis() as i64
+            ],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+}
+
+/// Fill in `content_hash` where it's missing, then fold each set of rows sharing a hash
+/// into one: the live row if there is one, else the oldest. Interactions, pins and fun
+/// facts of the others move over to it before they are deleted. Returns how many rows
+/// were merged away
+fn merge_duplicate_content(conn: &Connection) -> Result<usize> {
+    let missing = {
+        let mut stmt = conn.prepare("SELECT id, topic, title, content FROM content WHERE content_hash IS NULL")?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?, row.get(2)?, row.get(3)?))
+        })?;
+        rows.collect::<rusqlite::Result<Vec<_>>>()?
+    };
+    for (id, topic, title, text) in missing {
+        let topic: Topic = serde_json::from_str(&topic)?;
+        let hash = ContentUnit::new(topic, title, text, String::new()).content_hash();
+        conn.execute("UPDATE content SET content_hash = ?1 WHERE id = ?2", params![hash, id])?;
+    }
+
+    let duplicates = {
+        let mut stmt = conn.prepare(
+            "SELECT id, keep FROM (
+                 SELECT c.id, (
+                     SELECT k.id FROM content k
+                     WHERE k.content_hash = c.content_hash
+                     ORDER BY k.deleted_at IS NOT NULL, k.id
+                     LIMIT 1
+                 ) AS keep
+                 FROM content c
+                 WHERE c.content_hash IN
+                     (SELECT content_hash FROM content GROUP BY content_hash HAVING COUNT(*) > 1)
+             )
+             WHERE id != keep",
+        )?;
+        let rows = stmt.query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?)))?;
+        rows.collect::<rusqlite::Result<Vec<_>>>()?
+    };
+    for &(id, keep) in &duplicates {
+        fold_content(conn, id, keep)?;
+    }
+    Ok(duplicates.len())
+}
+
+/// Delete content `id` after moving its interactions, favourites, fun facts and pins to `keep`
+fn fold_content(conn: &Connection, id: i64, keep: i64) -> Result<()> {
+    conn.execute("UPDATE user_interactions SET content_id = ?2 WHERE content_id = ?1", params![id, keep])?;
+    // Favourites were still kept apart from pins when migration 20 ran on older databases;
+    // a profile may have both copies as favourites, and one is enough
+    if has_table(conn, "favorites")? {
+        conn.execute("UPDATE OR IGNORE favorites SET content_id = ?2 WHERE content_id = ?1", params![id, keep])?;
+        conn.execute("DELETE FROM favorites WHERE content_id = ?1", params![id])?;
+    }
+    conn.execute("UPDATE content SET parent_id = ?2 WHERE parent_id = ?1", params![id, keep])?;
+    conn.execute(
+        "UPDATE content SET pinned = MAX(pinned, (SELECT pinned FROM content WHERE id = ?1)) WHERE id = ?2",
+        params![id, keep],
+    )?;
+    // Duplicates merged by migration 20 predate per-profile state; the pins above move with them
+    if has_table(conn, "profile_content")? {
+        // A profile that has state for both copies keeps the pin of either and the later showing
+        conn.execute(
+            "UPDATE profile_content SET
+                 pinned = MAX(pinned, (SELECT d.pinned FROM profile_content d
+                                       WHERE d.content_id = ?1 AND d.profile_id = profile_content.profile_id)),
+                 last_shown = (SELECT MAX(p.last_shown) FROM profile_content p
+                               WHERE p.content_id IN (?1, ?2) AND p.profile_id = profile_content.profile_id)
+             WHERE content_id = ?2
+               AND profile_id IN (SELECT profile_id FROM profile_content WHERE content_id = ?1)",
+            params![id, keep],
+        )?;
+        conn.execute("UPDATE OR IGNORE profile_content SET content_id = ?2 WHERE content_id = ?1", params![id, keep])?;
+        conn.execute("DELETE FROM profile_content WHERE content_id = ?1", params![id])?;
+    }
+    conn.execute("DELETE FROM content WHERE id = ?1", params![id])?;
+    Ok(())
+}
+
+/// Delete content `id` for good, together with the fun facts taken from it and the
+/// interactions and pins of either. Returns the number of content rows deleted
+fn delete_content_row(conn: &Connection, id: i64) -> Result<usize> {
+    for table in ["user_interactions", "profile_content"] {
+        conn.execute(
+            &format!(
+                "DELETE FROM {} WHERE content_id IN (SELECT id FROM content WHERE id = ?1 OR parent_id = ?1)",
+                table
+            ),
+            params![id],
+        )?;
+    }
+    Ok(conn.execute("DELETE FROM content WHERE id = ?1 OR parent_id = ?1", params![id])?)
+}
+
+/// Whether `table` in the schema behind `conn` has a column called `column`
+fn has_column(conn: &Connection, table: &str, column: &str) -> Result<bool> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table))?;
+    let exists = stmt
+        .query_map([], |row| row.get::<_, String>(1))?
+        .filter_map(|name| name.ok())
+        .any(|name| name == column);
+    Ok(exists)
+}
+
+/// Whether the schema behind `conn` has a table (or virtual table) called `name`
+fn has_table(conn: &Connection, name: &str) -> Result<bool> {
+    let count: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = ?1",
+        params![name],
+        |row| row.get(0),
+    )?;
+    Ok(count > 0)
+}
+
+/// SQL predicate matching content stored under one of the current topics
+/// Content under topic names from other builds would fail in `row_to_content_unit`
+fn known_topics_filter() -> String {
+    let known: Vec<String> = Topic::all()
+        .iter()
+        .map(|topic| format!("'{}'", serde_json::to_string(topic).expect("topics serialize")))
+        .collect();
+    format!("topic IN ({})", known.join(", "))
+}
+
+/// A search as an FTS5 query: every word must appear, the last one also as the start
+/// of a longer word so results keep up while it's being typed. Words are quoted, so
+/// FTS syntax in them is taken literally. None when there are no words
+fn fts_query(query: &str) -> Option<String> {
+    let words: Vec<String> = query
+        .split_whitespace()
+        .map(|word| format!("\"{}\"", word.replace('"', "\"\"")))
+        .collect();
+    let (last, rest) = words.split_last()?;
+    Some(rest.iter().cloned().chain([format!("{}*", last)]).collect::<Vec<_>>().join(" "))
+}
+
+/// Migrations the schema behind `conn` doesn't have yet, oldest first
+fn pending_migrations(conn: &Connection) -> Result<Vec<&'static Migration>> {
+    let mut pending = Vec::new();
+    for migration in MIGRATIONS {
+        if !migration.is_applied(conn)? {
+            pending.push(migration);
+        }
+    }
+    Ok(pending)
+}
 
 /// Database wrapper that handles all SQLite operations
 /// This struct demonstrates Rust's ownership and encapsulation
 pub struct Database {
     conn: Connection,
+    recommendation: RecommendationConfig,
+    /// Chooses topics for weighted picks, as set in `recommendation.strategy`
+    strategy: RefCell<Box<dyn SelectionStrategy>>,
+    storage: StorageConfig,
+    sensitivity: SensitivityConfig,
+    /// Compiled `sensitivity.terms`, built when first needed
+    sensitivity_scorer: OnceCell<SensitivityScorer>,
+    /// Profile whose interactions are recorded and used for recommendations
+    profile: Profile,
+    /// Cached topic statistics, invalidated whenever interactions change
+    topic_stats: RefCell<Option<TopicStats>>,
+    /// Content hidden for the lifetime of this connection, e.g. near-duplicates of dismissed content
+    session_suppressed: RefCell<HashSet<i64>>,
+    /// Whether interactions are logged in full or only counted per topic
+    privacy: PrivacyMode,
+    /// Topics shown this session, most recent first; stands in for the interaction log
+    /// when it isn't kept
+    session_topics: RefCell<Vec<Topic>>,
+    /// Current `explore_exploit_balance`, which the reader can nudge while it runs
+    balance: Cell<f64>,
+    /// Compiled blocks checked when content is added, invalidated whenever blocks change
+    blocklist: RefCell<Option<ContentBlocklist>>,
+    /// Whether the linked SQLite supports `INSERT ... RETURNING`; a system library
+    /// can be older than the bundled one
+    supports_returning: bool,
 }
 
-impl Database {
-    /// Create a new database connection and initialize tables
-    /// This demonstrates error propagation with the ? operator
-    pub fn new(db_path: &str) -> Result<Self> {
-        let conn = Connection::open(db_path)?;
-        let db = Self { conn };
-        db.init_tables()?;
-        Ok(db)
+impl Database {
+    /// Create a new database connection and initialize tables
+    /// This demonstrates error propagation with the ? operator
+    /// An existing database with pending migrations is first copied to
+    /// `pre_migrate_backup_path`, so an upgrade that goes wrong can be undone
+    pub fn new(db_path: &str) -> Result<Self> {
+        if std::path::Path::new(db_path).exists() {
+            let conn = Connection::open_with_flags(db_path, rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+            if has_table(&conn, "content")? && !pending_migrations(&conn)?.is_empty() {
+                Self::backup(db_path, &Self::pre_migrate_backup_path(db_path, Self::schema_version(db_path)?))?;
+            }
+        }
+        Self::new_without_backup(db_path)
+    }
+
+    /// Like `new`, but applies pending migrations without copying the database first
+    pub fn new_without_backup(db_path: &str) -> Result<Self> {
+        Self::from_connection(Connection::open(db_path)?)
+    }
+
+    /// Open a database that only keeps as much reading history as `privacy` allows
+    pub fn new_with_privacy(db_path: &str, privacy: PrivacyMode) -> Result<Self> {
+        Ok(Self {
+            privacy,
+            ..Self::new(db_path)?
+        })
+    }
+
+    /// Create a throwaway database that lives only in memory
+    /// Useful for benchmarks and for embedding tellme without touching disk
+    pub fn new_in_memory() -> Result<Self> {
+        Self::from_connection(Connection::open_in_memory()?)
+    }
+
+    /// Wrap an open connection and make sure the schema is up to date
+    fn from_connection(conn: Connection) -> Result<Self> {
+        let db = Self {
+            conn,
+            recommendation: RecommendationConfig::default(),
+            strategy: RefCell::new(selection::strategy(StrategyKind::default())),
+            storage: StorageConfig::default(),
+            sensitivity: SensitivityConfig::default(),
+            sensitivity_scorer: OnceCell::new(),
+            profile: Profile {
+                id: DEFAULT_PROFILE_ID,
+                name: DEFAULT_PROFILE.to_string(),
+                created_at: String::new(),
+            },
+            topic_stats: RefCell::new(None),
+            session_suppressed: RefCell::new(HashSet::new()),
+            privacy: PrivacyMode::default(),
+            session_topics: RefCell::new(Vec::new()),
+            balance: Cell::new(DEFAULT_EXPLORE_EXPLOIT_BALANCE),
+            blocklist: RefCell::new(None),
+            supports_returning: supports_returning(rusqlite::version_number()),
+        };
+        db.init_tables()?;
+        let profile = db
+            .get_profile(DEFAULT_PROFILE)?
+            .ok_or_else(|| anyhow::anyhow!("The '{}' profile is missing", DEFAULT_PROFILE))?;
+        Ok(Self { profile, ..db })
+    }
+
+    /// Migrations the database at `db_path` still needs, without applying them
+    /// Unlike `new`, this leaves the file untouched
+    pub fn pending_migrations(db_path: &str) -> Result<Vec<&'static Migration>> {
+        let conn = Connection::open_with_flags(db_path, rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+        pending_migrations(&conn)
+    }
+
+    /// Schema version of the database at `db_path`: the newest migration it has with
+    /// every earlier one in place too
+    pub fn schema_version(db_path: &str) -> Result<u32> {
+        let conn = Connection::open_with_flags(db_path, rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+        let mut version = 0;
+        for migration in MIGRATIONS {
+            if !migration.is_applied(&conn)? {
+                break;
+            }
+            version = migration.version;
+        }
+        Ok(version)
+    }
+
+    /// Where the database at `db_path` is copied before migrating it from `version`
+    pub fn pre_migrate_backup_path(db_path: &str, version: u32) -> std::path::PathBuf {
+        std::path::Path::new(db_path).with_file_name(format!("pre-migrate-{}.db", version))
+    }
+
+    /// Copy the database at `db_path` to `backup_path` with SQLite's online backup,
+    /// which gives a consistent snapshot even while another connection is writing
+    pub fn backup(db_path: &str, backup_path: &std::path::Path) -> Result<()> {
+        let conn = Connection::open_with_flags(db_path, rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+        conn.backup(rusqlite::DatabaseName::Main, backup_path, None)?;
+        Ok(())
+    }
+
+    /// Migrations applied by tellme, oldest first, with when they ran and how long they took
+    pub fn get_migrations_log(&self) -> Result<Vec<MigrationRecord>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT version, description, applied_at, duration_ms FROM migrations_log ORDER BY version",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok(MigrationRecord {
+                version: row.get(0)?,
+                description: row.get(1)?,
+                applied_at: row.get(2)?,
+                duration_ms: row.get(3)?,
+            })
+        })?;
+        rows.collect::<rusqlite::Result<Vec<_>>>().map_err(Into::into)
+    }
+
+    /// Use the given recommender settings instead of the defaults, clamping
+    /// `topic_filter_share` as loading the config file does
+    pub fn with_recommendation(mut self, mut config: RecommendationConfig) -> Self {
+        config.clamp_topic_filter_share();
+        self.set_explore_exploit_balance(config.explore_exploit_balance);
+        self.strategy = RefCell::new(selection::strategy(config.strategy));
+        self.recommendation = config;
+        self
+    }
+
+    /// Choose topics with `strategy` instead of the configured one
+    pub fn with_strategy(mut self, strategy: Box<dyn SelectionStrategy>) -> Self {
+        self.strategy = RefCell::new(strategy);
+        self
+    }
+
+    /// Name of the strategy choosing topics, for diagnostics
+    pub fn strategy_name(&self) -> &'static str {
+        self.strategy.borrow().name()
+    }
+
+    /// How far topic selection leans towards exploring (1.0) rather than favourites (0.0)
+    pub fn explore_exploit_balance(&self) -> f64 {
+        self.balance.get()
+    }
+
+    /// Change the explore/exploit balance for the following picks, returning the new value
+    /// Values are clamped to 0.0-1.0 and rounded to one decimal
+    pub fn set_explore_exploit_balance(&self, balance: f64) -> f64 {
+        let balance = (balance.clamp(0.0, 1.0) * 10.0).round() / 10.0;
+        self.balance.set(balance);
+        balance
+    }
+
+    /// Use the given storage limits instead of the defaults (no limit)
+    pub fn with_storage(mut self, config: StorageConfig) -> Self {
+        self.storage = config;
+        self
+    }
+
+    /// Score stories with these terms, and leave sensitive ones out in safe mode
+    pub fn with_sensitivity(mut self, config: SensitivityConfig) -> Self {
+        self.sensitivity = config;
+        self.sensitivity_scorer = OnceCell::new();
+        self
+    }
+
+    fn sensitivity_scorer(&self) -> &SensitivityScorer {
+        self.sensitivity_scorer.get_or_init(|| SensitivityScorer::new(&self.sensitivity.terms))
+    }
+
+    /// Whether stored content scores as sensitive; unscored content doesn't
+    pub fn is_sensitive(&self, content_id: i64) -> Result<bool> {
+        let score: Option<u32> = self
+            .conn
+            .query_row("SELECT sensitivity FROM content WHERE id = ?1", params![content_id], |row| row.get(0))
+            .optional()?
+            .flatten();
+        Ok(score.is_some_and(|score| score >= self.sensitivity.threshold))
+    }
+
+    /// Score every story again, e.g. after `sensitivity.terms` changed
+    /// Returns the number of stories scored
+    pub fn rescore_sensitivity(&self) -> Result<usize> {
+        self.conn.execute("UPDATE content SET sensitivity = NULL", [])?;
+        let filled = self.backfill(&[BackfillColumn::Sensitivity])?;
+        Ok(filled.first().map_or(0, |(_, count)| *count))
+    }
+
+    /// Read and record interactions as the named profile instead of the default one
+    pub fn with_profile(mut self, name: &str) -> Result<Self> {
+        self.profile = self
+            .get_profile(name)?
+            .ok_or_else(|| anyhow::anyhow!("No profile named '{}'", name))?;
+        self.invalidate_topic_stats();
+        Ok(self)
+    }
+
+    /// The profile this connection reads and records interactions for
+    pub fn profile(&self) -> &Profile {
+        &self.profile
+    }
+
+    /// How much reading history this connection keeps
+    pub fn privacy(&self) -> PrivacyMode {
+        self.privacy
+    }
+
+    /// Initialize database tables if they don't exist
+    /// This demonstrates multi-line SQL strings and transaction handling
+    fn init_tables(&self) -> Result<()> {
+        // Create content table
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS content (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                topic TEXT NOT NULL,
+                title TEXT NOT NULL,
+                content TEXT NOT NULL,
+                source_url TEXT NOT NULL,
+                word_count INTEGER NOT NULL,
+                created_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        // Create user_interactions table
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS user_interactions (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                content_id INTEGER NOT NULL,
+                interaction_type TEXT NOT NULL,
+                timestamp TEXT NOT NULL,
+                duration_seconds INTEGER NOT NULL,
+                FOREIGN KEY (content_id) REFERENCES content (id)
+            )",
+            [],
+        )?;
+
+        // Create profiles table; everything recorded before profiles existed belongs to the default one
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS profiles (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                name TEXT NOT NULL UNIQUE,
+                created_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+        self.conn.execute(
+            "INSERT OR IGNORE INTO profiles (id, name, created_at) VALUES (?1, ?2, ?3)",
+            params![DEFAULT_PROFILE_ID, DEFAULT_PROFILE, chrono::Utc::now().to_rfc3339()],
+        )?;
+
+        // Create packs table; content installed from a pack points back at it through content.pack_id
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS packs (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                name TEXT NOT NULL UNIQUE,
+                description TEXT NOT NULL,
+                version TEXT NOT NULL,
+                source TEXT NOT NULL,
+                checksum TEXT NOT NULL,
+                installed_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        // Create per-topic interaction counters, kept instead of the log in aggregate privacy mode
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS topic_counters (
+                profile_id INTEGER NOT NULL,
+                topic TEXT NOT NULL,
+                fully_read INTEGER NOT NULL DEFAULT 0,
+                skipped INTEGER NOT NULL DEFAULT 0,
+                dismissed INTEGER NOT NULL DEFAULT 0,
+                PRIMARY KEY (profile_id, topic)
+            )",
+            [],
+        )?;
+
+        // Cache of term explanations looked up from the reader, so each is fetched once
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS term_lookups (
+                term TEXT PRIMARY KEY COLLATE NOCASE,
+                title TEXT NOT NULL,
+                extract TEXT NOT NULL,
+                source_url TEXT NOT NULL,
+                fetched_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        // Stories kept out of reading, by content hash or title pattern (see moderation.rs)
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS blocked_content (
+                entry TEXT NOT NULL,
+                source TEXT NOT NULL,
+                added_at TEXT NOT NULL,
+                PRIMARY KEY (entry, source)
+            )",
+            [],
+        )?;
+
+        // Where the remote blocklist was last fetched from, and its ETag
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS blocklist_sync (
+                url TEXT PRIMARY KEY,
+                etag TEXT,
+                synced_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        // Articles the fetcher has already processed, found by which topic's query, so
+        // neither a resumed nor a later run requests them again
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS fetch_progress (
+                topic TEXT NOT NULL,
+                query TEXT NOT NULL,
+                title TEXT NOT NULL,
+                units INTEGER NOT NULL,
+                processed_at TEXT NOT NULL,
+                PRIMARY KEY (topic, query, title)
+            )",
+            [],
+        )?;
+
+        // Create index for better query performance
+        self.conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_content_topic ON content (topic)",
+            [],
+        )?;
+
+        // Record of the migrations applied by tellme, with when and how long they took
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS migrations_log (
+                version INTEGER PRIMARY KEY,
+                description TEXT NOT NULL,
+                applied_at TEXT NOT NULL,
+                duration_ms INTEGER NOT NULL
+            )",
+            [],
+        )?;
+
+        // Bring older databases up to date
+        for migration in pending_migrations(&self.conn)? {
+            migration.apply(&self.conn)?;
+        }
+
+        Ok(())
+    }
+
+    /// Insert a new content unit into the database
+    /// This demonstrates parameter binding and returning generated IDs
+    /// Fails with `DuplicateContent` if content with the same hash is already stored
+    pub fn insert_content(&self, content: &mut ContentUnit) -> Result<()> {
+        self.insert_imported_content(content, None, None)
+    }
+
+    /// Insert imported content, with the original topic label of content that had to be
+    /// remapped (kept for auditing how imports were classified) and the pack it came from
+    /// Both are written with the row itself, so an import never ends up half-labelled
+    pub fn insert_imported_content(
+        &self,
+        content: &mut ContentUnit,
+        imported_topic: Option<&str>,
+        pack_id: Option<i64>,
+    ) -> Result<()> {
+        // The same text under the same title and topic is only stored once, even if deleted
+        let content_hash = content.content_hash();
+        let existing = self
+            .conn
+            .query_row("SELECT id FROM content WHERE content_hash = ?1", params![content_hash], |row| row.get(0))
+            .optional()?;
+        if let Some(id) = existing {
+            return Err(DuplicateContent(id).into());
+        }
+
+        // Make room first if the library is capped
+        if let Some(max) = self.storage.max_content_units {
+            if self.get_stored_content_count()? >= max {
+                match self.storage.limit_policy {
+                    LimitPolicy::Evict => {
+                        self.enforce_size_limit(max - 1)?;
+                        if self.get_stored_content_count()? >= max {
+                            anyhow::bail!("Content limit of {} units reached and nothing can be evicted", max);
+                        }
+                    }
+                    LimitPolicy::Refuse => {
+                        anyhow::bail!("Content limit of {} units reached", max);
+                    }
+                }
+            }
+        }
+
+        let topic_str = serde_json::to_string(&content.topic)?;
+        let created_at_str = content.created_at.to_rfc3339();
+
+        let id = self.insert_returning_id(
+            "INSERT INTO content (topic, title, content, source_url, word_count, created_at, hook_score, quality_score, content_hash, kind, parent_id, part_index, sensitivity, estimated_year, imported_topic, pack_id)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16)",
+            params![
+                topic_str,
+                content.title,
+                content.content,
+                content.source_url,
+                content.word_count,
+                created_at_str,
+                hook_score(&content.content),
+                calculate_content_quality_score(&content.content, &content.title),
+                content_hash,
+                content.kind.as_str(),
+                content.parent_id,
+                content.part_index,
+                sensitivity_score(self.sensitivity_scorer(), content),
+                stored_year(content),
+                imported_topic,
+                pack_id
+            ],
+        )?;
+
+        if self.is_blocked(&content.title, &content_hash)? {
+            self.conn.execute("UPDATE content SET blocked = 1 WHERE id = ?1", params![id])?;
+        }
+
+        content.id = id;
+        Ok(())
+    }
+
+    /// Run an INSERT and return the id of the new row
+    /// Uses `RETURNING id` where SQLite supports it, and `last_insert_rowid()` on
+    /// versions before 3.35
+    fn insert_returning_id<P: rusqlite::Params>(&self, sql: &str, params: P) -> Result<i64> {
+        if self.supports_returning {
+            let sql = format!("{} RETURNING id", sql);
+            Ok(self.conn.query_row(&sql, params, |row| row.get(0))?)
+        } else {
+            self.conn.execute(sql, params)?;
+            Ok(self.conn.last_insert_rowid())
+        }
+    }
+
+    /// A term explanation looked up before, matched case-insensitively
+    pub fn get_cached_term(&self, term: &str) -> Result<Option<TermSummary>> {
+        self.conn
+            .query_row(
+                "SELECT term, title, extract, source_url FROM term_lookups WHERE term = ?1",
+                params![term],
+                |row| {
+                    Ok(TermSummary {
+                        term: row.get(0)?,
+                        title: row.get(1)?,
+                        extract: row.get(2)?,
+                        source_url: row.get(3)?,
+                    })
+                },
+            )
+            .optional()
+            .map_err(Into::into)
+    }
+
+    /// Remember a term explanation for the next time it is looked up
+    pub fn cache_term(&self, summary: &TermSummary) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO term_lookups (term, title, extract, source_url, fetched_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                summary.term,
+                summary.title,
+                summary.extract,
+                summary.source_url,
+                chrono::Utc::now().to_rfc3339()
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Live content matching every word of `query` in its title or text, best match first
+    /// Title matches rank well above matches in the text. A query with no words gives no
+    /// results, and FTS syntax in it is matched as plain text
+    pub fn search_content(&self, query: &str, limit: usize) -> Result<Vec<ContentUnit>> {
+        let Some(fts_query) = fts_query(query) else {
+            return Ok(Vec::new());
+        };
+
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {}
+             FROM content
+             JOIN (
+                 SELECT rowid AS hit_id, bm25(content_fts, {}, 1.0) AS hit_rank
+                 FROM content_fts
+                 WHERE content_fts MATCH ?1
+             ) ON hit_id = content.id
+             WHERE {} AND {}
+             ORDER BY hit_rank
+             LIMIT ?2",
+            CONTENT_COLUMNS,
+            SEARCH_TITLE_WEIGHT,
+            LIVE_CONTENT,
+            known_topics_filter()
+        ))?;
+        let rows = stmt.query_map(params![fts_query, limit.min(MAX_SEARCH_RESULTS)], |row| {
+            self.row_to_content_unit(row)
+        })?;
+        rows.collect::<rusqlite::Result<Vec<_>>>().map_err(Into::into)
+    }
+
+    /// Whether any stored content, deleted or not, already has this title
+    pub fn content_title_exists(&self, title: &str) -> Result<bool> {
+        let exists = self.conn.query_row(
+            "SELECT EXISTS (SELECT 1 FROM content WHERE title = ?1)",
+            params![title],
+            |row| row.get(0),
+        )?;
+        Ok(exists)
+    }
+
+    /// Whether the fetcher already processed `title` as found by `query` for `topic`
+    pub fn fetch_progress_done(&self, topic: Topic, query: &str, title: &str) -> Result<bool> {
+        let topic_str = serde_json::to_string(&topic)?;
+        let done = self.conn.query_row(
+            "SELECT EXISTS (SELECT 1 FROM fetch_progress WHERE topic = ?1 AND query = ?2 AND title = ?3)",
+            params![topic_str, query, title],
+            |row| row.get(0),
+        )?;
+        Ok(done)
+    }
+
+    /// Mark an article as processed by the fetcher, with the number of units it gave
+    pub fn mark_fetch_progress(&self, topic: Topic, query: &str, title: &str, units: usize) -> Result<()> {
+        let topic_str = serde_json::to_string(&topic)?;
+        self.conn.execute(
+            "INSERT OR REPLACE INTO fetch_progress (topic, query, title, units, processed_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![topic_str, query, title, units, chrono::Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    /// Forget every processed article so the fetcher tries them all again
+    /// Returns how many were forgotten
+    pub fn reset_fetch_progress(&self) -> Result<usize> {
+        Ok(self.conn.execute("DELETE FROM fetch_progress", [])?)
+    }
+
+    /// Original topic label of imported content that had to be remapped, for auditing
+    /// how an import was classified
+    pub fn get_imported_topic(&self, content_id: i64) -> Result<Option<String>> {
+        let label = self
+            .conn
+            .query_row("SELECT imported_topic FROM content WHERE id = ?1", params![content_id], |row| row.get(0))
+            .optional()?;
+        Ok(label.flatten())
+    }
+
+    /// Look up live content by its stable hash (see `ContentUnit::content_hash`)
+    pub fn get_content_by_hash(&self, hash: &str) -> Result<Option<ContentUnit>> {
+        self.conn
+            .query_row(
+                &format!(
+                    "SELECT {}
+                     FROM content
+                     WHERE content_hash = ?1 AND {}
+                     ORDER BY id ASC
+                     LIMIT 1",
+                    CONTENT_COLUMNS,
+                    LIVE_CONTENT
+                ),
+                params![hash],
+                |row| self.row_to_content_unit(row),
+            )
+            .optional()
+            .map_err(Into::into)
+    }
+
+    /// Get a content unit using smart balanced recommendation
+    /// This ensures variety while still learning from user preferences
+    pub fn get_weighted_random_content(&self) -> Result<Option<ContentUnit>> {
+        // Now and then, revisit a pinned favourite instead
+        if self.roll_for_pinned() {
+            if let Some(content) = self.get_next_pinned()? {
+                return Ok(Some(content));
+            }
+        }
+
+        // Get topic statistics (cached) and recent topic history
+        let stats = self.topic_stats()?;
+        let recent_topics = self.get_recent_topics(RECENT_TOPIC_WINDOW)?;
+        
+        // If no preferences exist, return truly random content, unless topics should get
+        // even airtime (then every topic starts out with the same default weight) or
+        // another strategy chooses them
+        let even_airtime = self.recommendation.even_topic_airtime;
+        let diversity = self.recommendation.strategy == StrategyKind::Diversity;
+        if stats.preferences.is_empty() && diversity && !even_airtime {
+            return self.get_random_content();
+        }
+
+        // Only topics with something left to show can be picked
+        let mut selectable = self.get_selectable_topics()?;
+        if selectable.is_empty() {
+            return Ok(None);
+        }
+
+        let mut strategy = self.strategy.borrow_mut();
+        let mut rng = rand::thread_rng();
+        loop {
+            let mut ctx = SelectionContext {
+                inputs: self.selection_inputs(&stats, &recent_topics, &selectable),
+                rng: &mut rng,
+            };
+            let Some(topic) = strategy.choose(&mut ctx) else {
+                break;
+            };
+            if let Some(content) = self.get_random_content_by_topic(topic)? {
+                return Ok(Some(content));
+            }
+            // Everything in the chosen topic is cooling down: with even airtime, choose
+            // another topic rather than a story from all content
+            if !even_airtime || !selectable.remove(&topic) {
+                break;
+            }
+        }
+        self.get_random_content()
+    }
+
+    /// Up to `n` stories the recommender would likely serve next, without recording anything
+    /// Topics are chosen as in `get_weighted_random_content`, each preview pick counting as
+    /// recently shown for the next one; within a topic the likeliest story is listed (unseen
+    /// first, then the best hook). Pinned and resumed stories, which can come up in between,
+    /// are left out. The built-in strategies keep no state, so previewing doesn't change
+    /// what they choose later
+    pub fn preview_next_selections(&self, n: usize) -> Result<Vec<(Topic, ContentUnit)>> {
+        self.preview_next_selections_with(n, &mut rand::thread_rng())
+    }
+
+    /// `preview_next_selections`, drawing from `rng`
+    fn preview_next_selections_with(&self, n: usize, rng: &mut dyn rand::RngCore) -> Result<Vec<(Topic, ContentUnit)>> {
+        use rand::seq::IteratorRandom;
+
+        let stats = self.topic_stats()?;
+        let mut recent_topics = self.get_recent_topics(RECENT_TOPIC_WINDOW)?;
+        let mut selectable = self.get_selectable_topics()?;
+        let mut previewed = Vec::new();
+        let mut preview = Vec::new();
+
+        let diversity = self.recommendation.strategy == StrategyKind::Diversity;
+        let mut strategy = self.strategy.borrow_mut();
+        while preview.len() < n && !selectable.is_empty() {
+            let topic = if stats.preferences.is_empty() && diversity {
+                // Without preferences any story can come up, so any topic can too
+                selectable.iter().copied().choose(rng)
+            } else {
+                strategy.choose(&mut SelectionContext {
+                    inputs: self.selection_inputs(&stats, &recent_topics, &selectable),
+                    rng,
+                })
+            };
+            let Some(topic) = topic else {
+                break;
+            };
+
+            match self.get_likeliest_content(topic, &previewed)? {
+                Some(content) => {
+                    previewed.push(content.id);
+                    preview.push((topic, content));
+                    recent_topics.insert(0, topic);
+                    recent_topics.truncate(5);
+                }
+                // Everything left in the topic is already in the preview
+                None => {
+                    selectable.remove(&topic);
+                }
+            }
+        }
+
+        Ok(preview)
+    }
+
+    /// The story in `topic` that random picks favour most, leaving out `exclude`
+    fn get_likeliest_content(&self, topic: Topic, exclude: &[i64]) -> Result<Option<ContentUnit>> {
+        let excluded = exclude.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(", ");
+        self.conn
+            .query_row(
+                &format!(
+                    "SELECT {}
+                     FROM content
+                     WHERE topic = ?2 AND id NOT IN ({}) AND {} AND {} AND {} AND {}
+                     ORDER BY times_shown > 0, COALESCE(hook_score, 0) DESC, id
+                     LIMIT 1",
+                    CONTENT_COLUMNS,
+                    excluded,
+                    LIVE_CONTENT,
+                    NOT_FUN_FACT,
+                    self.not_cooling_down(),
+                    self.stream_filter()
+                ),
+                params![self.skip_cooldown_cutoff(), serde_json::to_string(&topic)?],
+                |row| self.row_to_content_unit(row),
+            )
+            .optional()
+            .map_err(Into::into)
+    }
+
+    /// Now and then (see `resume_probability`), the content most recently skipped when it
+    /// was almost read, to offer picking it up where the reader stopped
+    pub fn pick_resume_candidate(&self) -> Result<Option<ResumeCandidate>> {
+        use rand::Rng;
+        let probability = self.recommendation.resume_probability.clamp(0.0, 1.0);
+        if probability == 0.0 || !rand::thread_rng().gen_bool(probability) {
+            return Ok(None);
+        }
+        Ok(self.get_resume_candidates()?.into_iter().next())
+    }
+
+    /// Content the active profile skipped with at least 60% revealed in the last 7 days,
+    /// most recently skipped first. Anything read, dismissed or skipped again earlier on
+    /// since drops out, and so does the content shown last. The skip cooldown doesn't
+    /// apply: these are stories the reader was probably interrupted in, not ones they disliked
+    pub fn get_resume_candidates(&self) -> Result<Vec<ResumeCandidate>> {
+        let cutoff = (chrono::Utc::now() - chrono::Duration::days(RESUME_WINDOW_DAYS)).to_rfc3339();
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {}, latest.displayed_chars, latest.percent_displayed
+             FROM content
+             JOIN (
+                 SELECT content_id, interaction_type, displayed_chars, percent_displayed,
+                        MAX(timestamp) AS last_at
+                 FROM user_interactions
+                 WHERE profile_id = ?1 AND timestamp <= ?4
+                 GROUP BY content_id
+             ) latest ON latest.content_id = content.id
+             WHERE latest.interaction_type = 'skipped'
+               AND latest.last_at > ?2
+               AND latest.percent_displayed >= ?3 AND latest.percent_displayed < 100
+               AND latest.displayed_chars IS NOT NULL
+               AND {} AND {} AND id NOT IN ({}) AND {} AND {}
+             ORDER BY latest.last_at DESC",
+            CONTENT_COLUMNS,
+            LIVE_CONTENT,
+            known_topics_filter(),
+            self.suppressed_ids(),
+            self.not_shown_last(),
+            self.sensitivity_filter()
+        ))?;
+        let rows = stmt.query_map(
+            params![self.profile.id, cutoff, RESUME_MIN_PERCENT, latest_trusted_timestamp()],
+            |row| {
+                Ok(ResumeCandidate {
+                    content: self.row_to_content_unit(row)?,
+                    displayed_chars: row.get("displayed_chars")?,
+                    percent_displayed: row.get("percent_displayed")?,
+                })
+            },
+        )?;
+
+        rows.collect::<rusqlite::Result<Vec<_>>>().map_err(Into::into)
+    }
+
+    /// Decide whether a pick under a topic filter should come from the filtered topic
+    /// (see `topic_filter_share`), rather than be picked as if there were no filter
+    pub fn roll_for_topic_filter(&self) -> bool {
+        use rand::Rng;
+        let share = self.topic_filter_share();
+        share >= 1.0 || (share > 0.0 && rand::thread_rng().gen_bool(share))
+    }
+
+    /// Share of picks under a topic filter taken from the filtered topic, 0.0 to 1.0
+    pub fn topic_filter_share(&self) -> f64 {
+        self.recommendation.topic_filter_share
+    }
+
+    /// Whether a topic filter leaves room for other topics (`topic_filter_share` below 1.0)
+    pub fn has_soft_topic_filter(&self) -> bool {
+        self.topic_filter_share() < 1.0
+    }
+
+    /// Decide whether this pick should come from the pinned content
+    fn roll_for_pinned(&self) -> bool {
+        use rand::Rng;
+        let probability = self.recommendation.pinned_probability.clamp(0.0, 1.0);
+        probability > 0.0 && rand::thread_rng().gen_bool(probability)
+    }
+
+    /// The content the active profile pinned and saw longest ago, leaving out whatever it
+    /// was shown last so a pinned story never comes up twice in a row
+    fn get_next_pinned(&self) -> Result<Option<ContentUnit>> {
+        self.conn
+            .query_row(
+                &format!(
+                    "SELECT {}
+                     FROM content
+                     JOIN profile_content pc ON pc.content_id = content.id AND pc.profile_id = ?2
+                     WHERE pc.pinned = 1 AND {} AND {} AND {} AND {} AND {}
+                     ORDER BY pc.last_shown IS NOT NULL, pc.last_shown ASC
+                     LIMIT 1",
+                    CONTENT_COLUMNS,
+                    LIVE_CONTENT,
+                    known_topics_filter(),
+                    self.not_cooling_down(),
+                    self.not_shown_last(),
+                    self.sensitivity_filter()
+                ),
+                params![self.skip_cooldown_cutoff(), self.profile.id],
+                |row| self.row_to_content_unit(row),
+            )
+            .optional()
+            .map_err(Into::into)
+    }
+
+    /// Get random content from one topic only, for when the reader filters by topic
+    /// Unlike the recommender this never falls back to other topics
+    pub fn get_content_for_topic(&self, topic: Topic) -> Result<Option<ContentUnit>> {
+        self.get_random_content_by_topic(topic)
+    }
+
+    /// Get the next unseen content from one topic in guided order (see `guided_order`)
+    /// Once everything in the topic has been shown, falls back to random picks
+    pub fn get_guided_content_for_topic(&self, topic: Topic) -> Result<Option<ContentUnit>> {
+        let topic_str = serde_json::to_string(&topic)?;
+
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {}, quality_score
+             FROM content
+             WHERE topic = ?2 AND times_shown = 0 AND {} AND {} AND {} AND {}",
+            CONTENT_COLUMNS,
+            LIVE_CONTENT,
+            NOT_FUN_FACT,
+            self.not_cooling_down(),
+            self.stream_filter()
+        ))?;
+        let mut candidates = stmt
+            .query_map(params![self.skip_cooldown_cutoff(), topic_str], |row| {
+                Ok((self.row_to_content_unit(row)?, row.get::<_, Option<i32>>("quality_score")?))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        guided_order(&mut candidates);
+        match candidates.into_iter().next() {
+            Some((content, _)) => Ok(Some(content)),
+            None => self.get_random_content_by_topic(topic),
+        }
+    }
+
+    /// The dated story that comes next in chronological order after `after`, a year and
+    /// content id, from one topic if given, with its year
+    /// Stories are ordered by estimated year, then id; undated ones are left out. Skip
+    /// cooldowns don't apply, since going forward never comes back to a story
+    pub fn get_next_chronological(
+        &self,
+        topic: Option<Topic>,
+        after: (i32, i64),
+    ) -> Result<Option<(ContentUnit, i32)>> {
+        let topic_str = topic.map(|t| serde_json::to_string(&t)).transpose()?;
+
+        self.conn
+            .query_row(
+                &format!(
+                    "SELECT {}, estimated_year
+                     FROM content
+                     WHERE estimated_year IS NOT NULL AND estimated_year != 0
+                       AND (estimated_year > ?1 OR (estimated_year = ?1 AND id > ?2))
+                       AND (?3 IS NULL OR topic = ?3) AND {} AND {} AND {}
+                     ORDER BY estimated_year, id
+                     LIMIT 1",
+                    CONTENT_COLUMNS,
+                    LIVE_CONTENT,
+                    NOT_FUN_FACT,
+                    self.stream_filter()
+                ),
+                params![after.0, after.1, topic_str],
+                |row| Ok((self.row_to_content_unit(row)?, row.get("estimated_year")?)),
+            )
+            .optional()
+            .map_err(Into::into)
+    }
+
+    /// Get a random fun fact for quick-bite mode, from one topic if given
+    /// Fun facts follow the same cooldown and dismissal rules as regular content
+    pub fn get_quick_bite(&self, topic: Option<Topic>) -> Result<Option<ContentUnit>> {
+        let topic_str = topic.map(|t| serde_json::to_string(&t)).transpose()?;
+
+        self.conn
+            .query_row(
+                &format!(
+                    "SELECT {}
+                     FROM content
+                     WHERE kind = 'funfact' AND (?2 IS NULL OR topic = ?2) AND {} AND {} AND {}
+                     ORDER BY {}
+                     LIMIT 1",
+                    CONTENT_COLUMNS,
+                    LIVE_CONTENT,
+                    self.not_cooling_down(),
+                    self.stream_filter(),
+                    self.random_order()
+                ),
+                params![self.skip_cooldown_cutoff(), topic_str],
+                |row| self.row_to_content_unit(row),
+            )
+            .optional()
+            .map_err(Into::into)
+    }
+
+    /// Weight of each selectable topic for the next pick (see `selection::score_topics`)
+    fn score_topics(
+        &self,
+        stats: &TopicStats,
+        recent_topics: &[Topic],
+        selectable: &HashSet<Topic>,
+    ) -> Vec<(Topic, f64, Explanation)> {
+        selection::score_topics(&self.selection_inputs(stats, recent_topics, selectable))
+    }
+
+    /// Everything a topic is chosen from, with the settings in effect
+    fn selection_inputs<'a>(
+        &'a self,
+        stats: &'a TopicStats,
+        recent_topics: &'a [Topic],
+        selectable: &'a HashSet<Topic>,
+    ) -> SelectionInputs<'a> {
+        SelectionInputs {
+            preferences: &stats.preferences,
+            interaction_counts: &stats.interaction_counts,
+            recent_topics,
+            selectable,
+            settings: &self.recommendation,
+            balance: self.explore_exploit_balance(),
+        }
+    }
+
+    /// The recommender's view of every topic right now, as `get_weighted_random_content`
+    /// would use it for the next pick. Pinned and resumed picks, which can come first, aren't
+    /// covered
+    pub fn dump_recommender_state(&self) -> Result<RecommenderState> {
+        let stats = self.topic_stats()?;
+        let recent_topics = self.get_recent_topics(RECENT_TOPIC_WINDOW)?;
+        let selectable = self.get_selectable_topics()?;
+        let scores = self.score_topics(&stats, &recent_topics, &selectable);
+        let chances = if self.recommendation.strategy == StrategyKind::Diversity {
+            selection::diversity_chances(&self.selection_inputs(&stats, &recent_topics, &selectable), &scores)
+        } else {
+            let total: f64 = scores.iter().map(|(_, weight, _)| weight).sum();
+            let share = |weight: f64| if total > 0.0 { weight / total } else { 0.0 };
+            scores.iter().map(|&(topic, weight, _)| (topic, share(weight))).collect()
+        };
+
+        let topics = Topic::all()
+            .iter()
+            .map(|&topic| {
+                let score = scores.iter().find(|(scored, _, _)| *scored == topic);
+                let weight = score.map_or(0.0, |(_, weight, _)| *weight);
+                TopicState {
+                    topic,
+                    preference: stats.preferences.get(&topic).copied(),
+                    interactions: stats.interaction_counts.get(&topic).copied().unwrap_or(0),
+                    selectable: selectable.contains(&topic),
+                    weight,
+                    probability: chances
+                        .iter()
+                        .find(|(chanced, _)| *chanced == topic)
+                        .map_or(0.0, |(_, chance)| *chance),
+                    explanation: score.map(|(_, _, explanation)| *explanation),
+                }
+            })
+            .collect();
+
+        Ok(RecommenderState {
+            profile: self.profile.name.clone(),
+            generated_at: chrono::Utc::now().to_rfc3339(),
+            weighted: !stats.preferences.is_empty(),
+            settings: RecommendationConfig {
+                explore_exploit_balance: self.explore_exploit_balance(),
+                ..self.recommendation.clone()
+            },
+            recent_topics,
+            topics,
+        })
+    }
+    
+    /// Topics that still have live content which isn't cooling down or suppressed
+    fn get_selectable_topics(&self) -> Result<HashSet<Topic>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT DISTINCT topic FROM content WHERE {} AND {} AND {} AND {}",
+            LIVE_CONTENT,
+            NOT_FUN_FACT,
+            self.not_cooling_down(),
+            self.stream_filter()
+        ))?;
+        let rows = stmt.query_map(params![self.skip_cooldown_cutoff()], |row| {
+            row.get::<_, String>(0)
+        })?;
+
+        let mut topics = HashSet::new();
+        for row_result in rows {
+            if let Ok(topic) = serde_json::from_str::<Topic>(&row_result?) {
+                topics.insert(topic);
+            }
+        }
+        Ok(topics)
+    }
+
+    /// Get recently shown topics to prevent repetition
+    fn get_recent_topics(&self, limit: usize) -> Result<Vec<Topic>> {
+        if self.privacy == PrivacyMode::Aggregate {
+            return Ok(self.session_topics.borrow().iter().copied().take(limit).collect());
+        }
+
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT c.topic FROM user_interactions ui
+             JOIN content c ON ui.content_id = c.id
+             WHERE ui.profile_id = ?2 AND ui.timestamp <= ?3 AND {}
+             ORDER BY ui.timestamp DESC, ui.id DESC
+             LIMIT ?1",
+            LIVE_CONTENT
+        ))?;
+        
+        let rows = stmt.query_map(params![limit, self.profile.id, latest_trusted_timestamp()], |row| {
+            let topic_str: String = row.get(0)?;
+            Ok(topic_str)
+        })?;
+        
+        let mut recent_topics = Vec::new();
+        for row_result in rows {
+            let topic_str = row_result?;
+            if let Ok(topic) = serde_json::from_str::<Topic>(&topic_str) {
+                recent_topics.push(topic);
+            }
+        }
+        
+        Ok(recent_topics)
+    }
+    
+    /// Cutoff timestamp for the skip cooldown: content skipped after it is still withheld
+    fn skip_cooldown_cutoff(&self) -> String {
+        (chrono::Utc::now() - self.recommendation.skip_cooldown()).to_rfc3339()
+    }
+
+    /// SQL predicate that withholds content the active profile skipped after the cutoff
+    /// bound to `?1`. Once the skip cooldown has passed the content becomes eligible again.
+    /// Dismissed content is withheld for good, and suppressed content for the session
+    fn not_cooling_down(&self) -> String {
+        format!(
+            "id NOT IN (
+                SELECT content_id FROM user_interactions
+                WHERE ((interaction_type = 'skipped' AND timestamp > ?1 AND timestamp <= '{}')
+                       OR interaction_type = 'dismissed')
+                  AND profile_id = {}
+            ) AND id NOT IN ({})",
+            latest_trusted_timestamp(),
+            self.profile.id,
+            self.suppressed_ids()
+        )
+    }
+
+    /// SQL predicate for the stream exclusions: content under topic names this build doesn't
+    /// know (see `tellme maintain --remap-topics`), and optionally content the active profile
+    /// pinned or fully read recently (unless it is pinned). Pinned content still comes up
+    /// through the pinned picks, which don't use this
+    fn stream_filter(&self) -> String {
+        let mut filters = vec![known_topics_filter()];
+        if self.recommendation.exclude_pinned_from_stream {
+            filters.push(format!("NOT {}", self.is_pinned_sql()));
+        }
+        if self.sensitivity.safe_mode {
+            filters.push(self.sensitivity_filter());
+        }
+        if self.recommendation.exclude_read_within_days > 0 {
+            let cutoff = chrono::Utc::now()
+                - chrono::Duration::days(i64::from(self.recommendation.exclude_read_within_days));
+            filters.push(format!(
+                "(NOT EXISTS (
+                    SELECT 1 FROM user_interactions
+                    WHERE content_id = content.id AND profile_id = {}
+                      AND interaction_type = 'fully_read' AND timestamp > '{}' AND timestamp <= '{}'
+                ) OR {})",
+                self.profile.id,
+                cutoff.to_rfc3339(),
+                latest_trusted_timestamp(),
+                self.is_pinned_sql()
+            ));
+        }
+        filters.join(" AND ")
+    }
+
+    /// SQL predicate keeping sensitive content out in safe mode; matches everything otherwise
+    /// Unlike the rest of `stream_filter` this also applies to resumed and pinned picks,
+    /// as does `known_topics_filter`
+    fn sensitivity_filter(&self) -> String {
+        if self.sensitivity.safe_mode {
+            format!("(sensitivity IS NULL OR sensitivity < {})", self.sensitivity.threshold)
+        } else {
+            "TRUE".to_string()
+        }
+    }
+
+    /// Session-suppressed content ids as a comma-separated SQL list
+    fn suppressed_ids(&self) -> String {
+        self.session_suppressed
+            .borrow()
+            .iter()
+            .map(|id| id.to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Hide content from selection until this connection is closed
+    pub fn suppress_for_session(&self, content_ids: &[i64]) {
+        self.session_suppressed.borrow_mut().extend(content_ids);
+    }
+
+    /// Find live content in the same topic that covers much the same ground as `content`
+    /// Compares keywords from the titles and openings, so it's cheap enough to run per dismissal
+    pub fn find_near_duplicates(&self, content: &ContentUnit) -> Result<Vec<i64>> {
+        let opening = |title: &str, text: &str| {
+            let cut = text.char_indices().nth(NEAR_DUPLICATE_CHARS).map_or(text.len(), |(i, _)| i);
+            keywords(&format!("{}\n{}", title, &text[..cut]))
+        };
+        let target = opening(&content.title, &content.content);
+
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT id, title, substr(content, 1, {})
+             FROM content
+             WHERE {} AND topic = ?1 AND id != ?2",
+            NEAR_DUPLICATE_CHARS, LIVE_CONTENT
+        ))?;
+        let rows = stmt.query_map(
+            params![serde_json::to_string(&content.topic)?, content.id],
+            |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?)),
+        )?;
+
+        let mut similar = Vec::new();
+        for row in rows {
+            let (id, title, text) = row?;
+            if keyword_similarity(&target, &opening(&title, &text)) >= NEAR_DUPLICATE_SIMILARITY {
+                similar.push(id);
+            }
+        }
+        Ok(similar)
+    }
+
+    /// ORDER BY clause for random picks: unseen content first, then random with a
+    /// nudge towards content whose first sentence hooks the reader
+    /// Rows without a stored hook score count as neutral
+    fn random_order(&self) -> String {
+        format!(
+            "times_shown > 0 AND NOT {}, ABS(RANDOM() % 100) - COALESCE(hook_score, 0) * {}",
+            self.is_pinned_sql(),
+            self.recommendation.hook_weight
+        )
+    }
+
+    /// SQL predicate for content the active profile pinned
+    /// Pinned content skips the rotation penalties, so it keeps turning up in normal reading
+    fn is_pinned_sql(&self) -> String {
+        format!(
+            "EXISTS (SELECT 1 FROM profile_content WHERE content_id = content.id AND profile_id = {} AND pinned = 1)",
+            self.profile.id
+        )
+    }
+
+    /// SQL predicate leaving out the content the active profile was shown last
+    fn not_shown_last(&self) -> String {
+        format!(
+            "content.id IS NOT (SELECT content_id FROM profile_content
+                                WHERE profile_id = {} AND last_shown IS NOT NULL
+                                ORDER BY last_shown DESC LIMIT 1)",
+            self.profile.id
+        )
+    }
+
+    /// Get completely random content, preferring content that has never been shown
+    fn get_random_content(&self) -> Result<Option<ContentUnit>> {
+        self.conn
+            .query_row(
+                &format!(
+                    "SELECT {}
+                     FROM content
+                     WHERE {} AND {} AND {} AND {}
+                     ORDER BY {}
+                     LIMIT 1",
+                    CONTENT_COLUMNS,
+                    LIVE_CONTENT,
+                    NOT_FUN_FACT,
+                    self.not_cooling_down(),
+                    self.stream_filter(),
+                    self.random_order()
+                ),
+                params![self.skip_cooldown_cutoff()],
+                |row| self.row_to_content_unit(row),
+            )
+            .optional()
+            .map_err(Into::into)
+    }
+
+    /// Get random content from a specific topic, preferring content that has never been shown
+    fn get_random_content_by_topic(&self, topic: Topic) -> Result<Option<ContentUnit>> {
+        let topic_str = serde_json::to_string(&topic)?;
+        
+        self.conn
+            .query_row(
+                &format!(
+                    "SELECT {}
+                     FROM content
+                     WHERE topic = ?2 AND {} AND {} AND {} AND {}
+                     ORDER BY {}
+                     LIMIT 1",
+                    CONTENT_COLUMNS,
+                    LIVE_CONTENT,
+                    NOT_FUN_FACT,
+                    self.not_cooling_down(),
+                    self.stream_filter(),
+                    self.random_order()
+                ),
+                params![self.skip_cooldown_cutoff(), topic_str],
+                |row| self.row_to_content_unit(row),
+            )
+            .optional()
+            .map_err(Into::into)
+    }
+
+    /// Convert a database row to a ContentUnit
+    /// This demonstrates error handling within row mapping
+    fn row_to_content_unit(&self, row: &Row) -> rusqlite::Result<ContentUnit> {
+        let topic_str: String = row.get(1)?;
+        let topic: Topic = serde_json::from_str(&topic_str)
+            .map_err(|e| rusqlite::Error::FromSqlConversionFailure(
+                1, 
+                rusqlite::types::Type::Text, 
+                Box::new(e)
+            ))?;
+
+        let created_at_str: String = row.get(6)?;
+        let created_at = chrono::DateTime::parse_from_rfc3339(&created_at_str)
+            .map_err(|e| rusqlite::Error::FromSqlConversionFailure(
+                6, 
+                rusqlite::types::Type::Text, 
+                Box::new(e)
+            ))?
+            .with_timezone(&chrono::Utc);
+
+        Ok(ContentUnit {
+            id: row.get(0)?,
+            topic,
+            title: row.get(2)?,
+            content: row.get(3)?,
+            source_url: row.get(4)?,
+            word_count: row.get(5)?,
+            created_at,
+            kind: ContentKind::from_stored(&row.get::<_, String>(7)?),
+            parent_id: row.get(8)?,
+            part_index: row.get(9)?,
+        })
+    }
+
+    /// Record a user interaction with content
+    /// This demonstrates enum serialization and database transactions
+    pub fn record_interaction(&self, interaction: &UserInteraction) -> Result<()> {
+        self.store_interaction(&self.conn, interaction, self.profile.id)?;
+        self.invalidate_topic_stats();
+        Ok(())
+    }
+
+    /// Log or count one interaction for `profile_id`, depending on the privacy mode
+    fn store_interaction(
+        &self,
+        conn: &Connection,
+        interaction: &UserInteraction,
+        profile_id: i64,
+    ) -> Result<()> {
+        match self.privacy {
+            PrivacyMode::Full => Self::insert_interaction(conn, interaction, profile_id),
+            PrivacyMode::Aggregate => {
+                let topic = Self::count_interaction(conn, interaction, profile_id)?;
+                // Nothing on disk says what was shown, so keep it out of this session instead
+                self.session_suppressed.borrow_mut().insert(interaction.content_id());
+                self.session_topics.borrow_mut().insert(0, topic);
+                Ok(())
+            }
+        }
+    }
+
+    /// Record interactions that were queued while the database couldn't be written
+    /// Each is stored for the profile it was queued under, all in one transaction; topic
+    /// counts queued in aggregate privacy mode are added as they are. Entries for content
+    /// that no longer exists, or for a profile that has since been deleted (even if
+    /// another took its name), are dropped. Returns the number recorded.
+    pub fn record_queued_interactions(&self, queued: &[QueuedInteraction]) -> Result<usize> {
+        let tx = self.conn.unchecked_transaction()?;
+        let mut recorded = 0;
+
+        for entry in queued {
+            let profile_id: Option<i64> = tx
+                .query_row(
+                    "SELECT id FROM profiles WHERE name = ?1",
+                    params![entry.profile],
+                    |row| row.get(0),
+                )
+                .optional()?;
+            // Entries from older builds only have the name
+            let profile_id = profile_id.filter(|id| entry.profile_id.is_none_or(|queued| queued == *id));
+            let Some(profile_id) = profile_id else {
+                continue;
+            };
+            let stored = match &entry.record {
+                QueuedRecord::Interaction(interaction) => self.store_interaction(&tx, interaction, profile_id),
+                QueuedRecord::TopicCount { topic, counter } => {
+                    Self::add_to_topic_counter(&tx, profile_id, &serde_json::to_string(topic)?, *counter)
+                }
+            };
+            match stored {
+                Ok(()) => recorded += 1,
+                // Content purged while the entry was queued
+                Err(e) if e.is::<ContentNotFound>() => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        tx.commit()?;
+        self.invalidate_topic_stats();
+        Ok(recorded)
+    }
+
+    /// Insert one interaction row for `profile_id`
+    /// Fails with `ContentNotFound` if the content id is unknown; soft-deleted content counts as known
+    fn insert_interaction(
+        conn: &Connection,
+        interaction: &UserInteraction,
+        profile_id: i64,
+    ) -> Result<()> {
+        let (interaction_type, content_id, timestamp, duration) = match interaction {
+            UserInteraction::FullyRead { content_id, timestamp, reading_time_seconds } => {
+                ("fully_read", *content_id, timestamp, *reading_time_seconds)
+            }
+            UserInteraction::Skipped { content_id, timestamp, skip_time_seconds, .. } => {
+                ("skipped", *content_id, timestamp, *skip_time_seconds)
+            }
+            UserInteraction::Dismissed { content_id, timestamp, dismiss_time_seconds } => {
+                ("dismissed", *content_id, timestamp, *dismiss_time_seconds)
+            }
+        };
+        let (displayed_chars, percent_displayed) = match interaction {
+            UserInteraction::Skipped { displayed_chars, percent_displayed, .. } => {
+                (*displayed_chars, *percent_displayed)
+            }
+            _ => (None, None),
+        };
+
+        let exists: bool = conn.query_row(
+            "SELECT EXISTS (SELECT 1 FROM content WHERE id = ?1)",
+            params![content_id],
+            |row| row.get(0),
+        )?;
+        if !exists {
+            return Err(ContentNotFound(content_id).into());
+        }
+
+        conn.execute(
+            "INSERT INTO user_interactions (content_id, interaction_type, timestamp, duration_seconds, profile_id, displayed_chars, percent_displayed)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                content_id,
+                interaction_type,
+                timestamp.to_rfc3339(),
+                duration,
+                profile_id,
+                displayed_chars,
+                percent_displayed
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Add one interaction to the per-topic counters of `profile_id`, returning the topic
+    /// Fails with `ContentNotFound` like `insert_interaction`
+    fn count_interaction(
+        conn: &Connection,
+        interaction: &UserInteraction,
+        profile_id: i64,
+    ) -> Result<Topic> {
+        let topic_str = Self::content_topic(conn, interaction.content_id())?;
+        Self::add_to_topic_counter(conn, profile_id, &topic_str, TopicCounter::of(interaction))?;
+        Ok(serde_json::from_str(&topic_str)?)
+    }
+
+    /// Stored topic label of content, live or not
+    /// Fails with `ContentNotFound` if the content id is unknown
+    fn content_topic(conn: &Connection, content_id: i64) -> Result<String> {
+        let topic = conn
+            .query_row("SELECT topic FROM content WHERE id = ?1", params![content_id], |row| row.get(0))
+            .optional()?
+            .ok_or(ContentNotFound(content_id))?;
+        Ok(topic)
+    }
+
+    /// Topic of content, for queueing its aggregate count when the count can't be written
+    pub fn topic_of(&self, content_id: i64) -> Result<Topic> {
+        Ok(serde_json::from_str(&Self::content_topic(&self.conn, content_id)?)?)
+    }
+
+    /// Add one to a per-topic counter of `profile_id`
+    fn add_to_topic_counter(
+        conn: &Connection,
+        profile_id: i64,
+        topic_str: &str,
+        counter: TopicCounter,
+    ) -> Result<()> {
+        let counter = counter.column();
+        conn.execute(
+            &format!(
+                "INSERT INTO topic_counters (profile_id, topic, {counter}) VALUES (?1, ?2, 1)
+                 ON CONFLICT (profile_id, topic) DO UPDATE SET {counter} = {counter} + 1"
+            ),
+            params![profile_id, topic_str],
+        )?;
+        Ok(())
+    }
+
+    /// Calculate topic preferences based on user interactions
+    /// This demonstrates data aggregation and HashMap usage
+    pub fn get_topic_preferences(&self) -> Result<HashMap<Topic, f64>> {
+        Ok(self.topic_stats()?.preferences)
+    }
+
+    /// Get cached topic statistics, computing them if interactions changed since last time
+    fn topic_stats(&self) -> Result<TopicStats> {
+        if let Some(stats) = self.topic_stats.borrow().as_ref() {
+            return Ok(stats.clone());
+        }
+
+        let stats = self.compute_topic_stats()?;
+        *self.topic_stats.borrow_mut() = Some(stats.clone());
+        Ok(stats)
+    }
+
+    /// Drop cached topic statistics after interactions are added or removed
+    fn invalidate_topic_stats(&self) {
+        self.topic_stats.borrow_mut().take();
+    }
+
+    /// Refuse every write from now on, as a read-only or locked database file would
+    #[cfg(test)]
+    pub(crate) fn set_read_only(&self, read_only: bool) {
+        self.conn.pragma_update(None, "query_only", read_only).unwrap();
+    }
+
+    /// Aggregate interactions per topic in a single query
+    fn compute_topic_stats(&self) -> Result<TopicStats> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT c.topic, ui.interaction_type, COUNT(*) as count
+             FROM user_interactions ui
+             JOIN content c ON ui.content_id = c.id
+             WHERE ui.profile_id = ?1 AND {}
+             GROUP BY c.topic, ui.interaction_type",
+            LIVE_CONTENT
+        ))?;
+
+        let rows = stmt.query_map([self.profile.id], |row| {
+            let topic_str: String = row.get(0)?;
+            let interaction_type: String = row.get(1)?;
+            let count: i64 = row.get(2)?;
+            Ok((topic_str, interaction_type, count))
+        })?;
+
+        let mut topic_stats: HashMap<Topic, (i64, i64)> = HashMap::new(); // (fully_read, skipped)
+        let mut interaction_counts: HashMap<Topic, i64> = HashMap::new();
+
+        // Counters kept in aggregate privacy mode add to whatever was logged in full
+        let mut counter_stmt = self.conn.prepare(
+            "SELECT topic, 'fully_read', fully_read FROM topic_counters WHERE profile_id = ?1
+             UNION ALL
+             SELECT topic, 'skipped', skipped FROM topic_counters WHERE profile_id = ?1
+             UNION ALL
+             SELECT topic, 'dismissed', dismissed FROM topic_counters WHERE profile_id = ?1",
+        )?;
+        let counters = counter_stmt.query_map([self.profile.id], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, i64>(2)?))
+        })?;
+
+        for row_result in rows.chain(counters) {
+            let (topic_str, interaction_type, count) = row_result?;
+            let topic: Topic = serde_json::from_str(&topic_str)?;
+            
+            *interaction_counts.entry(topic).or_insert(0) += count;
+
+            let entry = topic_stats.entry(topic).or_insert((0, 0));
+            match interaction_type.as_str() {
+                "fully_read" => entry.0 += count,
+                "skipped" => entry.1 += count,
+                "dismissed" => entry.1 += count * DISMISS_SKIP_WEIGHT,
+                _ => {} // Ignore unknown interaction types
+            }
+        }
+
+        // Calculate preference scores (simple ratio of fully_read to total)
+        let mut preferences = HashMap::new();
+        for (topic, (fully_read, skipped)) in topic_stats {
+            let total = fully_read + skipped;
+            if total > 0 {
+                let score = fully_read as f64 / total as f64;
+                preferences.insert(topic, score);
+            }
+        }
+
+        Ok(TopicStats {
+            preferences,
+            interaction_counts,
+        })
+    }
+
+    /// Get title and snippet of up to `limit` content units, newest first
+    /// Only the start of each text is read from the database, so list views stay cheap
+    pub fn get_previews(&self, topic: Option<Topic>, limit: usize) -> Result<Vec<ContentPreview>> {
+        self.get_content_page(&ContentFilter::default().with_topic(topic), 0, limit)
+    }
+
+    /// Get a page of previews for stories matching `filter`, newest first, leaving out fun facts
+    pub fn get_content_page(
+        &self,
+        filter: &ContentFilter,
+        offset: usize,
+        limit: usize,
+    ) -> Result<Vec<ContentPreview>> {
+        let topic_str = filter.topic.map(|t| serde_json::to_string(&t)).transpose()?;
+
+        // Read a little past the preview length so the cut can land on a word boundary
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT id, topic, title, substr(content, 1, {}), word_count
+             FROM content c
+             WHERE {} AND {} AND {}
+             ORDER BY created_at DESC, id DESC
+             LIMIT ?6 OFFSET ?7",
+            PREVIEW_CHARS + 50,
+            LIVE_CONTENT,
+            NOT_FUN_FACT,
+            CONTENT_FILTER
+        ))?;
+
+        let params = params![
+            topic_str,
+            filter.min_words,
+            filter.max_words,
+            filter.has_interactions,
+            self.profile.id,
+            limit,
+            offset
+        ];
+        let rows = stmt.query_map(params, |row| {
+            let topic_str: String = row.get(1)?;
+            let topic: Topic = serde_json::from_str(&topic_str).map_err(|e| {
+                rusqlite::Error::FromSqlConversionFailure(1, rusqlite::types::Type::Text, Box::new(e))
+            })?;
+            let snippet: String = row.get(3)?;
+            Ok(ContentPreview {
+                id: row.get(0)?,
+                topic,
+                title: row.get(2)?,
+                preview: preview_text(&snippet, PREVIEW_CHARS),
+                word_count: row.get(4)?,
+            })
+        })?;
+
+        rows.collect::<rusqlite::Result<Vec<_>>>().map_err(Into::into)
+    }
+
+    /// Iterate over the live content matching `filter`, by id ascending
+    /// Rows are read `ITER_CHUNK` at a time through one cached statement, each chunk
+    /// starting after the last id seen, so memory use doesn't grow with the library.
+    /// Content added during iteration is included if its id comes after the current
+    /// position. After an error the iterator ends
+    pub fn iter_content(&self, filter: ContentFilter) -> impl Iterator<Item = Result<ContentUnit>> + '_ {
+        self.content_chunks(filter).flat_map(|chunk| {
+            let (units, error) = match chunk {
+                Ok(units) => (units, None),
+                Err(e) => (Vec::new(), Some(e)),
+            };
+            units.into_iter().map(Ok).chain(error.map(Err))
+        })
+    }
+
+    /// The chunks `iter_content` reads, each at most `ITER_CHUNK` units
+    fn content_chunks(&self, filter: ContentFilter) -> impl Iterator<Item = Result<Vec<ContentUnit>>> + '_ {
+        // None once a short chunk or an error shows there's nothing more to read
+        let mut after_id = Some(0);
+        std::iter::from_fn(move || {
+            let chunk = self.content_chunk(&filter, after_id?);
+            after_id = match &chunk {
+                Ok(units) if units.len() == ITER_CHUNK => units.last().map(|content| content.id),
+                _ => None,
+            };
+            match chunk {
+                Ok(units) if units.is_empty() => None,
+                chunk => Some(chunk),
+            }
+        })
+    }
+
+    /// Call `f` with every live content unit matching `filter`, by id ascending, and
+    /// return how many there were; an error from `f` stops the iteration and is returned
+    pub fn for_each_content(
+        &self,
+        filter: ContentFilter,
+        mut f: impl FnMut(ContentUnit) -> Result<()>,
+    ) -> Result<usize> {
+        let mut count = 0;
+        for content in self.iter_content(filter) {
+            f(content?)?;
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// The next `ITER_CHUNK` content units matching `filter` with ids above `after_id`
+    fn content_chunk(&self, filter: &ContentFilter, after_id: i64) -> Result<Vec<ContentUnit>> {
+        let topic_str = filter.topic.map(|t| serde_json::to_string(&t)).transpose()?;
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT {} FROM content c
+             WHERE {} AND {} AND id > ?6
+             ORDER BY id
+             LIMIT ?7",
+            CONTENT_COLUMNS, LIVE_CONTENT, CONTENT_FILTER
+        ))?;
+
+        let params = params![
+            topic_str,
+            filter.min_words,
+            filter.max_words,
+            filter.has_interactions,
+            self.profile.id,
+            after_id,
+            ITER_CHUNK
+        ];
+        let rows = stmt.query_map(params, |row| self.row_to_content_unit(row))?;
+        rows.collect::<rusqlite::Result<Vec<_>>>().map_err(Into::into)
+    }
+
+    /// Get the total number of live stories in the database; fun facts aren't counted
+    pub fn get_content_count(&self) -> Result<i64> {
+        let count = self.conn.query_row(
+            &format!("SELECT COUNT(*) FROM content WHERE {} AND {}", LIVE_CONTENT, NOT_FUN_FACT),
+            [],
+            |row| row.get::<_, i64>(0),
+        )?;
+        Ok(count)
+    }
+
+    /// Get the number of interactions recorded for this profile, including those kept only
+    /// as per-topic counters in aggregate privacy mode
+    pub fn get_interaction_count(&self) -> Result<i64> {
+        let count = self.conn.query_row(
+            "SELECT (SELECT COUNT(*) FROM user_interactions WHERE profile_id = ?1)
+                  + (SELECT COALESCE(SUM(fully_read + skipped + dismissed), 0)
+                     FROM topic_counters WHERE profile_id = ?1)",
+            [self.profile.id],
+            |row| row.get::<_, i64>(0),
+        )?;
+        Ok(count)
+    }
+
+    /// Number of interactions, across all profiles, stamped more than
+    /// `CLOCK_SKEW_TOLERANCE_MINUTES` after now: a sign the clock went back since they
+    /// were recorded
+    pub fn count_future_interactions(&self) -> Result<i64> {
+        let count = self.conn.query_row(
+            "SELECT COUNT(*) FROM user_interactions WHERE timestamp > ?1",
+            [latest_trusted_timestamp()],
+            |row| row.get(0),
+        )?;
+        Ok(count)
+    }
+
+    /// Get how many stories this profile finished and how many it skipped, as
+    /// `(fully_read, skipped)`, for a completion ratio; dismissals count as neither
+    pub fn get_interaction_counts_by_type(&self) -> Result<(i64, i64)> {
+        let counts = self.conn.query_row(
+            "SELECT
+                (SELECT COUNT(*) FROM user_interactions
+                 WHERE profile_id = ?1 AND interaction_type = 'fully_read')
+                + (SELECT COALESCE(SUM(fully_read), 0) FROM topic_counters WHERE profile_id = ?1),
+                (SELECT COUNT(*) FROM user_interactions
+                 WHERE profile_id = ?1 AND interaction_type = 'skipped')
+                + (SELECT COALESCE(SUM(skipped), 0) FROM topic_counters WHERE profile_id = ?1)",
+            [self.profile.id],
+            |row| Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?)),
+        )?;
+        Ok(counts)
+    }
+
+    /// Totals of this profile's interactions, with the average time spent on finished stories
+    pub fn get_interaction_stats(&self) -> Result<InteractionStats> {
+        let (fully_read, skipped) = self.get_interaction_counts_by_type()?;
+        let avg_reading_time_seconds = self.conn.query_row(
+            "SELECT AVG(duration_seconds) FROM user_interactions
+             WHERE profile_id = ?1 AND interaction_type = 'fully_read'",
+            [self.profile.id],
+            |row| row.get::<_, Option<f64>>(0),
+        )?;
+
+        Ok(InteractionStats {
+            total: self.get_interaction_count()?,
+            fully_read,
+            skipped,
+            avg_reading_time_seconds,
+        })
+    }
+
+    /// Get the number of live stories per topic, leaving out fun facts
+    /// Topics without any content are left out
+    pub fn get_content_count_by_topic(&self) -> Result<HashMap<Topic, i64>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT topic, COUNT(*) FROM content WHERE {} AND {} GROUP BY topic",
+            LIVE_CONTENT, NOT_FUN_FACT
+        ))?;
+
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+        })?;
+
+        let mut counts = HashMap::new();
+        for row_result in rows {
+            let (topic_str, count) = row_result?;
+            if let Ok(topic) = serde_json::from_str::<Topic>(&topic_str) {
+                counts.insert(topic, count);
+            }
+        }
+        Ok(counts)
+    }
+
+    /// Check if we have content for all topics
+    pub fn has_content_for_all_topics(&self) -> Result<bool> {
+        let topic_count = self.conn.query_row(
+            &format!("SELECT COUNT(DISTINCT topic) FROM content WHERE {} AND {}", LIVE_CONTENT, NOT_FUN_FACT),
+            [],
+            |row| row.get::<_, i64>(0),
+        )?;
+        
+        Ok(topic_count == Topic::all().len() as i64)
+    }
+
+    /// Soft-delete a content unit, and the fun facts taken from it, so it no longer appears
+    /// anywhere. The row is kept until maintenance purges it, so the deletion can be undone
+    pub fn delete_content(&self, content_id: i64) -> Result<bool> {
+        let changed = self.conn.execute(
+            "UPDATE content SET deleted_at = ?1 WHERE (id = ?2 OR parent_id = ?2) AND deleted_at IS NULL",
+            params![chrono::Utc::now().to_rfc3339(), content_id],
+        )?;
+        // Interactions with deleted content stop counting towards preferences
+        self.invalidate_topic_stats();
+        Ok(changed > 0)
+    }
+
+    /// Never show a content unit again, e.g. a fragment the fetcher shouldn't have kept
+    /// Unlike a soft delete the row is never purged, so the same story isn't fetched again
+    pub fn hide_content(&self, content_id: i64) -> Result<bool> {
+        let changed = self.conn.execute(
+            "UPDATE content SET hidden = 1 WHERE id = ?1 AND hidden = 0",
+            params![content_id],
+        )?;
+        self.invalidate_topic_stats();
+        Ok(changed > 0)
+    }
+
+    /// Number of content units hidden with `hide_content`, leaving out deleted ones
+    pub fn hidden_count(&self) -> Result<i64> {
+        let count = self.conn.query_row(
+            "SELECT COUNT(*) FROM content WHERE hidden = 1 AND deleted_at IS NULL",
+            [],
+            |row| row.get(0),
+        )?;
+        Ok(count)
+    }
+
+    /// Undo a soft delete, making the content available again along with the fun facts
+    /// deleted with it
+    pub fn restore_content(&self, content_id: i64) -> Result<bool> {
+        let deleted_at: Option<String> = self
+            .conn
+            .query_row("SELECT deleted_at FROM content WHERE id = ?1", params![content_id], |row| row.get(0))
+            .optional()?
+            .flatten();
+        let Some(deleted_at) = deleted_at else {
+            return Ok(false);
+        };
+        let changed = self.conn.execute(
+            "UPDATE content SET deleted_at = NULL WHERE id = ?1 OR (parent_id = ?1 AND deleted_at = ?2)",
+            params![content_id, deleted_at],
+        )?;
+        self.invalidate_topic_stats();
+        Ok(changed > 0)
+    }
+
+    /// Get the number of stored stories, including soft-deleted ones
+    /// This is what the storage limit counts, since deleted rows still take up space.
+    /// Fun facts go with the story they were taken from, so they aren't counted
+    pub fn get_stored_content_count(&self) -> Result<i64> {
+        let count = self.conn.query_row(
+            &format!("SELECT COUNT(*) FROM content WHERE {}", NOT_FUN_FACT),
+            [],
+            |row| row.get::<_, i64>(0),
+        )?;
+        Ok(count)
+    }
+
+    /// Permanently remove the lowest-value content until at most `max` units are stored
+    /// Content soft-deleted longer ago than the undo window goes first, then content
+    /// nobody has read, skipped or saved, lowest quality and oldest first. Anything else
+    /// is kept, so fewer units may be evicted than needed. Fun facts go with their story.
+    /// Returns the number of stories evicted.
+    pub fn enforce_size_limit(&self, max: i64) -> Result<usize> {
+        let excess = self.get_stored_content_count()? - max.max(0);
+        if excess <= 0 {
+            return Ok(0);
+        }
+
+        let undo_cutoff = (chrono::Utc::now() - chrono::Duration::days(SOFT_DELETE_RETENTION_DAYS)).to_rfc3339();
+        let tx = self.conn.unchecked_transaction()?;
+        let ids = {
+            let mut stmt = tx.prepare(&format!(
+                "SELECT id FROM content c
+                 WHERE {} AND CASE WHEN c.deleted_at IS NULL
+                       THEN NOT EXISTS (SELECT 1 FROM user_interactions ui WHERE ui.content_id = c.id)
+                            AND NOT EXISTS (SELECT 1 FROM profile_content pc WHERE pc.content_id = c.id AND pc.pinned = 1)
+                       ELSE c.deleted_at < ?2
+                       END
+                 ORDER BY c.deleted_at IS NULL,
+                          COALESCE(c.quality_score, 0),
+                          c.created_at
+                 LIMIT ?1",
+                NOT_FUN_FACT
+            ))?;
+            let rows = stmt.query_map(params![excess, undo_cutoff], |row| row.get::<_, i64>(0))?;
+            rows.collect::<rusqlite::Result<Vec<_>>>()?
+        };
+
+        for &id in &ids {
+            delete_content_row(&tx, id)?;
+        }
+        tx.commit()?;
+
+        self.invalidate_topic_stats();
+        Ok(ids.len())
+    }
+
+    /// Permanently remove content that was soft-deleted more than `retention` ago
+    /// Interactions and pins pointing at purged rows are removed too so joins
+    /// stay consistent, and so are the fun facts taken from purged stories
+    pub fn purge_deleted_content(&self, retention: chrono::Duration) -> Result<usize> {
+        let cutoff = (chrono::Utc::now() - retention).to_rfc3339();
+        let tx = self.conn.unchecked_transaction()?;
+
+        let ids = {
+            let mut stmt = tx.prepare("SELECT id FROM content WHERE deleted_at IS NOT NULL AND deleted_at < ?1")?;
+            let rows = stmt.query_map(params![cutoff], |row| row.get::<_, i64>(0))?;
+            rows.collect::<rusqlite::Result<Vec<_>>>()?
+        };
+        let mut purged = 0;
+        for id in ids {
+            purged += delete_content_row(&tx, id)?;
+        }
+
+        tx.commit()?;
+        self.invalidate_topic_stats();
+        Ok(purged)
+    }
+
+    /// Prune stories matching a retention policy, keeping at least `keep_per_topic` per topic
+    /// Within a topic the lowest quality, then oldest, stories go first. Matching looks at
+    /// every profile: a story read fully or pinned by anyone is kept. With
+    /// `dry_run` nothing is changed and the report lists what would be pruned.
+    pub fn apply_retention(&self, policy: &RetentionPolicy, dry_run: bool) -> Result<RetentionReport> {
+        let cutoff = (chrono::Utc::now() - chrono::Duration::days(policy.min_age_days as i64)).to_rfc3339();
+
+        let stored: HashMap<String, usize> = {
+            let mut stmt = self.conn.prepare(&format!(
+                "SELECT topic, COUNT(*) FROM content WHERE {} AND {} GROUP BY topic",
+                LIVE_CONTENT, NOT_FUN_FACT
+            ))?;
+            let rows = stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)? as usize)))?;
+            rows.collect::<rusqlite::Result<_>>()?
+        };
+
+        let candidates: Vec<(String, i64, String)> = {
+            let mut stmt = self.conn.prepare(&format!(
+                "SELECT c.topic, c.id, c.title FROM content c
+                 WHERE {} AND {}
+                   AND NOT EXISTS (SELECT 1 FROM profile_content pc WHERE pc.content_id = c.id AND pc.pinned = 1)
+                   AND c.created_at < ?1
+                   AND c.quality_score < ?2
+                   AND c.times_shown < ?3
+                   AND NOT EXISTS (
+                       SELECT 1 FROM user_interactions ui
+                       WHERE ui.content_id = c.id AND ui.interaction_type = 'fully_read'
+                   )
+                 ORDER BY c.topic, c.quality_score, c.created_at, c.id",
+                LIVE_CONTENT, NOT_FUN_FACT
+            ))?;
+            let rows = stmt.query_map(
+                params![cutoff, policy.quality_below, policy.shown_fewer_than],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )?;
+            rows.collect::<rusqlite::Result<_>>()?
+        };
+
+        let mut topics: Vec<TopicRetention> = Vec::new();
+        for (topic_str, id, title) in candidates {
+            // Content under old topic names is left for `--remap-topics` to sort out
+            let Ok(topic) = serde_json::from_str::<Topic>(&topic_str) else {
+                continue;
+            };
+            if topics.last().is_none_or(|last| last.topic != topic) {
+                topics.push(TopicRetention {
+                    topic,
+                    stored: stored.get(&topic_str).copied().unwrap_or(0),
+                    pruned: Vec::new(),
+                    kept_by_floor: 0,
+                });
+            }
+            let entry = topics.last_mut().expect("pushed above");
+            if entry.stored - entry.pruned.len() > policy.keep_per_topic {
+                entry.pruned.push((id, title));
+            } else {
+                entry.kept_by_floor += 1;
+            }
+        }
+
+        let report = RetentionReport {
+            dry_run,
+            soft_deleted: policy.soft_delete,
+            topics,
+        };
+        if dry_run || report.pruned() == 0 {
+            return Ok(report);
+        }
+
+        let now = chrono::Utc::now().to_rfc3339();
+        let tx = self.conn.unchecked_transaction()?;
+        for (id, _) in report.topics.iter().flat_map(|topic| &topic.pruned) {
+            // Fun facts go with their story either way, as with `delete_content`
+            if policy.soft_delete {
+                tx.execute(
+                    "UPDATE content SET deleted_at = ?1 WHERE (id = ?2 OR parent_id = ?2) AND deleted_at IS NULL",
+                    params![now, id],
+                )?;
+            } else {
+                delete_content_row(&tx, *id)?;
+            }
+        }
+        tx.commit()?;
+
+        self.invalidate_topic_stats();
+        Ok(report)
+    }
+
+    /// Block a story by content hash or title pattern
+    /// Returns false if the entry was already blocked from the same source
+    pub fn add_block(&self, entry: &str, source: BlockSource) -> Result<bool> {
+        let added = self.conn.execute(
+            "INSERT OR IGNORE INTO blocked_content (entry, source, added_at) VALUES (?1, ?2, ?3)",
+            params![normalize_entry(entry), source.as_str(), chrono::Utc::now().to_rfc3339()],
+        )?;
+        if added > 0 {
+            self.apply_blocks()?;
+        }
+        Ok(added > 0)
+    }
+
+    /// Remove a block added from `source`; the same entry from the other source stays
+    pub fn remove_block(&self, entry: &str, source: BlockSource) -> Result<bool> {
+        let removed = self.conn.execute(
+            "DELETE FROM blocked_content WHERE entry = ?1 AND source = ?2",
+            params![normalize_entry(entry), source.as_str()],
+        )?;
+        if removed > 0 {
+            self.apply_blocks()?;
+        }
+        Ok(removed > 0)
+    }
+
+    /// Every stored block, local ones first, oldest first
+    pub fn list_blocks(&self) -> Result<Vec<BlockedEntry>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT entry, source, added_at FROM blocked_content
+             ORDER BY source = 'remote', added_at, entry",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok(BlockedEntry {
+                entry: row.get(0)?,
+                source: BlockSource::from_stored(&row.get::<_, String>(1)?),
+                added_at: row.get(2)?,
+            })
+        })?;
+        rows.collect::<rusqlite::Result<Vec<_>>>().map_err(Into::into)
+    }
+
+    /// Make the remote blocks match `entries`, the current remote list
+    /// Local blocks are never added or removed here
+    pub fn sync_remote_blocks(&self, entries: &[String]) -> Result<BlocklistSync> {
+        let wanted: HashSet<String> = entries.iter().map(|entry| normalize_entry(entry)).collect();
+        let current: HashSet<String> = self
+            .list_blocks()?
+            .into_iter()
+            .filter(|block| block.source == BlockSource::Remote)
+            .map(|block| block.entry)
+            .collect();
+
+        let now = chrono::Utc::now().to_rfc3339();
+        let remote = BlockSource::Remote.as_str();
+        let tx = self.conn.unchecked_transaction()?;
+        for entry in wanted.difference(&current) {
+            tx.execute(
+                "INSERT INTO blocked_content (entry, source, added_at) VALUES (?1, ?2, ?3)",
+                params![entry, remote, now],
+            )?;
+        }
+        for entry in current.difference(&wanted) {
+            tx.execute(
+                "DELETE FROM blocked_content WHERE entry = ?1 AND source = ?2",
+                params![entry, remote],
+            )?;
+        }
+        tx.commit()?;
+
+        let added = wanted.difference(&current).count();
+        let removed = current.difference(&wanted).count();
+        if added + removed > 0 {
+            self.apply_blocks()?;
+        }
+        Ok(BlocklistSync {
+            added,
+            removed,
+            invalid: self.invalid_blocks()?,
+        })
+    }
+
+    /// Flag every stored story that matches a block, and unflag the rest
+    /// Returns the number of stories blocked
+    pub fn apply_blocks(&self) -> Result<usize> {
+        self.blocklist.replace(None);
+
+        let stories: Vec<(i64, String, Option<String>, bool)> = {
+            let mut stmt = self.conn.prepare("SELECT id, title, content_hash, blocked FROM content")?;
+            let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)))?;
+            rows.collect::<rusqlite::Result<_>>()?
+        };
+
+        let blocklist = self.content_blocklist()?;
+        let blocklist = blocklist.as_ref().expect("loaded above");
+        let mut blocked = 0;
+        let tx = self.conn.unchecked_transaction()?;
+        for (id, title, hash, was_blocked) in stories {
+            let is_blocked = blocklist.is_blocked(&title, hash.as_deref());
+            blocked += usize::from(is_blocked);
+            if is_blocked != was_blocked {
+                tx.execute("UPDATE content SET blocked = ?1 WHERE id = ?2", params![is_blocked, id])?;
+            }
+        }
+        tx.commit()?;
+
+        self.invalidate_topic_stats();
+        Ok(blocked)
+    }
+
+    /// Whether a story with this title and hash matches a stored block
+    fn is_blocked(&self, title: &str, content_hash: &str) -> Result<bool> {
+        let blocklist = self.content_blocklist()?;
+        Ok(blocklist.as_ref().is_some_and(|blocklist| blocklist.is_blocked(title, Some(content_hash))))
+    }
+
+    /// Stored blocks that don't compile (a bad regex in a shared list) and are ignored
+    pub fn invalid_blocks(&self) -> Result<Vec<String>> {
+        let blocklist = self.content_blocklist()?;
+        Ok(blocklist.as_ref().map(|blocklist| blocklist.invalid().to_vec()).unwrap_or_default())
+    }
+
+    /// The stored blocks compiled, loading them on first use
+    /// Entries that don't compile are skipped; `invalid_blocks` lists them
+    fn content_blocklist(&self) -> Result<std::cell::Ref<'_, Option<ContentBlocklist>>> {
+        if self.blocklist.borrow().is_none() {
+            let entries = self.list_blocks()?;
+            let blocklist = ContentBlocklist::new(entries.iter().map(|block| block.entry.as_str()));
+            self.blocklist.replace(Some(blocklist));
+        }
+        Ok(self.blocklist.borrow())
+    }
+
+    /// ETag and time of the last sync with the blocklist at `url`
+    pub fn get_blocklist_sync(&self, url: &str) -> Result<Option<(Option<String>, String)>> {
+        self.conn
+            .query_row(
+                "SELECT etag, synced_at FROM blocklist_sync WHERE url = ?1",
+                params![url],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()
+            .map_err(Into::into)
+    }
+
+    /// Remember a successful sync with the blocklist at `url`
+    /// An unchanged list keeps its ETag when `etag` is None
+    pub fn record_blocklist_sync(&self, url: &str, etag: Option<&str>) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO blocklist_sync (url, etag, synced_at) VALUES (?1, ?2, ?3)
+             ON CONFLICT (url) DO UPDATE SET etag = COALESCE(?2, etag), synced_at = ?3",
+            params![url, etag, chrono::Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    /// Look up a profile by name
+    pub fn get_profile(&self, name: &str) -> Result<Option<Profile>> {
+        self.conn
+            .query_row(
+                "SELECT id, name, created_at FROM profiles WHERE name = ?1",
+                params![name],
+                |row| {
+                    Ok(Profile {
+                        id: row.get(0)?,
+                        name: row.get(1)?,
+                        created_at: row.get(2)?,
+                    })
+                },
+            )
+            .optional()
+            .map_err(Into::into)
+    }
+
+    /// Get every profile, oldest first
+    pub fn list_profiles(&self) -> Result<Vec<Profile>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT id, name, created_at FROM profiles ORDER BY id ASC")?;
+        let rows = stmt.query_map([], |row| {
+            Ok(Profile {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                created_at: row.get(2)?,
+            })
+        })?;
+
+        rows.collect::<rusqlite::Result<Vec<_>>>().map_err(Into::into)
+    }
+
+    /// Create a new, empty profile
+    pub fn create_profile(&self, name: &str) -> Result<Profile> {
+        let name = name.trim();
+        if name.is_empty() {
+            anyhow::bail!("Profile name cannot be empty");
+        }
+        if self.get_profile(name)?.is_some() {
+            anyhow::bail!("Profile '{}' already exists", name);
+        }
+
+        self.conn.execute(
+            "INSERT INTO profiles (name, created_at) VALUES (?1, ?2)",
+            params![name, chrono::Utc::now().to_rfc3339()],
+        )?;
+        self.get_profile(name)?
+            .ok_or_else(|| anyhow::anyhow!("Profile '{}' was not created", name))
+    }
+
+    /// Delete a profile together with its interaction history
+    /// The default profile and the profile in use can't be deleted. Returns false if
+    /// no profile has that name.
+    pub fn delete_profile(&self, name: &str) -> Result<bool> {
+        let Some(profile) = self.get_profile(name)? else {
+            return Ok(false);
+        };
+        if profile.id == DEFAULT_PROFILE_ID {
+            anyhow::bail!("The '{}' profile can't be deleted", DEFAULT_PROFILE);
+        }
+        if profile.id == self.profile.id {
+            anyhow::bail!("Profile '{}' is in use", name);
+        }
+
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
+            "DELETE FROM user_interactions WHERE profile_id = ?1",
+            params![profile.id],
+        )?;
+        tx.execute(
+            "DELETE FROM topic_counters WHERE profile_id = ?1",
+            params![profile.id],
+        )?;
+        tx.execute("DELETE FROM profile_content WHERE profile_id = ?1", params![profile.id])?;
+        tx.execute("DELETE FROM profiles WHERE id = ?1", params![profile.id])?;
+        tx.commit()?;
+        Ok(true)
+    }
+
+    /// Look up an installed pack by name
+    pub fn get_pack(&self, name: &str) -> Result<Option<Pack>> {
+        Ok(self.list_packs()?.into_iter().find(|pack| pack.name == name))
+    }
+
+    /// List installed packs, oldest install first
+    pub fn list_packs(&self) -> Result<Vec<Pack>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT p.id, p.name, p.description, p.version, p.source, p.checksum, p.installed_at,
+                    (SELECT COUNT(*) FROM content c WHERE c.pack_id = p.id)
+             FROM packs p
+             ORDER BY p.id ASC",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok(Pack {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                description: row.get(2)?,
+                version: row.get(3)?,
+                source: row.get(4)?,
+                checksum: row.get(5)?,
+                installed_at: row.get(6)?,
+                content_count: row.get(7)?,
+            })
+        })?;
+
+        rows.collect::<rusqlite::Result<Vec<_>>>().map_err(Into::into)
+    }
+
+    /// Record a newly installed pack, before its content is imported
+    pub fn add_pack(
+        &self,
+        name: &str,
+        description: &str,
+        version: &str,
+        source: &str,
+        checksum: &str,
+    ) -> Result<Pack> {
+        let name = name.trim();
+        if name.is_empty() {
+            anyhow::bail!("Pack name cannot be empty");
+        }
+        if let Some(existing) = self.get_pack(name)? {
+            anyhow::bail!(
+                "Pack '{}' version {} is already installed; remove it first",
+                name,
+                existing.version
+            );
+        }
+
+        self.conn.execute(
+            "INSERT INTO packs (name, description, version, source, checksum, installed_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![name, description, version, source, checksum, chrono::Utc::now().to_rfc3339()],
+        )?;
+        self.get_pack(name)?
+            .ok_or_else(|| anyhow::anyhow!("Pack '{}' was not recorded", name))
+    }
+
+    /// Remove a pack along with the content installed from it, the fun facts taken from
+    /// that content and the interactions recorded against either
+    /// Returns the number of content units removed, or None if no such pack is installed
+    pub fn remove_pack(&self, name: &str) -> Result<Option<usize>> {
+        let Some(pack) = self.get_pack(name)? else {
+            return Ok(None);
+        };
+
+        let tx = self.conn.unchecked_transaction()?;
+        let ids = {
+            let mut stmt = tx.prepare("SELECT id FROM content WHERE pack_id = ?1")?;
+            let rows = stmt.query_map(params![pack.id], |row| row.get::<_, i64>(0))?;
+            rows.collect::<rusqlite::Result<Vec<_>>>()?
+        };
+        let mut removed = 0;
+        for id in ids {
+            removed += delete_content_row(&tx, id)?;
+        }
+        tx.execute("DELETE FROM packs WHERE id = ?1", params![pack.id])?;
+        tx.commit()?;
+
+        self.invalidate_topic_stats();
+        Ok(Some(removed))
+    }
+
+    /// Get every recorded interaction, oldest first, with the content's topic and title
+    pub fn get_interaction_history(&self) -> Result<Vec<InteractionRecord>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT ui.content_id, c.topic, c.title, ui.interaction_type, ui.timestamp, ui.duration_seconds
+             FROM user_interactions ui
+             JOIN content c ON ui.content_id = c.id
+             WHERE ui.profile_id = ?1
+             ORDER BY ui.timestamp ASC"
+        )?;
+
+        let rows = stmt.query_map([self.profile.id], |row| {
+            let topic_str: String = row.get(1)?;
+            Ok(InteractionRecord {
+                content_id: row.get(0)?,
+                // Stored as a JSON string; fall back to the raw value for unknown topics
+                topic: serde_json::from_str::<Topic>(&topic_str)
+                    .map(|t| t.to_string())
+                    .unwrap_or(topic_str),
+                title: row.get(2)?,
+                interaction_type: row.get(3)?,
+                timestamp: row.get(4)?,
+                duration_seconds: row.get(5)?,
+            })
+        })?;
+
+        rows.collect::<rusqlite::Result<Vec<_>>>().map_err(Into::into)
+    }
+
+    /// Count full reads per topic in weekly or monthly buckets of local time
+    /// Every bucket between the first and the most recent read is present, with zeros
+    /// where nothing was read, so the series can be drawn on a continuous axis
+    pub fn get_topic_trend(&self, bucket: TrendBucket) -> Result<TopicTrend> {
+        // Reads stamped in the future would stretch the axis with empty buckets up to them
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT c.topic, ui.timestamp
+             FROM user_interactions ui
+             JOIN content c ON ui.content_id = c.id
+             WHERE ui.interaction_type = 'fully_read' AND ui.profile_id = ?1 AND ui.timestamp <= ?2 AND {}",
+            LIVE_CONTENT
+        ))?;
+        let rows = stmt.query_map(params![self.profile.id, latest_trusted_timestamp()], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?;
+
+        // (topic, bucket start) for every read
+        let mut reads = Vec::new();
+        for row_result in rows {
+            let (topic_str, timestamp) = row_result?;
+            let (Ok(topic), Ok(time)) = (
+                serde_json::from_str::<Topic>(&topic_str),
+                chrono::DateTime::parse_from_rfc3339(&timestamp),
+            ) else {
+                continue;
+            };
+            let local_date = time.with_timezone(&chrono::Local).date_naive();
+            reads.push((topic, bucket.start_of(local_date)));
+        }
+
+        let (Some(first), Some(last)) = (
+            reads.iter().map(|(_, start)| *start).min(),
+            reads.iter().map(|(_, start)| *start).max(),
+        ) else {
+            return Ok(TopicTrend {
+                bucket,
+                ..Default::default()
+            });
+        };
+
+        let mut starts = Vec::new();
+        let mut start = first;
+        while start <= last {
+            starts.push(start);
+            start = bucket.next(start);
+        }
+        let index: HashMap<chrono::NaiveDate, usize> =
+            starts.iter().enumerate().map(|(i, start)| (*start, i)).collect();
+
+        let mut counts: HashMap<Topic, Vec<u64>> = HashMap::new();
+        for (topic, start) in reads {
+            let series = counts.entry(topic).or_insert_with(|| vec![0; starts.len()]);
+            series[index[&start]] += 1;
+        }
+
+        Ok(TopicTrend {
+            bucket,
+            labels: starts.iter().map(|start| bucket.label(*start)).collect(),
+            topics: Topic::all()
+                .iter()
+                .filter_map(|topic| counts.remove(topic).map(|series| (*topic, series)))
+                .collect(),
+        })
+    }
+
+    /// Count full reads per local day of `year`, for the activity calendar
+    pub fn get_activity_calendar(&self, year: i32) -> Result<ActivityCalendar> {
+        use chrono::Datelike;
+        let days_in_year = chrono::NaiveDate::from_ymd_opt(year, 12, 31)
+            .ok_or_else(|| anyhow::anyhow!("Year {} is out of range", year))?
+            .ordinal() as usize;
+
+        let mut stmt = self.conn.prepare(
+            "SELECT timestamp FROM user_interactions
+             WHERE interaction_type = 'fully_read' AND profile_id = ?1",
+        )?;
+        let rows = stmt.query_map([self.profile.id], |row| row.get::<_, String>(0))?;
+
+        let mut days = vec![0; days_in_year];
+        for row_result in rows {
+            let Ok(time) = chrono::DateTime::parse_from_rfc3339(&row_result?) else {
+                continue;
+            };
+            let local_date = time.with_timezone(&chrono::Local).date_naive();
+            if local_date.year() == year {
+                days[local_date.ordinal0() as usize] += 1;
+            }
+        }
+
+        Ok(ActivityCalendar { year, days })
+    }
+
+    /// Aggregate interactions per calendar month in local time, oldest month first
+    pub fn get_monthly_summary(&self) -> Result<Vec<MonthlySummary>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT strftime('%Y-%m', timestamp, 'localtime') AS month,
+                    SUM(CASE WHEN interaction_type = 'fully_read' THEN 1 ELSE 0 END),
+                    SUM(CASE WHEN interaction_type IN ('skipped', 'dismissed') THEN 1 ELSE 0 END),
+                    SUM(duration_seconds)
+             FROM user_interactions
+             WHERE profile_id = ?1
+             GROUP BY month
+             ORDER BY month ASC"
+        )?;
+
+        let rows = stmt.query_map([self.profile.id], |row| {
+            Ok(MonthlySummary {
+                month: row.get(0)?,
+                fully_read: row.get(1)?,
+                skipped: row.get(2)?,
+                reading_seconds: row.get(3)?,
+            })
+        })?;
+
+        rows.collect::<rusqlite::Result<Vec<_>>>().map_err(Into::into)
+    }
+
+    /// Count interactions, across all profiles, whose content no longer exists
+    /// These come from older builds that recorded interactions without checking the id
+    pub fn count_orphaned_interactions(&self) -> Result<i64> {
+        let count = self.conn.query_row(
+            "SELECT COUNT(*) FROM user_interactions ui
+             WHERE NOT EXISTS (SELECT 1 FROM content c WHERE c.id = ui.content_id)",
+            [],
+            |row| row.get::<_, i64>(0),
+        )?;
+        Ok(count)
+    }
+
+    /// Topic labels stored on content that no longer name a `Topic`, with how many units
+    /// carry each. They come from builds whose topic variants have since been renamed
+    pub fn get_unknown_topic_labels(&self) -> Result<Vec<(String, i64)>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT topic, COUNT(*) FROM content GROUP BY topic ORDER BY topic")?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+        })?;
+
+        let mut unknown = Vec::new();
+        for row_result in rows {
+            let (stored, count) = row_result?;
+            if serde_json::from_str::<Topic>(&stored).is_err() {
+                // Stored as a JSON string; anything else is reported as it is
+                let label = serde_json::from_str::<String>(&stored).unwrap_or(stored);
+                unknown.push((label, count));
+            }
+        }
+        Ok(unknown)
+    }
+
+    /// Move content stored under old topic names to the current serialization of `Topic`
+    /// Each `(old, new)` pair rewrites content (soft-deleted included) and aggregate topic
+    /// counters from the variant once serialized as `old` to `new`, all in one transaction.
+    /// The content hash covers the topic, so it is recomputed; a unit that turns out to
+    /// match stored content is folded into it. Returns the number of content units changed
+    pub fn remap_topics(&self, mapping: &[(&str, Topic)]) -> Result<usize> {
+        let tx = self.conn.unchecked_transaction()?;
+        let mut changed = 0;
+
+        for (old, new) in mapping {
+            let old_str = serde_json::to_string(old)?;
+            let new_str = serde_json::to_string(new)?;
+            if old_str == new_str {
+                continue;
+            }
+
+            let remapped = {
+                let mut stmt = tx.prepare("SELECT id, title, content FROM content WHERE topic IN (?1, ?2)")?;
+                let rows = stmt.query_map(params![old_str, old], |row| {
+                    Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?))
+                })?;
+                rows.collect::<rusqlite::Result<Vec<_>>>()?
+            };
+            for (id, title, text) in remapped {
+                let hash = ContentUnit::new(*new, title, text, String::new()).content_hash();
+                let existing: Option<i64> = tx
+                    .query_row(
+                        "SELECT id FROM content WHERE content_hash = ?1 AND id != ?2",
+                        params![hash, id],
+                        |row| row.get(0),
+                    )
+                    .optional()?;
+                match existing {
+                    Some(keep) => fold_content(&tx, id, keep)?,
+                    None => {
+                        tx.execute(
+                            "UPDATE content SET topic = ?1, content_hash = ?2 WHERE id = ?3",
+                            params![new_str, hash, id],
+                        )?;
+                    }
+                }
+                changed += 1;
+            }
+
+            // Counters are keyed by topic, so fold the old row into any existing new one
+            tx.execute(
+                "INSERT INTO topic_counters (profile_id, topic, fully_read, skipped, dismissed)
+                 SELECT profile_id, ?1, fully_read, skipped, dismissed
+                 FROM topic_counters WHERE topic IN (?2, ?3)
+                 ON CONFLICT (profile_id, topic) DO UPDATE SET
+                     fully_read = fully_read + excluded.fully_read,
+                     skipped = skipped + excluded.skipped,
+                     dismissed = dismissed + excluded.dismissed",
+                params![new_str, old_str, old],
+            )?;
+            tx.execute(
+                "DELETE FROM topic_counters WHERE topic IN (?1, ?2)",
+                params![old_str, old],
+            )?;
+        }
+
+        tx.commit()?;
+        self.invalidate_topic_stats();
+        Ok(changed)
+    }
+
+    /// Pin content for the active profile so it comes up again now and then (see
+    /// `pinned_probability`)
+    pub fn pin_content(&self, content_id: i64) -> Result<()> {
+        self.set_pinned(content_id, true)
+    }
+
+    /// Stop bringing content back on purpose; it is still picked like any other
+    pub fn unpin_content(&self, content_id: i64) -> Result<()> {
+        self.set_pinned(content_id, false)
+    }
+
+    fn set_pinned(&self, content_id: i64, pinned: bool) -> Result<()> {
+        if !self.content_exists(content_id)? {
+            return Err(ContentNotFound(content_id).into());
+        }
+        self.conn.execute(
+            "INSERT INTO profile_content (profile_id, content_id, pinned) VALUES (?1, ?2, ?3)
+             ON CONFLICT (profile_id, content_id) DO UPDATE SET pinned = ?3",
+            params![self.profile.id, content_id, pinned],
+        )?;
+        Ok(())
+    }
+
+    /// Whether the active profile pinned content
+    pub fn is_pinned(&self, content_id: i64) -> Result<bool> {
+        if !self.content_exists(content_id)? {
+            return Err(ContentNotFound(content_id).into());
+        }
+        let pinned = self
+            .conn
+            .query_row(
+                "SELECT pinned FROM profile_content WHERE profile_id = ?1 AND content_id = ?2",
+                params![self.profile.id, content_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(pinned.unwrap_or(false))
+    }
+
+    /// Whether a content row exists, live or not
+    fn content_exists(&self, content_id: i64) -> Result<bool> {
+        let exists = self
+            .conn
+            .query_row("SELECT 1 FROM content WHERE id = ?1", params![content_id], |_| Ok(()))
+            .optional()?;
+        Ok(exists.is_some())
+    }
+
+    /// Every live content unit the active profile pinned, by title
+    pub fn get_pinned(&self) -> Result<Vec<ContentUnit>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {}
+             FROM content
+             JOIN profile_content pc ON pc.content_id = content.id AND pc.profile_id = ?1
+             WHERE pc.pinned = 1 AND {}
+             ORDER BY title ASC",
+            CONTENT_COLUMNS,
+            LIVE_CONTENT
+        ))?;
+        let rows = stmt.query_map(params![self.profile.id], |row| self.row_to_content_unit(row))?;
+
+        rows.collect::<rusqlite::Result<Vec<_>>>().map_err(Into::into)
+    }
+
+    /// Save content as a favourite; favourites are pins, so saving it again changes nothing
+    pub fn add_favorite(&self, content_id: i64) -> Result<()> {
+        self.pin_content(content_id)
+    }
+
+    /// Drop content from the active profile's favourites
+    pub fn remove_favorite(&self, content_id: i64) -> Result<()> {
+        self.unpin_content(content_id)
+    }
+
+    /// Whether content is one of the active profile's favourites
+    pub fn is_favorite(&self, content_id: i64) -> Result<bool> {
+        self.is_pinned(content_id)
+    }
+
+    /// The active profile's live favourites by title, a page at a time
+    pub fn list_favorites(&self, limit: usize, offset: usize) -> Result<Vec<ContentUnit>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {}
+             FROM content
+             JOIN profile_content pc ON pc.content_id = content.id AND pc.profile_id = ?1
+             WHERE pc.pinned = 1 AND {}
+             ORDER BY title ASC, content.id ASC
+             LIMIT ?2 OFFSET ?3",
+            CONTENT_COLUMNS,
+            LIVE_CONTENT
+        ))?;
+        let rows = stmt.query_map(params![self.profile.id, limit as i64, offset as i64], |row| {
+            self.row_to_content_unit(row)
+        })?;
+
+        rows.collect::<rusqlite::Result<Vec<_>>>().map_err(Into::into)
+    }
+
+    /// Bookmark content to revisit it; like favourites, bookmarks are the active profile's pins
+    pub fn add_bookmark(&self, content_id: i64) -> Result<()> {
+        self.pin_content(content_id)
+    }
+
+    /// Remove the active profile's bookmark on content
+    pub fn remove_bookmark(&self, content_id: i64) -> Result<()> {
+        self.unpin_content(content_id)
+    }
+
+    /// Whether the active profile bookmarked content
+    pub fn is_bookmarked(&self, content_id: i64) -> Result<bool> {
+        self.is_pinned(content_id)
+    }
+
+    /// Every live content unit the active profile bookmarked, by title
+    pub fn get_bookmarks(&self) -> Result<Vec<ContentUnit>> {
+        self.get_pinned()
+    }
+
+    /// Note that a content unit was just shown to the user
+    /// In aggregate privacy mode nothing is written; the content is kept out of the rest
+    /// of the session instead
+    pub fn mark_shown(&self, content_id: i64) -> Result<()> {
+        if self.privacy == PrivacyMode::Aggregate {
+            self.session_suppressed.borrow_mut().insert(content_id);
+            return Ok(());
+        }
+        let now = chrono::Utc::now().to_rfc3339();
+        self.conn.execute(
+            "UPDATE content SET times_shown = times_shown + 1 WHERE id = ?1",
+            params![content_id],
+        )?;
+        self.conn.execute(
+            "INSERT INTO profile_content (profile_id, content_id, last_shown) VALUES (?1, ?2, ?3)
+             ON CONFLICT (profile_id, content_id) DO UPDATE SET last_shown = ?3",
+            params![self.profile.id, content_id, now],
+        )?;
+        Ok(())
+    }
+
+    /// Get the number of content units that have never been shown
+    pub fn get_unseen_count(&self) -> Result<i64> {
+        let count = self.conn.query_row(
+            &format!("SELECT COUNT(*) FROM content WHERE times_shown = 0 AND {}", LIVE_CONTENT),
+            [],
+            |row| row.get::<_, i64>(0),
+        )?;
+        Ok(count)
+    }
+
+    /// Recompute derived columns for rows where they are NULL
+    /// Rows are updated in batches, one transaction per batch, with progress printed as
+    /// each batch commits. Returns the number of rows filled in for each column.
+    pub fn backfill(&self, columns: &[BackfillColumn]) -> Result<Vec<(BackfillColumn, usize)>> {
+        let mut filled = Vec::with_capacity(columns.len());
+
+        for &column in columns {
+            let name = column.column_name();
+            let missing: usize = self.conn.query_row(
+                &format!("SELECT COUNT(*) FROM content WHERE {} IS NULL", name),
+                [],
+                |row| row.get(0),
+            )?;
+
+            let mut done = 0;
+            while done < missing {
+                let batch = {
+                    let mut stmt = self.conn.prepare(&format!(
+                        "SELECT {}
+                         FROM content
+                         WHERE {} IS NULL
+                         LIMIT ?1",
+                        CONTENT_COLUMNS,
+                        name
+                    ))?;
+                    let rows = stmt.query_map(params![BACKFILL_BATCH_SIZE], |row| {
+                        self.row_to_content_unit(row)
+                    })?;
+                    rows.collect::<rusqlite::Result<Vec<_>>>()?
+                };
+                if batch.is_empty() {
+                    break;
+                }
+
+                let tx = self.conn.unchecked_transaction()?;
+                for unit in &batch {
+                    tx.execute(
+                        &format!("UPDATE content SET {} = ?1 WHERE id = ?2", name),
+                        params![column.compute(unit, self.sensitivity_scorer()), unit.id],
+                    )?;
+                }
+                tx.commit()?;
+
+                done += batch.len();
+                println!("  {}: {}/{}", name, done.min(missing), missing);
+            }
+
+            filled.push((column, done));
+        }
+
+        Ok(filled)
+    }
+
+    /// Treat every unseen content unit as already shown once, to the active profile
+    /// Read/skip preference data is left untouched. Returns the number of rows changed.
+    pub fn mark_all_seen(&self) -> Result<usize> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
+            &format!(
+                "INSERT INTO profile_content (profile_id, content_id, last_shown)
+                 SELECT ?1, id, ?2 FROM content WHERE times_shown = 0 AND {}
+                 ON CONFLICT (profile_id, content_id) DO UPDATE SET last_shown = COALESCE(last_shown, ?2)",
+                LIVE_CONTENT
+            ),
+            params![self.profile.id, chrono::Utc::now().to_rfc3339()],
+        )?;
+        let changed = tx.execute(
+            &format!("UPDATE content SET times_shown = 1 WHERE times_shown = 0 AND {}", LIVE_CONTENT),
+            [],
+        )?;
+        tx.commit()?;
+        Ok(changed)
+    }
+
+    /// Forget which content has been shown so everything counts as new again
+    /// Read/skip preference data is left untouched. Returns the number of rows changed.
+    pub fn forget_seen(&self) -> Result<usize> {
+        let tx = self.conn.unchecked_transaction()?;
+        let changed = tx.execute("UPDATE content SET times_shown = 0 WHERE times_shown > 0", [])?;
+        tx.execute("UPDATE profile_content SET last_shown = NULL", [])?;
+        tx.execute("DELETE FROM profile_content WHERE pinned = 0", [])?;
+        tx.commit()?;
+        Ok(changed)
+    }
+}
+
+/// Sort content for reading a topic in order: articles by when they were added, the parts
+/// of each article in sequence, and better-scored pieces first where that leaves a tie
+/// Each candidate comes with its quality score, which content stored long ago may lack
+fn guided_order(candidates: &mut [(ContentUnit, Option<i32>)]) {
+    // Parts of one article are stored moments apart, so they are grouped by their source
+    // and placed by the earliest of them
+    let mut added: HashMap<String, chrono::DateTime<chrono::Utc>> = HashMap::new();
+    for (content, _) in candidates.iter() {
+        added
+            .entry(content.source_url.clone())
+            .and_modify(|at| *at = (*at).min(content.created_at))
+            .or_insert(content.created_at);
+    }
+
+    candidates.sort_by_cached_key(|(content, quality)| {
+        (
+            added[&content.source_url],
+            content.source_url.clone(),
+            content.part_index.unwrap_or(0),
+            std::cmp::Reverse(*quality),
+        )
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An in-memory database with one story per entry, returning their ids
+    fn library(stories: &[(Topic, &str)]) -> (Database, Vec<i64>) {
+        let db = Database::new_in_memory().unwrap();
+        let ids = stories
+            .iter()
+            .map(|&(topic, title)| add_story(&db, topic, title, &format!("The story of {}.", title)))
+            .collect();
+        (db, ids)
+    }
+
+    fn add_story(db: &Database, topic: Topic, title: &str, text: &str) -> i64 {
+        let mut unit = ContentUnit::new(topic, title.to_string(), text.to_string(), String::new());
+        db.insert_content(&mut unit).unwrap();
+        unit.id
+    }
+
+    /// Whether the row for `id` still exists, deleted or not
+    fn is_stored(db: &Database, id: i64) -> bool {
+        db.conn
+            .query_row("SELECT COUNT(*) FROM content WHERE id = ?1", [id], |row| row.get::<_, i64>(0))
+            .unwrap()
+            > 0
+    }
+
+    /// Ids served by `n` picks in a row
+    fn picks(db: &Database, n: usize) -> HashSet<i64> {
+        (0..n)
+            .filter_map(|_| db.get_weighted_random_content().unwrap())
+            .map(|content| content.id)
+            .collect()
+    }
+
+    #[test]
+    fn deleted_content_is_never_selected_and_undo_brings_it_back() {
+        let (db, ids) = library(&[(Topic::AncientEgypt, "Giza"), (Topic::AncientEgypt, "Karnak")]);
+        assert!(db.delete_content(ids[0]).unwrap());
+        assert_eq!(picks(&db, 50), HashSet::from([ids[1]]));
+        assert_eq!(db.get_content_count().unwrap(), 1);
+
+        assert!(db.restore_content(ids[0]).unwrap());
+        assert!(picks(&db, 100).contains(&ids[0]));
+    }
+
+    #[test]
+    fn purge_removes_only_content_deleted_before_the_retention_period() {
+        let (db, ids) = library(&[(Topic::AncientEgypt, "Giza"), (Topic::AncientEgypt, "Karnak")]);
+        db.delete_content(ids[0]).unwrap();
+        db.delete_content(ids[1]).unwrap();
+        let long_ago = (chrono::Utc::now() - chrono::Duration::days(SOFT_DELETE_RETENTION_DAYS + 1)).to_rfc3339();
+        db.conn.execute("UPDATE content SET deleted_at = ?1 WHERE id = ?2", params![long_ago, ids[0]]).unwrap();
+
+        let purged = db.purge_deleted_content(chrono::Duration::days(SOFT_DELETE_RETENTION_DAYS)).unwrap();
+        assert_eq!(purged, 1);
+        assert_eq!(db.get_stored_content_count().unwrap(), 1);
+        assert!(db.restore_content(ids[1]).unwrap());
+    }
+
+    #[test]
+    fn deleted_content_no_longer_shapes_preferences_or_recent_topics() {
+        let (db, ids) = library(&[(Topic::AncientEgypt, "Giza"), (Topic::AncientRome, "Forum")]);
+        db.record_interaction(&UserInteraction::fully_read(ids[0], 60)).unwrap();
+        assert!(db.get_topic_preferences().unwrap().contains_key(&Topic::AncientEgypt));
+
+        db.delete_content(ids[0]).unwrap();
+        assert!(!db.get_topic_preferences().unwrap().contains_key(&Topic::AncientEgypt));
+        assert!(db.get_recent_topics(5).unwrap().is_empty());
+    }
+
+    /// A skip of `content_id` recorded `hours_ago`
+    fn skip_from(content_id: i64, hours_ago: i64) -> UserInteraction {
+        UserInteraction::Skipped {
+            content_id,
+            timestamp: chrono::Utc::now() - chrono::Duration::hours(hours_ago),
+            skip_time_seconds: 2,
+            displayed_chars: None,
+            percent_displayed: None,
+        }
+    }
+
+    #[test]
+    fn skipped_content_comes_back_after_the_cooldown() {
+        let (db, ids) = library(&[(Topic::AncientEgypt, "Giza"), (Topic::AncientEgypt, "Karnak")]);
+        let db = db.with_recommendation(RecommendationConfig {
+            skip_cooldown_hours: 24,
+            ..RecommendationConfig::default()
+        });
+
+        db.record_interaction(&skip_from(ids[0], 1)).unwrap();
+        assert_eq!(picks(&db, 50), HashSet::from([ids[1]]));
+
+        db.conn.execute("DELETE FROM user_interactions", []).unwrap();
+        db.record_interaction(&skip_from(ids[0], 25)).unwrap();
+        assert!(picks(&db, 100).contains(&ids[0]));
+    }
+
+    #[test]
+    fn marking_everything_seen_ends_the_unseen_first_preference() {
+        let (db, ids) = library(&[(Topic::AncientEgypt, "Giza"), (Topic::AncientEgypt, "Karnak")]);
+        db.mark_shown(ids[0]).unwrap();
+        assert_eq!(picks(&db, 50), HashSet::from([ids[1]]));
+
+        assert_eq!(db.mark_all_seen().unwrap(), 1);
+        assert_eq!(db.get_unseen_count().unwrap(), 0);
+        assert_eq!(picks(&db, 100), HashSet::from([ids[0], ids[1]]));
+        assert_eq!(db.mark_all_seen().unwrap(), 0, "nothing left to mark");
+    }
+
+    /// `db` serving stories again right after they were read
+    fn without_read_exclusion(db: Database) -> Database {
+        db.with_recommendation(RecommendationConfig { exclude_read_within_days: 0, ..RecommendationConfig::default() })
+    }
+
+    #[test]
+    fn forgetting_seen_content_makes_it_new_again_but_keeps_preferences() {
+        let (db, ids) = library(&[(Topic::AncientEgypt, "Giza"), (Topic::AncientEgypt, "Karnak")]);
+        let db = without_read_exclusion(db);
+        db.mark_shown(ids[0]).unwrap();
+        db.mark_shown(ids[1]).unwrap();
+        db.record_interaction(&UserInteraction::fully_read(ids[1], 60)).unwrap();
+        assert_eq!(db.get_unseen_count().unwrap(), 0);
+
+        assert_eq!(db.forget_seen().unwrap(), 2);
+        assert_eq!(db.get_unseen_count().unwrap(), 2);
+        assert_eq!(picks(&db, 100), HashSet::from([ids[0], ids[1]]));
+        assert!(db.get_topic_preferences().unwrap().contains_key(&Topic::AncientEgypt));
+
+        db.mark_shown(ids[1]).unwrap();
+        assert_eq!(picks(&db, 50), HashSet::from([ids[0]]));
+    }
+
+    #[test]
+    fn profiles_keep_separate_preferences_and_reading_stats() {
+        use chrono::Datelike;
+        let (db, ids) = library(&[(Topic::AncientEgypt, "Giza"), (Topic::AncientRome, "Forum")]);
+        db.create_profile("sam").unwrap();
+        let year = chrono::Local::now().year();
+        let reads = |db: &Database| db.get_activity_calendar(year).unwrap().days.iter().sum::<u64>();
+
+        db.record_interaction(&UserInteraction::fully_read(ids[0], 60)).unwrap();
+        let db = db.with_profile("sam").unwrap();
+        assert!(db.get_topic_preferences().unwrap().is_empty());
+        assert!(db.get_monthly_summary().unwrap().is_empty());
+        assert_eq!(reads(&db), 0);
+
+        db.record_interaction(&UserInteraction::fully_read(ids[1], 60)).unwrap();
+        let preferences = db.get_topic_preferences().unwrap();
+        assert!(preferences.contains_key(&Topic::AncientRome));
+        assert!(!preferences.contains_key(&Topic::AncientEgypt));
+
+        let db = db.with_profile(DEFAULT_PROFILE).unwrap();
+        let preferences = db.get_topic_preferences().unwrap();
+        assert!(preferences.contains_key(&Topic::AncientEgypt));
+        assert!(!preferences.contains_key(&Topic::AncientRome));
+        assert_eq!(reads(&db), 1);
+        assert_eq!(db.get_monthly_summary().unwrap()[0].fully_read, 1);
+    }
+
+    #[test]
+    fn size_limit_evicts_only_content_nobody_has_touched() {
+        let (db, ids) = library(&[
+            (Topic::AncientEgypt, "Giza"),
+            (Topic::AncientEgypt, "Karnak"),
+            (Topic::AncientRome, "Forum"),
+            (Topic::AncientRome, "Ostia"),
+        ]);
+        db.record_interaction(&UserInteraction::skipped(ids[0], 5)).unwrap();
+        db.pin_content(ids[1]).unwrap();
+
+        assert_eq!(db.enforce_size_limit(1).unwrap(), 2);
+        assert!(is_stored(&db, ids[0]) && is_stored(&db, ids[1]));
+        assert_eq!(db.get_stored_content_count().unwrap(), 2);
+    }
+
+    #[test]
+    fn size_limit_keeps_deletions_that_can_still_be_undone() {
+        let (db, ids) = library(&[
+            (Topic::AncientEgypt, "Giza"),
+            (Topic::AncientEgypt, "Karnak"),
+            (Topic::AncientRome, "Forum"),
+        ]);
+        db.record_interaction(&UserInteraction::fully_read(ids[2], 60)).unwrap();
+        db.delete_content(ids[0]).unwrap();
+        db.delete_content(ids[1]).unwrap();
+        let long_ago = (chrono::Utc::now() - chrono::Duration::days(SOFT_DELETE_RETENTION_DAYS + 1)).to_rfc3339();
+        db.conn.execute("UPDATE content SET deleted_at = ?1 WHERE id = ?2", params![long_ago, ids[1]]).unwrap();
+
+        assert_eq!(db.enforce_size_limit(1).unwrap(), 1);
+        assert!(db.restore_content(ids[0]).unwrap());
+        assert!(!db.restore_content(ids[1]).unwrap());
+    }
+
+    #[test]
+    fn inserting_past_the_limit_fails_when_nothing_can_be_evicted() {
+        let (db, ids) = library(&[(Topic::AncientEgypt, "Giza")]);
+        let db = db.with_storage(StorageConfig {
+            max_content_units: Some(1),
+            ..StorageConfig::default()
+        });
+        db.record_interaction(&UserInteraction::fully_read(ids[0], 60)).unwrap();
+
+        let mut unit = ContentUnit::new(Topic::AncientRome, "Forum".to_string(), "The Forum.".to_string(), String::new());
+        assert!(db.insert_content(&mut unit).is_err());
+        assert_eq!(db.get_stored_content_count().unwrap(), 1);
+    }
+
+    #[test]
+    fn interactions_for_unknown_content_are_rejected_without_a_row() {
+        let (db, ids) = library(&[(Topic::AncientEgypt, "Giza")]);
+        let missing = ids[0] + 100;
+
+        let err = db.record_interaction(&UserInteraction::fully_read(missing, 60)).unwrap_err();
+        assert_eq!(err.downcast_ref::<ContentNotFound>(), Some(&ContentNotFound(missing)));
+        let rows: i64 = db.conn.query_row("SELECT COUNT(*) FROM user_interactions", [], |row| row.get(0)).unwrap();
+        assert_eq!(rows, 0);
+
+        // Deleted content can still be undone, so it still takes interactions
+        db.delete_content(ids[0]).unwrap();
+        db.record_interaction(&UserInteraction::skipped(ids[0], 5)).unwrap();
+    }
+
+    #[test]
+    fn orphaned_interactions_from_older_builds_are_counted() {
+        let (db, ids) = library(&[(Topic::AncientEgypt, "Giza")]);
+        db.record_interaction(&UserInteraction::fully_read(ids[0], 60)).unwrap();
+        assert_eq!(db.count_orphaned_interactions().unwrap(), 0);
+
+        // Older builds ran without foreign key enforcement
+        db.conn.pragma_update(None, "foreign_keys", false).unwrap();
+        db.conn
+            .execute(
+                "INSERT INTO user_interactions (content_id, interaction_type, timestamp, duration_seconds)
+                 VALUES (?1, 'skipped', ?2, 3)",
+                params![ids[0] + 100, chrono::Utc::now().to_rfc3339()],
+            )
+            .unwrap();
+        assert_eq!(db.count_orphaned_interactions().unwrap(), 1);
+    }
+
+    #[test]
+    fn content_page_filters_match_every_combination() {
+        let db = Database::new_in_memory().unwrap();
+        let words = |n: usize| vec!["word"; n].join(" ");
+        let stories = [
+            (Topic::AncientEgypt, "Giza", 50, Some(true)),
+            (Topic::AncientRome, "Forum", 500, None),
+            (Topic::AncientEgypt, "Karnak", 1200, Some(false)),
+            (Topic::AncientRome, "Ostia", 399, None),
+        ];
+        let ids: Vec<i64> = stories
+            .iter()
+            .map(|&(topic, title, count, read)| {
+                let id = add_story(&db, topic, title, &words(count));
+                match read {
+                    Some(true) => db.record_interaction(&UserInteraction::fully_read(id, 60)).unwrap(),
+                    Some(false) => db.record_interaction(&UserInteraction::skipped(id, 5)).unwrap(),
+                    None => {}
+                }
+                id
+            })
+            .collect();
+        let deleted = add_story(&db, Topic::AncientEgypt, "Abydos", &words(50));
+        db.delete_content(deleted).unwrap();
+
+        let mut empty_pages = 0;
+        for topic in [None, Some(Topic::AncientEgypt), Some(Topic::AncientRome)] {
+            for length in [None, Some(LengthBucket::Short), Some(LengthBucket::Medium), Some(LengthBucket::Long)] {
+                for has_interactions in [None, Some(true), Some(false)] {
+                    let mut filter = ContentFilter::default().with_topic(topic);
+                    if let Some(length) = length {
+                        filter = filter.with_length(length);
+                    }
+                    if let Some(has_interactions) = has_interactions {
+                        filter = filter.with_interactions(has_interactions);
+                    }
+
+                    let (min, max) = length.map_or((None, None), LengthBucket::word_range);
+                    let expected: HashSet<i64> = stories
+                        .iter()
+                        .zip(&ids)
+                        .filter(|((story_topic, _, count, read), _)| {
+                            topic.is_none_or(|t| t == *story_topic)
+                                && min.is_none_or(|min| *count >= min)
+                                && max.is_none_or(|max| *count < max)
+                                && has_interactions.is_none_or(|has| has == read.is_some())
+                        })
+                        .map(|(_, id)| *id)
+                        .collect();
+                    let page: HashSet<i64> =
+                        db.get_content_page(&filter, 0, 10).unwrap().iter().map(|preview| preview.id).collect();
+
+                    assert_eq!(page, expected, "{:?}", filter);
+                    empty_pages += usize::from(page.is_empty());
+                }
+            }
+        }
+        assert!(empty_pages > 0, "some combinations should match nothing");
+    }
+
+    #[test]
+    fn identical_content_hashes_the_same_in_every_database() {
+        let (first, _) = library(&[(Topic::AncientEgypt, "Giza")]);
+        let (second, _) = library(&[(Topic::AncientRome, "Forum"), (Topic::AncientEgypt, "Giza")]);
+        let unit = ContentUnit::new(Topic::AncientEgypt, "Giza".to_string(), "The story  of\nGiza.".to_string(), String::new());
+
+        // Keyed by the topic's stored name and the whitespace-normalized text
+        let hash = unit.content_hash();
+        assert_eq!(hash, crate::sha256_hex(b"AncientEgypt\nGiza\nThe story of Giza."));
+        let found = |db: &Database| db.get_content_by_hash(&hash).unwrap().map(|content| content.title);
+        assert_eq!(found(&first).as_deref(), Some("Giza"));
+        assert_eq!(found(&second).as_deref(), Some("Giza"));
+    }
+
+    #[test]
+    fn remapped_topics_get_new_hashes_and_fold_into_matching_content() {
+        let (db, ids) = library(&[(Topic::AncientEgypt, "Giza"), (Topic::AncientEgypt, "Forum"), (Topic::AncientRome, "Forum")]);
+        db.conn.execute("UPDATE content SET topic = '\"OldEgypt\"' WHERE id IN (?1, ?2)", params![ids[0], ids[1]]).unwrap();
+        db.record_interaction(&UserInteraction::fully_read(ids[1], 60)).unwrap();
+
+        assert_eq!(db.remap_topics(&[("OldEgypt", Topic::AncientRome)]).unwrap(), 2);
+
+        let moved = ContentUnit::new(Topic::AncientRome, "Giza".to_string(), "The story of Giza.".to_string(), String::new());
+        assert_eq!(db.get_content_by_hash(&moved.content_hash()).unwrap().map(|c| c.id), Some(ids[0]));
+
+        // The remapped Forum is now the same story as the Rome one, history and all
+        assert!(!is_stored(&db, ids[1]));
+        assert_eq!(db.get_stored_content_count().unwrap(), 2);
+        assert!(db.get_topic_preferences().unwrap().contains_key(&Topic::AncientRome));
+    }
+
+    #[test]
+    fn pins_and_last_shown_times_belong_to_each_profile() {
+        let (db, ids) = library(&[(Topic::AncientEgypt, "Giza"), (Topic::AncientRome, "Forum")]);
+        db.create_profile("sam").unwrap();
+        db.pin_content(ids[0]).unwrap();
+        db.mark_shown(ids[0]).unwrap();
+        // Just shown to this profile, so not served again straight away
+        assert!(db.get_next_pinned().unwrap().is_none());
+
+        let db = db.with_profile("sam").unwrap();
+        assert!(!db.is_pinned(ids[0]).unwrap());
+        assert!(db.get_pinned().unwrap().is_empty());
+        db.pin_content(ids[0]).unwrap();
+        db.pin_content(ids[1]).unwrap();
+        db.mark_shown(ids[1]).unwrap();
+        assert_eq!(db.get_next_pinned().unwrap().map(|c| c.id), Some(ids[0]));
+
+        let db = db.with_profile(DEFAULT_PROFILE).unwrap();
+        assert_eq!(db.get_pinned().unwrap().iter().map(|c| c.id).collect::<Vec<_>>(), vec![ids[0]]);
+        db.unpin_content(ids[0]).unwrap();
+        assert!(db.with_profile("sam").unwrap().is_pinned(ids[0]).unwrap());
+    }
+
+    #[test]
+    fn favourites_are_pins_listed_a_page_at_a_time_with_one_row_each() {
+        let (db, ids) = library(&[
+            (Topic::AncientEgypt, "Giza"),
+            (Topic::AncientRome, "Forum"),
+            (Topic::Medieval, "Abbey"),
+            (Topic::Renaissance, "Dome"),
+        ]);
+        let rows = |id: i64| {
+            db.conn
+                .query_row("SELECT COUNT(*) FROM profile_content WHERE content_id = ?1", [id], |row| {
+                    row.get::<_, i64>(0)
+                })
+                .unwrap()
+        };
+
+        // Toggling twice each way leaves a single row behind
+        db.add_favorite(ids[0]).unwrap();
+        db.add_favorite(ids[0]).unwrap();
+        db.remove_favorite(ids[0]).unwrap();
+        db.remove_favorite(ids[0]).unwrap();
+        assert!(!db.is_favorite(ids[0]).unwrap());
+        db.add_favorite(ids[0]).unwrap();
+        assert!(db.is_favorite(ids[0]).unwrap() && db.is_pinned(ids[0]).unwrap());
+        assert_eq!(rows(ids[0]), 1);
+
+        for &id in &ids[1..] {
+            db.add_favorite(id).unwrap();
+        }
+        let page = |limit, offset| {
+            db.list_favorites(limit, offset)
+                .unwrap()
+                .into_iter()
+                .map(|c| c.title)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(page(2, 0), ["Abbey", "Dome"]);
+        assert_eq!(page(2, 2), ["Forum", "Giza"]);
+        assert_eq!(page(10, 3), ["Giza"]);
+        assert!(page(0, 0).is_empty());
+        assert!(page(2, 4).is_empty());
+        assert_eq!(db.add_favorite(-1).unwrap_err().downcast_ref(), Some(&ContentNotFound(-1)));
+    }
+
+    #[test]
+    fn bookmarks_are_the_profiles_pins() {
+        let (db, ids) = library(&[(Topic::AncientEgypt, "Giza"), (Topic::AncientRome, "Forum")]);
+        db.add_bookmark(ids[0]).unwrap();
+        db.pin_content(ids[1]).unwrap();
+        assert!(db.is_bookmarked(ids[0]).unwrap() && db.is_pinned(ids[0]).unwrap());
+        let titles = |db: &Database| db.get_bookmarks().unwrap().into_iter().map(|c| c.title).collect::<Vec<_>>();
+        assert_eq!(titles(&db), ["Forum", "Giza"]);
+
+        db.remove_bookmark(ids[1]).unwrap();
+        assert!(!db.is_pinned(ids[1]).unwrap());
+        assert_eq!(titles(&db), ["Giza"]);
+        db.delete_content(ids[0]).unwrap();
+        assert!(titles(&db).is_empty());
+        db.create_profile("sam").unwrap();
+        assert!(!db.with_profile("sam").unwrap().is_bookmarked(ids[1]).unwrap());
+    }
+
+    #[test]
+    fn pins_from_before_profiles_go_to_the_default_profile() {
+        let (db, ids) = library(&[(Topic::AncientEgypt, "Giza"), (Topic::AncientRome, "Forum")]);
+        db.conn.execute_batch("DROP TABLE profile_content").unwrap();
+        db.conn
+            .execute("UPDATE content SET pinned = 1, last_shown = ?1 WHERE id = ?2", params![chrono::Utc::now().to_rfc3339(), ids[0]])
+            .unwrap();
+        db.init_tables().unwrap();
+
+        assert!(db.is_pinned(ids[0]).unwrap());
+        assert!(!db.is_pinned(ids[1]).unwrap());
+        // The old last-shown time came along, so the pin isn't served twice in a row
+        assert!(db.get_next_pinned().unwrap().is_none());
+        db.create_profile("sam").unwrap();
+        assert!(!db.with_profile("sam").unwrap().is_pinned(ids[0]).unwrap());
+    }
+
+    fn empty_calendar(year: i32) -> ActivityCalendar {
+        let first = |year| chrono::NaiveDate::from_ymd_opt(year, 1, 1).unwrap();
+        let days = (first(year + 1) - first(year)).num_days() as usize;
+        ActivityCalendar { year, days: vec![0; days] }
+    }
+
+    #[test]
+    fn calendar_rows_follow_the_weekday_of_every_date() {
+        use chrono::Datelike;
+        for year in [2012, 2023, 2024, 2100] {
+            let calendar = empty_calendar(year);
+            let mut previous = None;
+            for index in 0..calendar.days.len() {
+                let date = calendar.date(index).unwrap();
+                let (week, weekday) = calendar.cell(index);
+                assert_eq!(weekday, date.weekday().num_days_from_monday() as usize, "{}", date);
+                // Each Monday starts a new column and every other day stays in its week's column
+                if let Some(previous) = previous {
+                    assert_eq!(week, if weekday == 0 { previous + 1 } else { previous }, "{}", date);
+                }
+                previous = Some(week);
+            }
+            assert!(calendar.date(calendar.days.len()).is_none());
+        }
+    }
+
+    #[test]
+    fn calendar_grids_fit_leap_years_and_years_starting_on_a_sunday() {
+        // 2023 starts on a Sunday, 2024 is a leap year starting on a Monday, 2012 is both
+        // and 2100 is no leap year
+        let cases = [
+            (2023, 365, (0, 6), 53),
+            (2024, 366, (0, 0), 53),
+            (2012, 366, (0, 6), 54),
+            (2100, 365, (0, 4), 53),
+        ];
+        for (year, days, first, weeks) in cases {
+            let calendar = empty_calendar(year);
+            assert_eq!(calendar.days.len(), days, "{}", year);
+            assert_eq!(calendar.cell(0), first, "{}", year);
+            assert_eq!(calendar.weeks(), weeks, "{}", year);
+        }
+        let leap = empty_calendar(2024);
+        assert_eq!(leap.date(59), chrono::NaiveDate::from_ymd_opt(2024, 2, 29));
+        assert_eq!(leap.cell(59), (8, 3));
+    }
+
+    #[test]
+    fn calendar_counts_reads_on_their_local_day_and_shades_relative_to_the_busiest() {
+        use chrono::TimeZone;
+        let (db, ids) = library(&[(Topic::AncientEgypt, "Giza")]);
+        let leap_day = chrono::Local.with_ymd_and_hms(2024, 2, 29, 12, 0, 0).unwrap();
+        for (day, reads) in [(leap_day, 4), (leap_day + chrono::Duration::days(1), 1)] {
+            for _ in 0..reads {
+                let read = UserInteraction::FullyRead {
+                    content_id: ids[0],
+                    timestamp: day.with_timezone(&chrono::Utc),
+                    reading_time_seconds: 60,
+                };
+                db.record_interaction(&read).unwrap();
+            }
+        }
+
+        let calendar = db.get_activity_calendar(2024).unwrap();
+        assert_eq!(calendar.days.len(), 366);
+        assert_eq!((calendar.days[59], calendar.days[60]), (4, 1));
+        assert_eq!((calendar.total(), calendar.active_days()), (5, 2));
+        assert_eq!([0, 1, 2, 3, 4].map(|count| calendar.intensity(count)), [0, 1, 2, 3, 4]);
+        assert_eq!(db.get_activity_calendar(2023).unwrap().total(), 0);
+        assert_eq!(empty_calendar(2024).intensity(0), 0);
+    }
+
+    #[test]
+    fn a_tiny_library_keeps_serving_content() {
+        let (db, ids) = library(&[(Topic::AncientEgypt, "Giza"), (Topic::AncientEgypt, "Karnak"), (Topic::AncientRome, "Forum")]);
+        let db = without_read_exclusion(db);
+        // A reading history makes the recency penalties apply
+        db.record_interaction(&UserInteraction::fully_read(ids[0], 60)).unwrap();
+
+        let mut served = HashSet::new();
+        for _ in 0..300 {
+            let content = db.get_weighted_random_content().unwrap().expect("a story every time");
+            db.mark_shown(content.id).unwrap();
+            db.record_interaction(&UserInteraction::fully_read(content.id, 60)).unwrap();
+            served.insert(content.id);
+        }
+        assert_eq!(served, ids.into_iter().collect());
+    }
+
+    /// A story with one fun fact taken from it, returning the ids of both
+    fn story_with_fact(db: &Database, title: &str) -> (i64, i64) {
+        let story = add_story(db, Topic::AncientEgypt, title, &format!("The story of {}.", title));
+        let parent = ContentUnit::new(Topic::AncientEgypt, title.to_string(), String::new(), String::new());
+        let sentence = format!("{} took 20 years.", title);
+        let mut fact = ContentUnit::fun_fact(&ContentUnit { id: story, ..parent }, sentence);
+        db.insert_content(&mut fact).unwrap();
+        (story, fact.id)
+    }
+
+    #[test]
+    fn fun_facts_are_not_counted_as_stories() {
+        let db = Database::new_in_memory().unwrap();
+        let (story, _) = story_with_fact(&db, "Giza");
+
+        assert_eq!(db.get_content_count().unwrap(), 1);
+        assert_eq!(db.get_stored_content_count().unwrap(), 1);
+        assert_eq!(db.get_content_count_by_topic().unwrap(), HashMap::from([(Topic::AncientEgypt, 1)]));
+        let previews = db.get_previews(None, 10).unwrap();
+        assert_eq!(previews.iter().map(|preview| preview.id).collect::<Vec<_>>(), vec![story]);
+    }
+
+    fn deleted_ids(db: &Database) -> HashSet<i64> {
+        let mut stmt = db.conn.prepare("SELECT id FROM content WHERE deleted_at IS NOT NULL").unwrap();
+        let rows = stmt.query_map([], |row| row.get(0)).unwrap();
+        rows.collect::<rusqlite::Result<_>>().unwrap()
+    }
+
+    #[test]
+    fn fun_facts_are_deleted_and_restored_with_their_story() {
+        let db = Database::new_in_memory().unwrap();
+        let (story, fact) = story_with_fact(&db, "Giza");
+        let (_, other_fact) = story_with_fact(&db, "Karnak");
+
+        assert!(db.delete_content(story).unwrap());
+        assert_eq!(deleted_ids(&db), HashSet::from([story, fact]));
+        assert!(db.restore_content(story).unwrap());
+        assert!(deleted_ids(&db).is_empty());
+
+        db.delete_content(story).unwrap();
+        db.conn
+            .execute("UPDATE content SET deleted_at = '2000-01-01T00:00:00+00:00' WHERE deleted_at IS NOT NULL", [])
+            .unwrap();
+        assert_eq!(db.purge_deleted_content(chrono::Duration::days(1)).unwrap(), 2);
+        assert!(!is_stored(&db, fact));
+        assert!(is_stored(&db, other_fact));
+    }
+
+    #[test]
+    fn evicting_a_story_takes_its_fun_facts() {
+        let db = Database::new_in_memory().unwrap();
+        let (story, fact) = story_with_fact(&db, "Giza");
+        let (kept, kept_fact) = story_with_fact(&db, "Karnak");
+        db.record_interaction(&UserInteraction::fully_read(kept, 60)).unwrap();
+
+        assert_eq!(db.enforce_size_limit(1).unwrap(), 1);
+        assert!(!is_stored(&db, story) && !is_stored(&db, fact));
+        assert!(is_stored(&db, kept) && is_stored(&db, kept_fact));
+    }
+
+    fn skip_at(content_id: i64, days_ago: i64, displayed_chars: usize, percent_displayed: u8) -> UserInteraction {
+        UserInteraction::Skipped {
+            content_id,
+            timestamp: chrono::Utc::now() - chrono::Duration::days(days_ago),
+            skip_time_seconds: 30,
+            displayed_chars: Some(displayed_chars),
+            percent_displayed: Some(percent_displayed),
+        }
+    }
+
+    #[test]
+    fn recent_skips_near_the_end_are_offered_again_at_their_offset() {
+        let (db, ids) = library(&[
+            (Topic::AncientEgypt, "Giza"),
+            (Topic::AncientEgypt, "Karnak"),
+            (Topic::AncientRome, "Forum"),
+            (Topic::AncientRome, "Senate"),
+            (Topic::AncientRome, "Circus"),
+        ]);
+        db.record_interaction(&skip_at(ids[0], 1, 420, 85)).unwrap();
+        db.record_interaction(&skip_at(ids[1], 0, 12, 5)).unwrap();
+        db.record_interaction(&skip_at(ids[2], 8, 400, 90)).unwrap();
+        db.record_interaction(&skip_at(ids[3], 2, 300, 60)).unwrap();
+        db.record_interaction(&skip_at(ids[4], 3, 350, 70)).unwrap();
+        db.record_interaction(&UserInteraction::fully_read(ids[4], 60)).unwrap();
+
+        let candidates = db.get_resume_candidates().unwrap();
+        let found: Vec<_> = candidates
+            .iter()
+            .map(|candidate| (candidate.content.id, candidate.displayed_chars, candidate.percent_displayed))
+            .collect();
+        assert_eq!(found, vec![(ids[0], 420, 85), (ids[3], 300, 60)]);
+
+        // Not offered right after being shown
+        db.mark_shown(ids[0]).unwrap();
+        let candidates = db.get_resume_candidates().unwrap();
+        assert_eq!(candidates.iter().map(|candidate| candidate.content.id).collect::<Vec<_>>(), vec![ids[3]]);
+    }
+
+    #[test]
+    fn stories_read_in_the_last_30_days_stay_out_of_the_stream_by_default() {
+        let (db, ids) = library(&[
+            (Topic::AncientEgypt, "Giza"),
+            (Topic::AncientEgypt, "Karnak"),
+            (Topic::AncientEgypt, "Abydos"),
+        ]);
+        assert_eq!(RecommendationConfig::default().exclude_read_within_days, 30);
+        db.record_interaction(&read_at(ids[0], -29 * 24 * 60)).unwrap();
+        db.record_interaction(&read_at(ids[1], -31 * 24 * 60)).unwrap();
+        db.mark_all_seen().unwrap();
+
+        assert_eq!(picks(&db, 100), HashSet::from([ids[1], ids[2]]));
+        // 0 turns the exclusion off
+        assert_eq!(picks(&without_read_exclusion(db), 100), ids.into_iter().collect());
+    }
+
+    #[test]
+    fn stream_exclusions_look_their_tables_up_by_index() {
+        let db = Database::new_in_memory().unwrap().with_recommendation(RecommendationConfig {
+            exclude_pinned_from_stream: true,
+            exclude_read_within_days: 30,
+            ..RecommendationConfig::default()
+        });
+        let query = format!(
+            "EXPLAIN QUERY PLAN SELECT id FROM content WHERE topic = 'x' AND {} AND {} AND {}",
+            LIVE_CONTENT,
+            NOT_FUN_FACT,
+            db.stream_filter()
+        );
+        let mut statement = db.conn.prepare(&query).unwrap();
+        let plan = statement
+            .query_map([], |row| row.get::<_, String>("detail"))
+            .unwrap()
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .unwrap();
+
+        assert!(plan.iter().any(|step| step.contains("idx_interactions_content")), "{plan:#?}");
+        for table in ["user_interactions", "profile_content"] {
+            assert!(plan.iter().any(|step| step.starts_with(&format!("SEARCH {table} "))), "{plan:#?}");
+            assert!(!plan.iter().any(|step| step.starts_with(&format!("SCAN {table}"))), "{plan:#?}");
+        }
+    }
+
+    /// A database file in a fresh directory whose schema is missing the index added by
+    /// migration 15, as if an older build had written it
+    fn outdated_database(name: &str) -> (std::path::PathBuf, String) {
+        let dir = std::env::temp_dir().join(format!("tellme-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("tellme.db").to_str().unwrap().to_string();
+        Database::new(&path).unwrap();
+        assert!(pending_versions(&path).is_empty());
+        assert!(!Database::pre_migrate_backup_path(&path, 0).exists());
+        Connection::open(&path).unwrap().execute_batch("DROP INDEX idx_interactions_content").unwrap();
+        (dir, path)
+    }
+
+    fn pending_versions(path: &str) -> Vec<u32> {
+        Database::pending_migrations(path).unwrap().iter().map(|migration| migration.version).collect()
+    }
+
+    #[test]
+    fn listing_pending_migrations_changes_nothing() {
+        let (dir, path) = outdated_database("migrate-dry-run");
+        assert_eq!(pending_versions(&path), vec![15]);
+        assert_eq!(Database::schema_version(&path).unwrap(), 14);
+        assert_eq!(pending_versions(&path), vec![15]);
+        assert!(!Database::pre_migrate_backup_path(&path, 14).exists());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn migrating_logs_each_version_and_only_new_snapshots_first() {
+        let (dir, path) = outdated_database("migrate-backup");
+        let started = chrono::Utc::now().to_rfc3339();
+        let db = Database::new_without_backup(&path).unwrap();
+        assert!(pending_versions(&path).is_empty());
+        assert!(!Database::pre_migrate_backup_path(&path, 14).exists());
+        let record = db.get_migrations_log().unwrap().into_iter().find(|record| record.version == 15).unwrap();
+        assert!(record.applied_at >= started && record.duration_ms >= 0, "{record:?}");
+        drop(db);
+
+        Connection::open(&path).unwrap().execute_batch("DROP INDEX idx_interactions_content").unwrap();
+        let _db = Database::new(&path).unwrap();
+        assert!(pending_versions(&path).is_empty());
+        // The snapshot is the database as it was before the migration
+        let backup = Database::pre_migrate_backup_path(&path, 14);
+        assert_eq!(pending_versions(backup.to_str().unwrap()), vec![15]);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn migration_sql_gives_old_rows_the_default_profile() {
+        let migration = MIGRATIONS.iter().find(|migration| migration.version == 13).unwrap();
+        assert_eq!(
+            migration.sql(),
+            format!("ALTER TABLE user_interactions ADD COLUMN profile_id INTEGER NOT NULL DEFAULT {}", DEFAULT_PROFILE_ID)
+        );
+    }
+
+    /// A piece of `source` added `minutes` after the first, as part `part` if given
+    fn piece(title: &str, source: &str, minutes: i64, part: Option<u32>) -> ContentUnit {
+        let mut unit = ContentUnit::new(Topic::AncientEgypt, title.to_string(), String::new(), source.to_string());
+        unit.created_at = chrono::DateTime::UNIX_EPOCH + chrono::Duration::minutes(minutes);
+        unit.part_index = part;
+        unit
+    }
+
+    fn titles(candidates: &[(ContentUnit, Option<i32>)]) -> Vec<&str> {
+        candidates.iter().map(|(content, _)| content.title.as_str()).collect()
+    }
+
+    #[test]
+    fn guided_order_reads_articles_as_added_and_their_parts_in_sequence() {
+        let mut candidates = vec![
+            (piece("Karnak", "karnak", 30, None), Some(90)),
+            (piece("Giza 3", "giza", 11, Some(2)), Some(10)),
+            (piece("Giza 1", "giza", 10, Some(0)), Some(20)),
+            (piece("Luxor", "luxor", 0, None), None),
+            (piece("Giza 2", "giza", 9, Some(1)), Some(99)),
+        ];
+        guided_order(&mut candidates);
+        assert_eq!(titles(&candidates), ["Luxor", "Giza 1", "Giza 2", "Giza 3", "Karnak"]);
+
+        // A part stored after the next article still stays with the rest of its own
+        candidates.iter_mut().find(|(content, _)| content.title == "Giza 3").unwrap().0.created_at =
+            chrono::DateTime::UNIX_EPOCH + chrono::Duration::minutes(40);
+        candidates.reverse();
+        guided_order(&mut candidates);
+        assert_eq!(titles(&candidates), ["Luxor", "Giza 1", "Giza 2", "Giza 3", "Karnak"]);
+    }
+
+    #[test]
+    fn guided_order_puts_better_scored_pieces_first_where_all_else_ties() {
+        let mut candidates = vec![
+            (piece("Unscored", "quotes", 0, None), None),
+            (piece("Fair", "quotes", 0, None), Some(40)),
+            (piece("Good", "quotes", 0, None), Some(80)),
+            (piece("Later", "other", 0, None), Some(100)),
+        ];
+        guided_order(&mut candidates);
+        // Sources added at the same moment keep a fixed order, whatever their scores
+        assert_eq!(titles(&candidates), ["Later", "Good", "Fair", "Unscored"]);
+    }
+
+    #[test]
+    fn guided_picks_follow_the_stored_quality_scores() {
+        let (db, ids) = library(&[(Topic::AncientEgypt, "Giza"), (Topic::AncientEgypt, "Karnak")]);
+        // One source added at one moment, so only the scores tell the two apart
+        let now = chrono::Utc::now().to_rfc3339();
+        db.conn.execute("UPDATE content SET source_url = 'egypt', created_at = ?1", [now]).unwrap();
+        for (id, quality) in [(ids[0], 20), (ids[1], 70)] {
+            db.conn.execute("UPDATE content SET quality_score = ?2 WHERE id = ?1", params![id, quality]).unwrap();
+        }
+        let next = db.get_guided_content_for_topic(Topic::AncientEgypt).unwrap().unwrap();
+        assert_eq!(next.id, ids[1]);
+    }
+
+    #[test]
+    fn looked_up_terms_are_cached_whatever_their_case() {
+        let db = Database::new_in_memory().unwrap();
+        assert!(db.get_cached_term("Pax Mongolica").unwrap().is_none());
+        let summary = TermSummary {
+            term: "Pax Mongolica".to_string(),
+            title: "Pax Mongolica".to_string(),
+            extract: "A period of relative stability in Eurasia.".to_string(),
+            source_url: "https://en.wikipedia.org/wiki/Pax_Mongolica".to_string(),
+        };
+        db.cache_term(&summary).unwrap();
+        let cached = db.get_cached_term("pax mongolica").unwrap().unwrap();
+        assert_eq!((cached.title, cached.extract), (summary.title, summary.extract));
+    }
+
+    #[test]
+    fn favourites_from_before_they_were_pins_become_pins() {
+        let (dir, path) = outdated_database("favourites");
+        let db = Database::new(&path).unwrap();
+        let giza = add_story(&db, Topic::AncientEgypt, "Giza", "The pyramids of Giza.");
+        let forum = add_story(&db, Topic::AncientRome, "Forum", "The Roman Forum.");
+        db.pin_content(forum).unwrap();
+        drop(db);
+
+        // The favourites table an older build kept, with one story also pinned
+        Connection::open(&path)
+            .unwrap()
+            .execute_batch(&format!(
+                "CREATE TABLE favorites (
+                     profile_id INTEGER, content_id INTEGER, added_at TEXT, PRIMARY KEY (profile_id, content_id)
+                 );
+                 INSERT INTO favorites VALUES ({profile}, {giza}, ''), ({profile}, {forum}, '')",
+                profile = DEFAULT_PROFILE_ID
+            ))
+            .unwrap();
+        assert_eq!(pending_versions(&path), vec![27]);
+
+        let db = Database::new(&path).unwrap();
+        assert!(pending_versions(&path).is_empty());
+        assert!(db.is_pinned(giza).unwrap() && db.is_pinned(forum).unwrap());
+        drop(db);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    /// A story in `topic` added `days_ago`, scored `quality` and shown `shown` times
+    fn stale_story(db: &Database, topic: Topic, title: &str, days_ago: i64, quality: Option<i32>, shown: i64) -> i64 {
+        let id = add_story(db, topic, title, &format!("The story of {}.", title));
+        let added = (chrono::Utc::now() - chrono::Duration::days(days_ago)).to_rfc3339();
+        db.conn
+            .execute(
+                "UPDATE content SET created_at = ?2, quality_score = ?3, times_shown = ?4 WHERE id = ?1",
+                params![id, added, quality, shown],
+            )
+            .unwrap();
+        id
+    }
+
+    /// Prunes stories over 30 days old, scored below 2 and never shown, with no floor
+    fn strict_policy() -> RetentionPolicy {
+        RetentionPolicy {
+            enabled: true,
+            min_age_days: 30,
+            quality_below: 2,
+            shown_fewer_than: 1,
+            keep_per_topic: 0,
+            soft_delete: true,
+        }
+    }
+
+    fn pruned_ids(report: &RetentionReport) -> Vec<i64> {
+        report.topics.iter().flat_map(|topic| &topic.pruned).map(|(id, _)| *id).collect()
+    }
+
+    #[test]
+    fn retention_prunes_only_stories_matching_every_rule() {
+        let db = Database::new_in_memory().unwrap();
+        let stale = stale_story(&db, Topic::AncientEgypt, "Stale", 61, Some(1), 0);
+        // A skip isn't a read, so it doesn't save a story
+        let skipped = stale_story(&db, Topic::AncientEgypt, "Skipped", 60, Some(1), 0);
+        db.record_interaction(&UserInteraction::skipped(skipped, 5)).unwrap();
+        stale_story(&db, Topic::AncientEgypt, "Recent", 10, Some(1), 0);
+        stale_story(&db, Topic::AncientEgypt, "Good", 60, Some(2), 0);
+        stale_story(&db, Topic::AncientEgypt, "Shown", 60, Some(1), 1);
+        stale_story(&db, Topic::AncientEgypt, "Unscored", 60, None, 0);
+        let read = stale_story(&db, Topic::AncientEgypt, "Read", 60, Some(1), 0);
+        db.record_interaction(&UserInteraction::fully_read(read, 60)).unwrap();
+        let deleted = stale_story(&db, Topic::AncientEgypt, "Deleted", 60, Some(1), 0);
+        db.delete_content(deleted).unwrap();
+        let (_, fact) = story_with_fact(&db, "Giza");
+        let added = (chrono::Utc::now() - chrono::Duration::days(60)).to_rfc3339();
+        db.conn
+            .execute("UPDATE content SET created_at = ?2, quality_score = 0 WHERE id = ?1", params![fact, added])
+            .unwrap();
+        // Pins by any profile count, not just the active one's
+        let pinned = stale_story(&db, Topic::AncientEgypt, "Pinned", 60, Some(1), 0);
+        db.create_profile("sam").unwrap();
+        let db = db.with_profile("sam").unwrap();
+        db.pin_content(pinned).unwrap();
+
+        let dry_run = db.apply_retention(&strict_policy(), true).unwrap();
+        assert_eq!(pruned_ids(&dry_run), vec![stale, skipped]);
+        assert_eq!(deleted_ids(&db), HashSet::from([deleted]));
+
+        let report = db.apply_retention(&strict_policy(), false).unwrap();
+        assert!(!report.dry_run && report.soft_deleted);
+        assert_eq!(pruned_ids(&report), vec![stale, skipped]);
+        assert_eq!(deleted_ids(&db), HashSet::from([deleted, stale, skipped]));
+        assert_eq!(db.apply_retention(&strict_policy(), false).unwrap().pruned(), 0);
+    }
+
+    #[test]
+    fn retention_keeps_the_best_of_each_topic_above_the_floor() {
+        let db = Database::new_in_memory().unwrap();
+        let worst = stale_story(&db, Topic::AncientRome, "Worst", 60, Some(0), 0);
+        let oldest = stale_story(&db, Topic::AncientRome, "Oldest", 90, Some(1), 0);
+        stale_story(&db, Topic::AncientRome, "Newer", 60, Some(1), 0);
+        stale_story(&db, Topic::AncientRome, "Read", 60, Some(5), 1);
+        stale_story(&db, Topic::AncientEgypt, "Alone", 60, Some(0), 0);
+
+        let policy = RetentionPolicy { keep_per_topic: 2, ..strict_policy() };
+        let report = db.apply_retention(&policy, false).unwrap();
+        assert_eq!(pruned_ids(&report), vec![worst, oldest]);
+        assert_eq!(report.kept_by_floor(), 2);
+        let rome = report.topics.iter().find(|topic| topic.topic == Topic::AncientRome).unwrap();
+        assert_eq!((rome.stored, rome.kept_by_floor), (4, 1));
+        assert_eq!(db.get_content_count_by_topic().unwrap()[&Topic::AncientRome], 2);
+        assert_eq!(db.get_content_count_by_topic().unwrap()[&Topic::AncientEgypt], 1);
+    }
+
+    #[test]
+    fn pruned_stories_take_their_fun_facts_along() {
+        let soft = Database::new_in_memory().unwrap();
+        let (story, fact) = story_with_fact(&soft, "Giza");
+        soft.conn.execute("UPDATE content SET created_at = '2000-01-01T00:00:00Z', quality_score = 0", []).unwrap();
+        assert_eq!(soft.apply_retention(&strict_policy(), false).unwrap().pruned(), 1);
+        assert_eq!(deleted_ids(&soft), HashSet::from([story, fact]));
+        assert!(soft.restore_content(story).unwrap());
+        assert!(deleted_ids(&soft).is_empty());
+
+        let hard = Database::new_in_memory().unwrap();
+        let (story, fact) = story_with_fact(&hard, "Giza");
+        hard.conn.execute("UPDATE content SET created_at = '2000-01-01T00:00:00Z', quality_score = 0", []).unwrap();
+        hard.record_interaction(&UserInteraction::skipped(fact, 2)).unwrap();
+        let policy = RetentionPolicy { soft_delete: false, ..strict_policy() };
+        assert_eq!(hard.apply_retention(&policy, false).unwrap().pruned(), 1);
+        assert!(!is_stored(&hard, story) && !is_stored(&hard, fact));
+        let interactions: i64 =
+            hard.conn.query_row("SELECT COUNT(*) FROM user_interactions", [], |row| row.get(0)).unwrap();
+        assert_eq!(interactions, 0);
     }
 
-    /// Initialize database tables if they don't exist
-    /// This demonstrates multi-line SQL strings and transaction handling
-    fn init_tables(&self) -> Result<()> {
-        // Create content table
-        self.conn.execute(
-            "CREATE TABLE IF NOT EXISTS content (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                topic TEXT NOT NULL,
-                title TEXT NOT NULL,
-                content TEXT NOT NULL,
-                source_url TEXT NOT NULL,
-                word_count INTEGER NOT NULL,
-                created_at TEXT NOT NULL
-            )",
-            [],
-        )?;
+    #[test]
+    fn interaction_stats_add_up_the_profiles_log() {
+        let (db, ids) = library(&[(Topic::AncientEgypt, "Giza"), (Topic::AncientRome, "Colosseum")]);
+        let empty = db.get_interaction_stats().unwrap();
+        assert_eq!((empty.total, empty.fully_read, empty.skipped), (0, 0, 0));
+        assert_eq!(empty.avg_reading_time_seconds, None);
 
-        // Create user_interactions table
-        self.conn.execute(
-            "CREATE TABLE IF NOT EXISTS user_interactions (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                content_id INTEGER NOT NULL,
-                interaction_type TEXT NOT NULL,
-                timestamp TEXT NOT NULL,
-                duration_seconds INTEGER NOT NULL,
-                FOREIGN KEY (content_id) REFERENCES content (id)
-            )",
-            [],
-        )?;
+        db.record_interaction(&UserInteraction::fully_read(ids[0], 60)).unwrap();
+        db.record_interaction(&UserInteraction::fully_read(ids[1], 120)).unwrap();
+        db.record_interaction(&UserInteraction::skipped(ids[0], 3)).unwrap();
+        db.record_interaction(&UserInteraction::dismissed(ids[1], 1)).unwrap();
+        let stats = db.get_interaction_stats().unwrap();
+        assert_eq!(db.get_interaction_count().unwrap(), 4);
+        assert_eq!((stats.total, stats.fully_read, stats.skipped), (4, 2, 1));
+        assert_eq!(stats.avg_reading_time_seconds, Some(90.0));
 
-        // Create index for better query performance
-        self.conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_content_topic ON content (topic)",
-            [],
-        )?;
+        db.create_profile("Ada").unwrap();
+        let db = db.with_profile("Ada").unwrap();
+        db.record_interaction(&UserInteraction::skipped(ids[1], 2)).unwrap();
+        let stats = db.get_interaction_stats().unwrap();
+        assert_eq!((stats.total, stats.fully_read, stats.skipped), (1, 0, 1));
+        assert_eq!(stats.avg_reading_time_seconds, None);
+    }
 
-        Ok(())
+    #[test]
+    fn interaction_stats_count_aggregate_mode_reads_without_timing_them() {
+        let (db, ids) = library(&[(Topic::AncientEgypt, "Giza"), (Topic::AncientRome, "Colosseum")]);
+        db.record_interaction(&UserInteraction::fully_read(ids[0], 40)).unwrap();
+        let db = Database { privacy: PrivacyMode::Aggregate, ..db };
+        db.record_interaction(&UserInteraction::fully_read(ids[1], 400)).unwrap();
+        db.record_interaction(&UserInteraction::skipped(ids[1], 2)).unwrap();
+        let stats = db.get_interaction_stats().unwrap();
+        assert_eq!(db.get_interaction_count().unwrap(), 3);
+        assert_eq!((stats.total, stats.fully_read, stats.skipped), (3, 2, 1));
+        assert_eq!(stats.avg_reading_time_seconds, Some(40.0));
     }
 
-    /// Insert a new content unit into the database
-    /// This demonstrates parameter binding and returning generated IDs
-    pub fn insert_content(&self, content: &mut ContentUnit) -> Result<()> {
-        let topic_str = serde_json::to_string(&content.topic)?;
-        let created_at_str = content.created_at.to_rfc3339();
+    #[test]
+    fn iterating_a_large_library_holds_one_chunk_at_a_time() {
+        let db = Database::new_in_memory().unwrap();
+        // Story i gets id i + 1, alternates between Egypt and Rome and was read if i % 7 == 0
+        db.conn
+            .execute(
+                "WITH RECURSIVE n(i) AS (SELECT 0 UNION ALL SELECT i + 1 FROM n WHERE i < 9999)
+                 INSERT INTO content (topic, title, content, source_url, word_count, created_at)
+                 SELECT CASE i % 2 WHEN 0 THEN ?1 ELSE ?2 END, 'Story ' || i, 'The story of ' || i || '.',
+                        '', 50 + i % 400, ?3
+                 FROM n",
+                params![
+                    serde_json::to_string(&Topic::AncientEgypt).unwrap(),
+                    serde_json::to_string(&Topic::AncientRome).unwrap(),
+                    chrono::Utc::now().to_rfc3339()
+                ],
+            )
+            .unwrap();
+        db.conn
+            .execute(
+                "INSERT INTO user_interactions (content_id, interaction_type, timestamp, duration_seconds, profile_id)
+                 SELECT id, 'fully_read', ?1, 60, ?2 FROM content WHERE (id - 1) % 7 = 0",
+                params![chrono::Utc::now().to_rfc3339(), DEFAULT_PROFILE_ID],
+            )
+            .unwrap();
+        db.delete_content(2).unwrap();
 
-        let id = self.conn.query_row(
-            "INSERT INTO content (topic, title, content, source_url, word_count, created_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6)
-             RETURNING id",
-            params![
-                topic_str,
-                content.title,
-                content.content,
-                content.source_url,
-                content.word_count,
-                created_at_str
-            ],
-            |row| row.get::<_, i64>(0),
-        )?;
+        let chunk_sizes: Vec<usize> =
+            db.content_chunks(ContentFilter::default()).map(|chunk| chunk.unwrap().len()).collect();
+        assert_eq!(chunk_sizes.iter().sum::<usize>(), 9_999);
+        assert_eq!(chunk_sizes.len(), 9_999_usize.div_ceil(ITER_CHUNK));
+        assert!(chunk_sizes.iter().all(|&size| size <= ITER_CHUNK));
 
-        content.id = id;
-        Ok(())
+        let all: Vec<i64> = db.iter_content(ContentFilter::default()).map(|unit| unit.unwrap().id).collect();
+        assert_eq!(all.len(), 9_999);
+        assert!(all.windows(2).all(|pair| pair[0] < pair[1]), "not in id order");
+
+        let filter = ContentFilter::default().with_topic(Some(Topic::AncientRome)).with_interactions(true);
+        let mut read_from_rome = Vec::new();
+        let count = db
+            .for_each_content(filter, |unit| {
+                read_from_rome.push(unit.id);
+                Ok(())
+            })
+            .unwrap();
+        let expected: Vec<i64> = (0..10_000).filter(|i| i % 2 == 1 && i % 7 == 0).map(|i| i + 1).collect();
+        assert_eq!(count, expected.len());
+        assert_eq!(read_from_rome, expected);
+
+        let mut seen = 0;
+        let stopped = db.for_each_content(ContentFilter::default(), |_| {
+            seen += 1;
+            anyhow::ensure!(seen < 3, "enough");
+            Ok(())
+        });
+        assert_eq!(stopped.unwrap_err().to_string(), "enough");
+        assert_eq!(seen, 3);
     }
 
-    /// Get a content unit using smart balanced recommendation
-    /// This ensures variety while still learning from user preferences
-    pub fn get_weighted_random_content(&self) -> Result<Option<ContentUnit>> {
-        // Get topic preferences and recent topic history
-        let topic_weights = self.get_topic_preferences()?;
-        let recent_topics = self.get_recent_topics(5)?; // Last 5 topics shown
-        
-        // If no preferences exist, return truly random content
-        if topic_weights.is_empty() {
-            return self.get_random_content();
-        }
+    const GRAPHIC_STORY: &str = "After the siege the defenders were tortured and beheaded, and the \
+        massacre that followed left the streets full of corpses.";
+    const MILD_STORY: &str = "The harbour at Ostia was busy with ships carrying grain, oil and wine to Rome; \
+        one old sailor was murdered there, the records say, over a game of dice.";
 
-        // Calculate smart weights with diversity bonus
-        let smart_topic = self.select_topic_with_diversity(&topic_weights, &recent_topics)?;
-        
-        self.get_random_content_by_topic(smart_topic)
+    fn safe_mode(db: Database) -> Database {
+        db.with_sensitivity(SensitivityConfig {
+            safe_mode: true,
+            ..SensitivityConfig::default()
+        })
     }
 
-    /// Select topic using weighted random selection with diversity bonuses
-    fn select_topic_with_diversity(
-        &self, 
-        preferences: &HashMap<Topic, f64>,
-        recent_topics: &[Topic]
-    ) -> Result<Topic> {
-        let mut topic_scores = HashMap::new();
-        
-        // Start with base preference scores (0.0 to 1.0)
-        for topic in Topic::all() {
-            let base_score = preferences.get(topic).copied().unwrap_or(0.3); // Default 30% for new topics
-            topic_scores.insert(*topic, base_score);
-        }
-        
-        // Apply diversity bonuses/penalties
-        for (topic, score) in topic_scores.iter_mut() {
-            // Heavy penalty for topics shown recently (more recent = bigger penalty)
-            for (i, recent_topic) in recent_topics.iter().enumerate() {
-                if topic == recent_topic {
-                    let penalty = match i {
-                        0 => 0.1,  // Last topic: 90% penalty
-                        1 => 0.3,  // 2nd last: 70% penalty  
-                        2 => 0.6,  // 3rd last: 40% penalty
-                        3 => 0.8,  // 4th last: 20% penalty
-                        4 => 0.9,  // 5th last: 10% penalty
-                        _ => 1.0,
-                    };
-                    *score *= penalty;
-                }
-            }
-            
-            // Exploration bonus for topics with few interactions
-            let interaction_count = self.get_topic_interaction_count(*topic).unwrap_or(0);
-            if interaction_count < 3 {
-                *score += 0.2; // 20% bonus for under-explored topics
-            }
-            
-            // Ensure minimum score for variety
-            *score = score.max(0.05); // Every topic has at least 5% chance
+    #[test]
+    fn graphic_stories_score_as_sensitive_and_mild_ones_do_not() {
+        let db = Database::new_in_memory().unwrap();
+        let graphic = add_story(&db, Topic::AncientRome, "Sack", GRAPHIC_STORY);
+        let mild = add_story(&db, Topic::AncientRome, "Ostia", MILD_STORY);
+        assert!(db.is_sensitive(graphic).unwrap());
+        assert!(!db.is_sensitive(mild).unwrap());
+
+        // Scores are stored, so changing the terms needs a rescore
+        let db = db.with_sensitivity(SensitivityConfig {
+            terms: [("dice".to_string(), 6)].into(),
+            ..SensitivityConfig::default()
+        });
+        assert!(db.is_sensitive(graphic).unwrap());
+        assert_eq!(db.rescore_sensitivity().unwrap(), 2);
+        assert!(!db.is_sensitive(graphic).unwrap());
+        assert!(db.is_sensitive(mild).unwrap());
+    }
+
+    #[test]
+    fn safe_mode_keeps_sensitive_stories_out_of_resumed_and_pinned_picks() {
+        let db = Database::new_in_memory().unwrap();
+        let graphic = add_story(&db, Topic::AncientRome, "Sack", GRAPHIC_STORY);
+        let mild = add_story(&db, Topic::AncientRome, "Ostia", MILD_STORY);
+        for id in [graphic, mild] {
+            db.record_interaction(&UserInteraction::skipped_at(id, 30, 100, 80)).unwrap();
         }
-        
-        // Weighted random selection
-        self.weighted_random_selection(&topic_scores)
+        // Skipped stories are cooling down, so pin two others
+        let pinned_graphic = add_story(&db, Topic::AncientRome, "Sack again", GRAPHIC_STORY);
+        let pinned_mild = add_story(&db, Topic::AncientRome, "Ostia again", MILD_STORY);
+        db.pin_content(pinned_graphic).unwrap();
+        db.pin_content(pinned_mild).unwrap();
+
+        let resumable = |db: &Database| -> HashSet<i64> {
+            db.get_resume_candidates().unwrap().iter().map(|candidate| candidate.content.id).collect()
+        };
+        let next_pinned = |db: &Database| db.get_next_pinned().unwrap().map(|content| content.id);
+        assert_eq!(resumable(&db), HashSet::from([graphic, mild]));
+        // Pinned but never shown, so neither counts as the story shown last
+        assert_eq!(next_pinned(&db), Some(pinned_graphic));
+
+        let db = safe_mode(db);
+        assert_eq!(resumable(&db), HashSet::from([mild]));
+        assert_eq!(next_pinned(&db), Some(pinned_mild));
     }
-    
-    /// Perform weighted random selection from topic scores
-    fn weighted_random_selection(&self, topic_scores: &HashMap<Topic, f64>) -> Result<Topic> {
-        use rand::Rng;
-        
-        let total_weight: f64 = topic_scores.values().sum();
-        let mut rng = rand::thread_rng();
-        let mut random_point = rng.gen::<f64>() * total_weight;
-        
-        for (topic, weight) in topic_scores {
-            random_point -= weight;
-            if random_point <= 0.0 {
-                return Ok(*topic);
-            }
+
+    #[test]
+    fn resumed_and_pinned_picks_pass_over_topics_from_other_builds() {
+        let db = Database::new_in_memory().unwrap();
+        let known = add_story(&db, Topic::AncientRome, "Ostia", MILD_STORY);
+        let foreign = add_story(&db, Topic::AncientRome, "Lunch", "A story from a build with other topics.");
+        db.conn.execute("UPDATE content SET topic = '\"Cooking\"' WHERE id = ?1", [foreign]).unwrap();
+        for id in [known, foreign] {
+            db.record_interaction(&UserInteraction::skipped_at(id, 30, 100, 80)).unwrap();
         }
-        
-        // Fallback to random topic (shouldn't happen)
-        let topics = Topic::all();
-        let random_index = rng.gen_range(0..topics.len());
-        Ok(topics[random_index])
+        let candidates = db.get_resume_candidates().unwrap();
+        assert_eq!(candidates.iter().map(|candidate| candidate.content.id).collect::<Vec<_>>(), [known]);
+
+        db.conn.execute("DELETE FROM user_interactions", []).unwrap();
+        db.pin_content(foreign).unwrap();
+        assert!(db.get_next_pinned().unwrap().is_none());
+        db.pin_content(known).unwrap();
+        assert_eq!(db.get_next_pinned().unwrap().map(|content| content.id), Some(known));
     }
-    
-    /// Get recently shown topics to prevent repetition
-    fn get_recent_topics(&self, limit: usize) -> Result<Vec<Topic>> {
-        let mut stmt = self.conn.prepare(
-            "SELECT c.topic FROM user_interactions ui
-             JOIN content c ON ui.content_id = c.id
-             ORDER BY ui.timestamp DESC
-             LIMIT ?1"
-        )?;
-        
-        let rows = stmt.query_map([limit], |row| {
-            let topic_str: String = row.get(0)?;
-            Ok(topic_str)
-        })?;
-        
-        let mut recent_topics = Vec::new();
-        for row_result in rows {
-            let topic_str = row_result?;
-            if let Ok(topic) = serde_json::from_str::<Topic>(&topic_str) {
-                recent_topics.push(topic);
-            }
+
+    #[test]
+    fn searches_rank_title_matches_first_and_take_fts_syntax_literally() {
+        let db = Database::new_in_memory().unwrap();
+        let siege = add_story(
+            &db,
+            Topic::Byzantine,
+            "Siege of Constantinople",
+            "The defenders burned the Arab fleet with Greek fire in 678.",
+        );
+        let weapon = add_story(&db, Topic::Byzantine, "Greek fire", "An incendiary weapon of the Byzantine navy.");
+        let deleted = add_story(&db, Topic::Byzantine, "Greek fire ships", "Dromons fitted with siphons.");
+        db.delete_content(deleted).unwrap();
+
+        let ids = |query: &str| -> Vec<i64> {
+            db.search_content(query, 10).unwrap().iter().map(|content| content.id).collect()
+        };
+        assert_eq!(ids("greek fi"), [weapon, siege]);
+        assert_eq!(ids("siege"), [siege]);
+        assert_eq!(db.search_content("greek", 1).unwrap().len(), 1);
+        for query in ["", "   ", "\"greek", "fire AND (", "NEAR(greek", "*", "title:greek", "-fire"] {
+            assert!(db.search_content(query, 10).is_ok(), "{query}");
         }
-        
-        Ok(recent_topics)
+        assert!(ids("fire AND (").is_empty());
     }
-    
-    /// Get the number of interactions for a specific topic
-    fn get_topic_interaction_count(&self, topic: Topic) -> Result<i64> {
-        let topic_str = serde_json::to_string(&topic)?;
-        
-        let count = self.conn.query_row(
-            "SELECT COUNT(*) FROM user_interactions ui
-             JOIN content c ON ui.content_id = c.id
-             WHERE c.topic = ?1",
-            params![topic_str],
-            |row| row.get::<_, i64>(0),
-        )?;
-        
-        Ok(count)
+
+    /// A full read of `content_id` stamped `minutes` from now, negative for the past
+    fn read_at(content_id: i64, minutes: i64) -> UserInteraction {
+        UserInteraction::FullyRead {
+            content_id,
+            timestamp: chrono::Utc::now() + chrono::Duration::minutes(minutes),
+            reading_time_seconds: 60,
+        }
     }
 
-    /// Get completely random content
-    fn get_random_content(&self) -> Result<Option<ContentUnit>> {
-        self.conn
-            .query_row(
-                "SELECT id, topic, title, content, source_url, word_count, created_at
-                 FROM content
-                 ORDER BY RANDOM()
-                 LIMIT 1",
-                [],
-                |row| self.row_to_content_unit(row),
-            )
-            .optional()
-            .map_err(Into::into)
+    #[test]
+    fn the_state_dump_and_preview_count_exploration_picks_at_a_balance_of_zero() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let (db, ids) = library(&[
+            (Topic::AncientEgypt, "Giza"),
+            (Topic::AncientEgypt, "Karnak"),
+            (Topic::AncientRome, "Forum"),
+            (Topic::Viking, "Hedeby"),
+        ]);
+        for minutes in [-30, -20, -10] {
+            db.record_interaction(&read_at(ids[0], minutes)).unwrap();
+        }
+        db.set_explore_exploit_balance(0.0);
+
+        // Only Egypt is weighed, but the 15% exploration rate is spread over all three
+        let state = db.dump_recommender_state().unwrap();
+        let chance = |topic| state.topics.iter().find(|t| t.topic == topic).unwrap().probability;
+        for (topic, expected) in [(Topic::AncientEgypt, 0.9), (Topic::AncientRome, 0.05), (Topic::Viking, 0.05)] {
+            assert!((chance(topic) - expected).abs() < 1e-9, "{topic}: {}", chance(topic));
+        }
+        assert_eq!(chance(Topic::Medieval), 0.0);
+
+        let mut rng = StdRng::seed_from_u64(11);
+        let draws = 1_000;
+        let egypt = (0..draws)
+            .filter(|_| db.preview_next_selections_with(1, &mut rng).unwrap()[0].0 == Topic::AncientEgypt)
+            .count();
+        assert!((egypt as f64 / draws as f64 - 0.9).abs() < 0.03, "{egypt} of {draws}");
     }
 
-    /// Get random content from a specific topic
-    fn get_random_content_by_topic(&self, topic: Topic) -> Result<Option<ContentUnit>> {
-        let topic_str = serde_json::to_string(&topic)?;
-        
-        self.conn
-            .query_row(
-                "SELECT id, topic, title, content, source_url, word_count, created_at
-                 FROM content
-                 WHERE topic = ?1
-                 ORDER BY RANDOM()
-                 LIMIT 1",
-                params![topic_str],
-                |row| self.row_to_content_unit(row),
-            )
-            .optional()
-            .map_err(Into::into)
+    /// A full read of `content_id` at noon local time on the given day
+    fn read_on(content_id: i64, year: i32, month: u32, day: u32) -> UserInteraction {
+        use chrono::TimeZone;
+        UserInteraction::FullyRead {
+            content_id,
+            timestamp: chrono::Local.with_ymd_and_hms(year, month, day, 12, 0, 0).unwrap().with_timezone(&chrono::Utc),
+            reading_time_seconds: 60,
+        }
     }
 
-    /// Convert a database row to a ContentUnit
-    /// This demonstrates error handling within row mapping
-    fn row_to_content_unit(&self, row: &Row) -> rusqlite::Result<ContentUnit> {
-        let topic_str: String = row.get(1)?;
-        let topic: Topic = serde_json::from_str(&topic_str)
-            .map_err(|e| rusqlite::Error::FromSqlConversionFailure(
-                1, 
-                rusqlite::types::Type::Text, 
-                Box::new(e)
-            ))?;
+    #[test]
+    fn trends_bucket_reads_by_month_and_fill_empty_months_with_zeros() {
+        let (db, ids) = library(&[(Topic::AncientEgypt, "Giza"), (Topic::Viking, "Hedeby")]);
+        for interaction in [
+            read_on(ids[0], 2026, 1, 31),
+            read_on(ids[1], 2026, 2, 1),
+            read_on(ids[0], 2026, 2, 28),
+            read_on(ids[1], 2026, 4, 1),
+        ] {
+            db.record_interaction(&interaction).unwrap();
+        }
 
-        let created_at_str: String = row.get(6)?;
-        let created_at = chrono::DateTime::parse_from_rfc3339(&created_at_str)
-            .map_err(|e| rusqlite::Error::FromSqlConversionFailure(
-                6, 
-                rusqlite::types::Type::Text, 
-                Box::new(e)
-            ))?
-            .with_timezone(&chrono::Utc);
+        let trend = db.get_topic_trend(TrendBucket::Monthly).unwrap();
+        assert_eq!(trend.labels, ["2026-01", "2026-02", "2026-03", "2026-04"]);
+        assert_eq!(
+            trend.topics,
+            vec![(Topic::AncientEgypt, vec![1, 1, 0, 0]), (Topic::Viking, vec![0, 1, 0, 1])]
+        );
 
-        Ok(ContentUnit {
-            id: row.get(0)?,
-            topic,
-            title: row.get(2)?,
-            content: row.get(3)?,
-            source_url: row.get(4)?,
-            word_count: row.get(5)?,
-            created_at,
-        })
+        assert!(Database::new_in_memory().unwrap().get_topic_trend(TrendBucket::Monthly).unwrap().labels.is_empty());
     }
 
-    /// Record a user interaction with content
-    /// This demonstrates enum serialization and database transactions
-    pub fn record_interaction(&self, interaction: &UserInteraction) -> Result<()> {
-        let (interaction_type, content_id, timestamp, duration) = match interaction {
-            UserInteraction::FullyRead { content_id, timestamp, reading_time_seconds } => {
-                ("fully_read", *content_id, timestamp, *reading_time_seconds)
-            }
-            UserInteraction::Skipped { content_id, timestamp, skip_time_seconds } => {
-                ("skipped", *content_id, timestamp, *skip_time_seconds)
-            }
-        };
-
-        self.conn.execute(
-            "INSERT INTO user_interactions (content_id, interaction_type, timestamp, duration_seconds)
-             VALUES (?1, ?2, ?3, ?4)",
-            params![
-                content_id,
-                interaction_type,
-                timestamp.to_rfc3339(),
-                duration
-            ],
-        )?;
+    #[test]
+    fn weekly_trends_run_monday_to_sunday_across_month_ends() {
+        let (db, ids) = library(&[(Topic::AncientEgypt, "Giza")]);
+        // Saturday 31 January and Sunday 1 February share a week; Monday 2 February starts the next
+        for day in [(1, 31), (2, 1), (2, 2), (2, 16)] {
+            db.record_interaction(&read_on(ids[0], 2026, day.0, day.1)).unwrap();
+        }
 
-        Ok(())
+        let trend = db.get_topic_trend(TrendBucket::Weekly).unwrap();
+        assert_eq!(trend.labels, ["2026-W05", "2026-W06", "2026-W07", "2026-W08"]);
+        assert_eq!(trend.topics, vec![(Topic::AncientEgypt, vec![2, 1, 0, 1])]);
     }
 
-    /// Calculate topic preferences based on user interactions
-    /// This demonstrates data aggregation and HashMap usage
-    fn get_topic_preferences(&self) -> Result<HashMap<Topic, f64>> {
-        let mut stmt = self.conn.prepare(
-            "SELECT c.topic, ui.interaction_type, COUNT(*) as count
-             FROM user_interactions ui
-             JOIN content c ON ui.content_id = c.id
-             GROUP BY c.topic, ui.interaction_type"
-        )?;
+    #[test]
+    fn trends_leave_out_deleted_stories_and_reads_dated_in_the_future() {
+        let (db, ids) = library(&[(Topic::AncientEgypt, "Giza"), (Topic::Viking, "Hedeby")]);
+        for interaction in [read_at(ids[0], -60), read_at(ids[1], -60), read_at(ids[0], 90 * 24 * 60)] {
+            db.record_interaction(&interaction).unwrap();
+        }
+        db.delete_content(ids[1]).unwrap();
 
-        let rows = stmt.query_map([], |row| {
-            let topic_str: String = row.get(0)?;
-            let interaction_type: String = row.get(1)?;
-            let count: i64 = row.get(2)?;
-            Ok((topic_str, interaction_type, count))
-        })?;
+        let trend = db.get_topic_trend(TrendBucket::Monthly).unwrap();
+        assert_eq!(trend.labels.len(), 1, "{:?}", trend.labels);
+        assert_eq!(trend.topics, vec![(Topic::AncientEgypt, vec![1])]);
+    }
 
-        let mut topic_stats: HashMap<Topic, (i64, i64)> = HashMap::new(); // (fully_read, skipped)
+    #[test]
+    fn inserts_fall_back_to_the_last_rowid_before_sqlite_3_35() {
+        assert!(!supports_returning(3_034_001));
+        assert!(supports_returning(3_035_000));
+        assert!(supports_returning(3_045_000));
+        let linked = Database::new_in_memory().unwrap();
+        assert_eq!(linked.supports_returning, supports_returning(rusqlite::version_number()));
 
-        for row_result in rows {
-            let (topic_str, interaction_type, count) = row_result?;
-            let topic: Topic = serde_json::from_str(&topic_str)?;
-            
-            let entry = topic_stats.entry(topic).or_insert((0, 0));
-            match interaction_type.as_str() {
-                "fully_read" => entry.0 += count,
-                "skipped" => entry.1 += count,
-                _ => {} // Ignore unknown interaction types
-            }
+        let (db, ids) = library(&[(Topic::AncientEgypt, "Giza")]);
+        let db = Database { supports_returning: false, ..db };
+        let forum = add_story(&db, Topic::AncientRome, "Forum", "The story of Forum.");
+        let hedeby = add_story(&db, Topic::Viking, "Hedeby", "The story of Hedeby.");
+        assert_eq!([forum, hedeby], [ids[0] + 1, ids[0] + 2]);
+        for (id, title) in [(ids[0], "Giza"), (forum, "Forum"), (hedeby, "Hedeby")] {
+            let stored: String =
+                db.conn.query_row("SELECT title FROM content WHERE id = ?1", [id], |row| row.get(0)).unwrap();
+            assert_eq!(stored, title);
         }
+    }
 
-        // Calculate preference scores (simple ratio of fully_read to total)
-        let mut preferences = HashMap::new();
-        for (topic, (fully_read, skipped)) in topic_stats {
-            let total = fully_read + skipped;
-            if total > 0 {
-                let score = fully_read as f64 / total as f64;
-                preferences.insert(topic, score);
-            }
+    /// A library read in the order Giza, Forum, Hedeby over the last three hours, with two
+    /// reads stamped two days ahead (as if the clock had since gone back), a third inside
+    /// the tolerance, and the rows inserted out of order
+    fn skewed_library() -> (Database, Vec<i64>) {
+        let (db, ids) = library(&[
+            (Topic::AncientEgypt, "Giza"),
+            (Topic::AncientRome, "Forum"),
+            (Topic::Viking, "Hedeby"),
+            (Topic::Medieval, "Cluny"),
+        ]);
+        for interaction in [
+            read_at(ids[3], 2 * 24 * 60),
+            read_at(ids[2], -60),
+            read_at(ids[0], -180),
+            read_at(ids[3], 2 * 24 * 60 + 5),
+            read_at(ids[1], -120),
+            read_at(ids[0], CLOCK_SKEW_TOLERANCE_MINUTES / 2),
+        ] {
+            db.record_interaction(&interaction).unwrap();
         }
+        (db, ids)
+    }
 
-        Ok(preferences)
+    #[test]
+    fn interactions_dated_past_the_tolerance_are_counted_as_future() {
+        let (db, _) = skewed_library();
+        assert_eq!(db.count_future_interactions().unwrap(), 2);
+
+        // Every profile's are counted
+        db.create_profile("Ada").unwrap();
+        let db = db.with_profile("Ada").unwrap();
+        assert_eq!(db.count_future_interactions().unwrap(), 2);
     }
 
-    /// Get the total number of content units in the database
-    pub fn get_content_count(&self) -> Result<i64> {
-        let count = self.conn.query_row(
-            "SELECT COUNT(*) FROM content",
-            [],
-            |row| row.get::<_, i64>(0),
-        )?;
-        Ok(count)
+    #[test]
+    fn recent_topics_follow_the_timestamps_and_leave_out_future_ones() {
+        let (db, _) = skewed_library();
+        assert_eq!(
+            db.get_recent_topics(10).unwrap(),
+            [Topic::AncientEgypt, Topic::Viking, Topic::AncientRome, Topic::AncientEgypt]
+        );
     }
 
-    /// Check if we have content for all topics
-    pub fn has_content_for_all_topics(&self) -> Result<bool> {
-        let topic_count = self.conn.query_row(
-            "SELECT COUNT(DISTINCT topic) FROM content",
-            [],
-            |row| row.get::<_, i64>(0),
-        )?;
-        
-        Ok(topic_count == Topic::all().len() as i64)
+    #[test]
+    fn future_reads_neither_hide_resumable_stories_nor_upset_the_stats() {
+        let (db, ids) = skewed_library();
+        // Skipped near the end an hour ago; a read stamped in the future doesn't count as later
+        db.record_interaction(&UserInteraction::Skipped {
+            content_id: ids[3],
+            timestamp: chrono::Utc::now() - chrono::Duration::hours(1),
+            skip_time_seconds: 30,
+            displayed_chars: Some(400),
+            percent_displayed: Some(80),
+        })
+        .unwrap();
+        let resumable: Vec<i64> =
+            db.get_resume_candidates().unwrap().iter().map(|candidate| candidate.content.id).collect();
+        assert_eq!(resumable, [ids[3]]);
+
+        let stats = db.get_interaction_stats().unwrap();
+        assert_eq!((stats.total, stats.fully_read, stats.skipped), (7, 6, 1));
+        assert_eq!(stats.avg_reading_time_seconds, Some(60.0));
+        let months = db.get_monthly_summary().unwrap();
+        assert_eq!(months.iter().map(|month| month.fully_read).sum::<i64>(), 6);
+        assert!(months.windows(2).all(|pair| pair[0].month < pair[1].month));
     }
-} 
\ No newline at end of file
+}
@@ -0,0 +1,160 @@
+// export.rs - Export reading data to portable formats
+// These functions write to any `io::Write`, so frontends decide where the
+// data ends up (a file picked in a dialog, stdout, a buffer...)
+
+use crate::{Database, Result};
+use std::io::Write;
+
+/// Write the full interaction history as CSV, with timestamps in local time
+/// Returns the number of data rows written (excluding the header)
+pub fn export_interactions_csv<W: Write>(db: &Database, mut writer: W) -> Result<usize> {
+    let history = db.get_interaction_history()?;
+
+    writeln!(writer, "timestamp,content_id,topic,title,interaction,duration_seconds")?;
+    for record in &history {
+        writeln!(
+            writer,
+            "{},{},{},{},{},{}",
+            csv_field(&local_timestamp(&record.timestamp)),
+            record.content_id,
+            csv_field(&record.topic),
+            csv_field(&record.title),
+            csv_field(&record.interaction_type),
+            record.duration_seconds
+        )?;
+    }
+
+    writer.flush()?;
+    Ok(history.len())
+}
+
+/// Write a month-by-month reading report as a Markdown table
+/// Returns the number of months included in the report
+pub fn export_monthly_report_markdown<W: Write>(db: &Database, mut writer: W) -> Result<usize> {
+    let months = db.get_monthly_summary()?;
+
+    writeln!(writer, "# tellme monthly reading report")?;
+    writeln!(writer)?;
+
+    if months.is_empty() {
+        writeln!(writer, "No reading activity recorded yet.")?;
+    } else {
+        writeln!(writer, "| Month | Fully read | Skipped | Completion | Reading time |")?;
+        writeln!(writer, "|-------|-----------:|--------:|-----------:|-------------:|")?;
+        for month in &months {
+            let total = month.fully_read + month.skipped;
+            let completion = if total > 0 {
+                month.fully_read as f64 / total as f64 * 100.0
+            } else {
+                0.0
+            };
+            writeln!(
+                writer,
+                "| {} | {} | {} | {:.0}% | {}m |",
+                month.month,
+                month.fully_read,
+                month.skipped,
+                completion,
+                month.reading_seconds / 60
+            )?;
+        }
+    }
+
+    writer.flush()?;
+    Ok(months.len())
+}
+
+/// Write the active profile's bookmarks as a Markdown list
+/// Returns the number of stories listed
+pub fn export_bookmarks_markdown<W: Write>(db: &Database, mut writer: W) -> Result<usize> {
+    let bookmarks = db.get_bookmarks()?;
+
+    writeln!(writer, "# tellme bookmarks")?;
+    writeln!(writer)?;
+
+    if bookmarks.is_empty() {
+        writeln!(writer, "No bookmarked stories yet.")?;
+    }
+    for content in &bookmarks {
+        let title = markdown_text(&content.title);
+        if content.source_url.is_empty() {
+            writeln!(writer, "- {} ({})", title, content.topic)?;
+        } else {
+            writeln!(writer, "- [{}](<{}>) ({})", title, content.source_url, content.topic)?;
+        }
+    }
+
+    writer.flush()?;
+    Ok(bookmarks.len())
+}
+
+/// Convert a stored UTC RFC 3339 timestamp to local time, keeping the offset so it
+/// stays unambiguous. Unparseable values are passed through unchanged
+fn local_timestamp(timestamp: &str) -> String {
+    chrono::DateTime::parse_from_rfc3339(timestamp)
+        .map(|t| t.with_timezone(&chrono::Local).to_rfc3339_opts(chrono::SecondsFormat::Secs, false))
+        .unwrap_or_else(|_| timestamp.to_string())
+}
+
+/// Quote a CSV field if it contains separators, quotes or line breaks
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Escape characters that Markdown would read as formatting or link syntax
+fn markdown_text(value: &str) -> String {
+    value.chars().fold(String::new(), |mut escaped, c| {
+        if matches!(c, '\\' | '[' | ']' | '*' | '_' | '`' | '<' | '>') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+        escaped
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ContentUnit, Topic};
+
+    #[test]
+    fn bookmarks_are_listed_as_markdown_links_by_title() {
+        let db = Database::new_in_memory().unwrap();
+        let mut stories = [
+            ContentUnit::new(Topic::Viking, "Hedeby".into(), "A trading town.".into(), String::new()),
+            ContentUnit::new(
+                Topic::AncientRome,
+                "Forum [Romanum]".into(),
+                "The heart of the city.".into(),
+                "https://en.wikipedia.org/wiki/Roman_Forum".into(),
+            ),
+            ContentUnit::new(Topic::AncientEgypt, "Giza".into(), "Pyramids.".into(), String::new()),
+        ];
+        for story in &mut stories {
+            db.insert_content(story).unwrap();
+        }
+        db.add_bookmark(stories[0].id).unwrap();
+        db.add_bookmark(stories[1].id).unwrap();
+
+        let mut markdown = Vec::new();
+        assert_eq!(export_bookmarks_markdown(&db, &mut markdown).unwrap(), 2);
+        assert_eq!(
+            String::from_utf8(markdown).unwrap(),
+            format!(
+                "# tellme bookmarks\n\n- [Forum \\[Romanum\\]](<https://en.wikipedia.org/wiki/Roman_Forum>) ({})\n\
+                 - Hedeby ({})\n",
+                Topic::AncientRome,
+                Topic::Viking
+            )
+        );
+
+        let mut empty = Vec::new();
+        let other = Database::new_in_memory().unwrap();
+        assert_eq!(export_bookmarks_markdown(&other, &mut empty).unwrap(), 0);
+        assert!(String::from_utf8(empty).unwrap().ends_with("No bookmarked stories yet.\n"));
+    }
+}
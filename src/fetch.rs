@@ -0,0 +1,1700 @@
+// fetch.rs - Wikipedia content fetching
+// Shared by the fetch_data binary and the reader's fetch screen. A run reports
+// what it is doing as `FetchEvent`s over a channel, so each front end can show
+// progress its own way.
+
+use crate::{
+    config::FetchConfig,
+    content::{
+        calculate_content_quality_score, extract_fun_facts, hook_score, split_sentences,
+        ContentUnit, TermSummary, Topic, FUN_FACTS_PER_ARTICLE,
+    },
+    database::{Database, DuplicateContent},
+    Config, Result, DB_FILE, FETCH_CHECKPOINT_FILE,
+};
+use futures::StreamExt;
+use governor::{DefaultDirectRateLimiter, Quota, RateLimiter};
+use rand::seq::SliceRandom;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    collections::{HashSet, VecDeque},
+    path::{Path, PathBuf},
+    sync::{mpsc, Arc},
+    time::Duration,
+};
+
+/// Target number of units per topic (REDUCED for focused historical content!)
+/// With 21 historical periods, this gives us ~525 total units (quality over quantity)
+pub const UNITS_PER_TOPIC: usize = 25;
+
+/// Search results requested per query
+pub const SEARCH_LIMIT: usize = 50;
+
+/// Something that happened during a fetch run
+#[derive(Debug, Clone)]
+pub enum FetchEvent {
+    /// The run is starting over `topics`, in the order each round visits them; those in
+    /// `finished_earlier` were finished by an earlier, interrupted run
+    Started {
+        topics: Vec<Topic>,
+        finished_earlier: Vec<Topic>,
+        units_per_topic: usize,
+    },
+    /// Work on a topic began, `units` of them already stored by an interrupted run
+    TopicStarted { topic: Topic, units: usize },
+    Searching { topic: Topic, query: String },
+    /// A topic's searches are done, leaving `titles` articles to try
+    TopicQueued { topic: Topic, titles: usize },
+    Fetching { topic: Topic, query: String, title: String },
+    /// The title searched for redirects to another article, which is used instead
+    Redirected { from: String, to: String },
+    /// The article has no intro, so its body is cut into units instead
+    FromBody { title: String, chars: usize },
+    /// The article's intro was too long for one unit and is being cut by sentence
+    LongIntro { title: String, chars: usize },
+    /// A unit was stored; `units` is the topic's count so far
+    UnitAdded {
+        topic: Topic,
+        title: String,
+        units: usize,
+        quality: i32,
+        hook: i32,
+    },
+    FunFactAdded { topic: Topic, text: String },
+    /// A unit wasn't stored because the same content already is
+    DuplicateSkipped { topic: Topic, title: String },
+    NoContent { title: String },
+    /// Nothing the article was cut into passed the quality checks
+    Rejected { title: String },
+    /// Fetching or storing one article failed; the run carries on
+    ArticleFailed {
+        title: String,
+        kind: FailureKind,
+        error: String,
+    },
+    TopicFinished {
+        topic: Topic,
+        units: usize,
+        blocked_titles: usize,
+    },
+    /// A topic stopped early on an error; the other topics carry on
+    TopicFailed { topic: Topic, error: String },
+    /// Every topic is done
+    Finished { units: usize, blocked_titles: usize },
+    /// The run stopped early and can be resumed from its checkpoint
+    Failed { error: String },
+}
+
+/// Seconds of database replication lag at which Wikipedia should turn our requests away
+/// (Wikimedia asks bulk clients to send `maxlag` so they back off when servers are busy)
+pub const MAXLAG_SECONDS: u32 = 5;
+
+/// Times a request is retried after a maxlag error before giving up
+const MAXLAG_RETRIES: u32 = 5;
+
+/// Wait before retrying a maxlag error when the response doesn't suggest one
+const DEFAULT_RETRY_AFTER: Duration = Duration::from_secs(5);
+
+/// Contact sent in the user agent when `[fetch] contact` isn't set
+const DEFAULT_CONTACT: &str = "https://github.com/xeij/tellme";
+
+/// Error for a request Wikipedia answered with an error status
+/// Check for it with `err.is::<HttpStatus>()`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HttpStatus(pub u16);
+
+impl std::fmt::Display for HttpStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Wikipedia answered with HTTP {}", self.0)
+    }
+}
+
+impl std::error::Error for HttpStatus {}
+
+/// Error for a response that is JSON but not shaped as expected; names what was missing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnexpectedResponse(pub &'static str);
+
+impl std::fmt::Display for UnexpectedResponse {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Unexpected response from Wikipedia (no {})", self.0)
+    }
+}
+
+impl std::error::Error for UnexpectedResponse {}
+
+/// Why a request or an article came to nothing, for the run summary
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FailureKind {
+    /// DNS failures, refused connections, timeouts and dropped responses
+    Network,
+    /// Wikipedia answered with an error status
+    HttpStatus,
+    /// The response wasn't JSON, or not shaped as expected
+    BadResponse,
+    /// The article was fetched, but none of it passed the quality checks
+    Rejected,
+    /// Anything else, such as a failed database write
+    Other,
+}
+
+impl FailureKind {
+    /// The kind of failure behind an error from the fetch pipeline
+    pub fn classify(error: &anyhow::Error) -> Self {
+        if error.is::<HttpStatus>() {
+            FailureKind::HttpStatus
+        } else if error.is::<UnexpectedResponse>() || error.is::<serde_json::Error>() {
+            FailureKind::BadResponse
+        } else if let Some(e) = error.downcast_ref::<reqwest::Error>() {
+            if e.is_status() {
+                FailureKind::HttpStatus
+            } else if e.is_decode() {
+                FailureKind::BadResponse
+            } else {
+                FailureKind::Network
+            }
+        } else {
+            FailureKind::Other
+        }
+    }
+
+    /// Short name for console output
+    pub fn as_str(self) -> &'static str {
+        match self {
+            FailureKind::Network => "network",
+            FailureKind::HttpStatus => "http",
+            FailureKind::BadResponse => "response",
+            FailureKind::Rejected => "rejected",
+            FailureKind::Other => "other",
+        }
+    }
+}
+
+/// Sections at the end of an article that are reference material rather than reading
+const TRAILING_SECTIONS: [&str; 9] = [
+    "See also",
+    "Notes",
+    "Footnotes",
+    "References",
+    "Citations",
+    "Sources",
+    "Bibliography",
+    "Further reading",
+    "External links",
+];
+
+/// Text of one article, as fetched
+#[derive(Debug, Clone)]
+pub struct Article {
+    /// Title after Wikipedia resolved redirects and normalised it
+    pub title: String,
+    pub text: String,
+    pub url: String,
+    /// Whether the text is the article's body, because it has no intro to use
+    pub from_body: bool,
+}
+
+/// Wikipedia API client for fetching articles
+/// This struct demonstrates HTTP client usage and rate limiting
+#[derive(Clone)]
+pub struct WikipediaClient {
+    client: Client,
+    /// Paces every request this client and its clones send, however many run at once
+    limiter: Arc<DefaultDirectRateLimiter>,
+    request_interval: Duration,
+    /// Fetched text with a larger share of numbers is left out (`fetch.max_numeric_ratio`)
+    max_numeric_ratio: f64,
+    /// Articles a run fetches at once (`fetch.concurrency`)
+    concurrency: usize,
+    base_url: String,
+    /// Root of the REST API, which serves page summaries
+    rest_url: String,
+}
+
+impl Default for WikipediaClient {
+    fn default() -> Self {
+        Self::new(&FetchConfig::default())
+    }
+}
+
+impl WikipediaClient {
+    /// Create a new Wikipedia client, identifying itself with the configured contact
+    pub fn new(config: &FetchConfig) -> Self {
+        let client = Client::builder()
+            .user_agent(user_agent(config.contact.as_deref()))
+            .timeout(Duration::from_secs(30))
+            .build()
+            .expect("Failed to create HTTP client");
+
+        // One request per interval, with no bursts, so the cap holds for any concurrency
+        let request_interval = config.request_interval();
+        let quota = Quota::with_period(request_interval).expect("request interval is never zero");
+
+        Self {
+            client,
+            limiter: Arc::new(RateLimiter::direct(quota)),
+            request_interval,
+            max_numeric_ratio: config.max_numeric_ratio,
+            concurrency: config.article_concurrency(),
+            base_url: config.api_url.clone(),
+            rest_url: config.rest_url.clone(),
+        }
+    }
+
+    /// Shortest time between two requests from this client
+    pub fn request_interval(&self) -> Duration {
+        self.request_interval
+    }
+
+    /// Largest share of numbers accepted in fetched text
+    pub fn max_numeric_ratio(&self) -> f64 {
+        self.max_numeric_ratio
+    }
+
+    /// Articles a run fetches at once
+    pub fn concurrency(&self) -> usize {
+        self.concurrency
+    }
+
+    /// Look up the summary Wikipedia gives for `term`, following redirects
+    /// Returns `None` when there is no article by that name or it is a disambiguation page
+    pub async fn get_term_summary(&self, term: &str) -> Result<Option<TermSummary>> {
+        let url = format!(
+            "{}/page/summary/{}",
+            self.rest_url,
+            urlencoding::encode(&term.replace(' ', "_"))
+        );
+        self.limiter.until_ready().await;
+        let response = self.client.get(&url).send().await?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let json: Value = response.error_for_status()?.json().await?;
+        Ok(parse_term_summary(term, &json))
+    }
+
+    /// Call the API with `params`, adding the JSON format and `maxlag` to every request
+    /// Each attempt first waits for its turn under the request rate cap.
+    /// When the servers are lagging, waits as long as the response asks (Retry-After)
+    /// and tries again, up to `MAXLAG_RETRIES` times
+    pub async fn api_get(&self, params: &[(&str, &str)]) -> Result<Value> {
+        let maxlag = MAXLAG_SECONDS.to_string();
+        let mut retries = 0;
+        loop {
+            self.limiter.until_ready().await;
+            let response = self
+                .client
+                .get(&self.base_url)
+                .query(params)
+                .query(&[("format", "json"), ("maxlag", maxlag.as_str())])
+                .send()
+                .await?;
+            let status = response.status();
+            let retry_after = response
+                .headers()
+                .get(reqwest::header::RETRY_AFTER)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string);
+            // Error pages are usually HTML, but a maxlag error can come with an error status
+            let json: Value = match serde_json::from_str(&response.text().await?) {
+                Ok(json) => json,
+                Err(_) if !status.is_success() => return Err(HttpStatus(status.as_u16()).into()),
+                Err(e) => return Err(e.into()),
+            };
+
+            match maxlag_delay(&json, retry_after.as_deref()) {
+                None if !status.is_success() => return Err(HttpStatus(status.as_u16()).into()),
+                None => return Ok(json),
+                Some(_) if retries >= MAXLAG_RETRIES => {
+                    anyhow::bail!("Wikipedia is too busy (still lagging after {} retries)", retries)
+                }
+                Some(delay) => {
+                    retries += 1;
+                    tokio::time::sleep(delay).await;
+                }
+            }
+        }
+    }
+
+    /// Search for articles on a given topic
+    /// This demonstrates async HTTP requests and JSON parsing
+    pub async fn search_articles(&self, query: &str, limit: usize) -> Result<Vec<String>> {
+        let limit = limit.to_string();
+        let json = self
+            .api_get(&[
+                ("action", "opensearch"),
+                ("search", query),
+                ("limit", &limit),
+                ("namespace", "0"),
+            ])
+            .await?;
+        
+        // The OpenSearch response lists the matching titles second
+        let titles = json
+            .get(1)
+            .and_then(|v| v.as_array())
+            .ok_or(UnexpectedResponse("search results"))?;
+        Ok(titles.iter().filter_map(|v| v.as_str()).map(|s| s.to_string()).collect())
+    }
+
+    /// Get the content of a Wikipedia article, following redirects
+    /// Articles without an intro extract fall back to their body, minus the reference
+    /// sections at the end
+    pub async fn get_article_content(&self, title: &str) -> Result<Option<Article>> {
+        if let Some(article) = self.get_extract(title, true).await? {
+            return Ok(Some(article));
+        }
+        self.get_extract(title, false).await
+    }
+
+    /// Fetch the intro (`intro_only`) or the whole text of an article
+    /// None when the page is missing or the extract is empty
+    async fn get_extract(&self, title: &str, intro_only: bool) -> Result<Option<Article>> {
+        let mut params = vec![
+            ("action", "query"),
+            ("titles", title),
+            ("redirects", "1"),
+            ("prop", "extracts"),
+            ("explaintext", ""),
+        ];
+        if intro_only {
+            params.extend([("exintro", ""), ("exsectionformat", "plain")]);
+        } else {
+            // Headings are needed to find where the reference sections start
+            params.push(("exsectionformat", "wiki"));
+        }
+        let json = self.api_get(&params).await?;
+
+        // Navigate the complex Wikipedia API response structure
+        let page = json
+            .get("query")
+            .and_then(|q| q.get("pages"))
+            .and_then(Value::as_object)
+            .and_then(|pages| pages.values().next())
+            .ok_or(UnexpectedResponse("pages"))?;
+        let Some(extract) = page.get("extract").and_then(Value::as_str) else {
+            return Ok(None);
+        };
+        let text = if intro_only { extract.trim().to_string() } else { article_body(extract) };
+        if text.is_empty() {
+            return Ok(None);
+        }
+
+        let title = page.get("title").and_then(Value::as_str).unwrap_or(title).to_string();
+        let url = format!("https://en.wikipedia.org/wiki/{}", urlencoding::encode(&title));
+        Ok(Some(Article {
+            title,
+            text,
+            url,
+            from_body: !intro_only,
+        }))
+    }
+}
+
+/// The readable part of a full article extract with `== Heading ==` lines: everything
+/// before the first reference section, without the headings themselves
+fn article_body(extract: &str) -> String {
+    let mut paragraphs = Vec::new();
+    for line in extract.lines().map(str::trim) {
+        if line.starts_with("==") && line.ends_with("==") {
+            let heading = line.trim_matches('=').trim();
+            let level = line.len() - line.trim_start_matches('=').len();
+            if level == 2 && TRAILING_SECTIONS.iter().any(|s| s.eq_ignore_ascii_case(heading)) {
+                break;
+            }
+        } else if !line.is_empty() {
+            paragraphs.push(line);
+        }
+    }
+    paragraphs.join("\n")
+}
+
+/// User agent naming tellme, its version and how to reach whoever runs it
+fn user_agent(contact: Option<&str>) -> String {
+    let contact = contact.map(str::trim).filter(|c| !c.is_empty()).unwrap_or(DEFAULT_CONTACT);
+    format!("tellme/{} ({})", env!("CARGO_PKG_VERSION"), contact)
+}
+
+/// How long to wait before retrying, if `json` is a maxlag error
+/// Prefers the Retry-After header, then the lag the error reports, then a default
+fn maxlag_delay(json: &Value, retry_after: Option<&str>) -> Option<Duration> {
+    let error = json.get("error").filter(|e| e.get("code").and_then(Value::as_str) == Some("maxlag"))?;
+    let seconds = retry_after
+        .and_then(|value| value.trim().parse::<u64>().ok())
+        .or_else(|| error.get("lag").and_then(Value::as_f64).map(|lag| lag.ceil() as u64));
+    Some(seconds.map_or(DEFAULT_RETRY_AFTER, Duration::from_secs))
+}
+
+/// Compiled title blocklist built from the fetch configuration
+/// Titles matching any pattern are skipped before their content is fetched
+pub struct TitleBlocklist {
+    substrings: Vec<String>,
+    regexes: Vec<regex::Regex>,
+}
+
+impl TitleBlocklist {
+    /// Compile the configured patterns, rejecting invalid regexes up front
+    pub fn from_config(config: &FetchConfig) -> Result<Self> {
+        Self::from_patterns(config.title_blocklist())
+    }
+
+    /// Compile patterns: `regex:` followed by a regular expression, or else a
+    /// case-insensitive substring
+    pub fn from_patterns<'a>(patterns: impl IntoIterator<Item = &'a str>) -> Result<Self> {
+        let mut substrings = Vec::new();
+        let mut regexes = Vec::new();
+
+        for pattern in patterns {
+            if let Some(expr) = pattern.strip_prefix("regex:") {
+                let re = regex::Regex::new(expr)
+                    .map_err(|e| anyhow::anyhow!("Invalid blocklist regex '{}': {}", expr, e))?;
+                regexes.push(re);
+            } else {
+                substrings.push(pattern.to_lowercase());
+            }
+        }
+
+        Ok(Self { substrings, regexes })
+    }
+
+    /// Check whether a title matches any blocklist pattern
+    pub fn is_blocked(&self, title: &str) -> bool {
+        let title_lower = title.to_lowercase();
+        self.substrings.iter().any(|s| title_lower.contains(s.as_str()))
+            || self.regexes.iter().any(|re| re.is_match(title))
+    }
+}
+
+/// Per-topic fetch results used for the final summary
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TopicFetchStats {
+    pub units: usize,
+    pub blocked_titles: usize,
+    /// Units left out because the same content was already stored
+    #[serde(default)]
+    pub duplicates: usize,
+    /// Searches and article fetches attempted
+    #[serde(default)]
+    pub requests: usize,
+    #[serde(default)]
+    pub failures: FailureCounts,
+}
+
+/// Failed requests and rejected articles, by kind
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct FailureCounts {
+    pub network: usize,
+    pub http_status: usize,
+    pub bad_response: usize,
+    pub rejected: usize,
+    pub other: usize,
+}
+
+impl FailureCounts {
+    pub fn add(&mut self, kind: FailureKind) {
+        match kind {
+            FailureKind::Network => self.network += 1,
+            FailureKind::HttpStatus => self.http_status += 1,
+            FailureKind::BadResponse => self.bad_response += 1,
+            FailureKind::Rejected => self.rejected += 1,
+            FailureKind::Other => self.other += 1,
+        }
+    }
+
+    fn plus(self, other: Self) -> Self {
+        Self {
+            network: self.network + other.network,
+            http_status: self.http_status + other.http_status,
+            bad_response: self.bad_response + other.bad_response,
+            rejected: self.rejected + other.rejected,
+            other: self.other + other.other,
+        }
+    }
+}
+
+/// An article to try for a topic, with the search query that found it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkItem {
+    pub query: String,
+    pub title: String,
+}
+
+/// One topic's share of a fetch run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TopicQueue {
+    pub topic: Topic,
+    /// Whether the topic's searches have run and filled `queue`
+    pub searched: bool,
+    /// Articles found by the searches and not tried yet, in search order
+    pub queue: VecDeque<WorkItem>,
+    /// Results so far for this topic
+    pub stats: TopicFetchStats,
+    /// Whether the topic reached its target, ran out of articles or failed
+    pub finished: bool,
+}
+
+impl TopicQueue {
+    fn new(topic: Topic) -> Self {
+        Self {
+            topic,
+            searched: false,
+            queue: VecDeque::new(),
+            stats: TopicFetchStats::default(),
+            finished: false,
+        }
+    }
+
+    /// Units fetched as a percentage of `target`, at most 100
+    pub fn percent_of(&self, target: usize) -> usize {
+        (self.stats.units * 100 / target.max(1)).min(100)
+    }
+}
+
+/// How far a fetch run has got, saved after every article so an interrupted run can
+/// continue with `--resume` instead of searching everything again
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Checkpoint {
+    /// Every topic of the run, in the (shuffled) order each round visits them
+    pub topics: Vec<TopicQueue>,
+    /// File the checkpoint is saved to, `FETCH_CHECKPOINT_FILE` unless set otherwise
+    #[serde(skip, default = "default_checkpoint_path")]
+    path: PathBuf,
+}
+
+fn default_checkpoint_path() -> PathBuf {
+    PathBuf::from(FETCH_CHECKPOINT_FILE)
+}
+
+impl Checkpoint {
+    /// Start a run over `topics`
+    pub fn new(topics: Vec<Topic>) -> Self {
+        Self {
+            topics: topics.into_iter().map(TopicQueue::new).collect(),
+            path: default_checkpoint_path(),
+        }
+    }
+
+    /// Start a run over every topic in a random order
+    pub fn shuffled() -> Self {
+        let mut topics = Topic::all().to_vec();
+        topics.shuffle(&mut rand::thread_rng());
+        Self::new(topics)
+    }
+
+    /// Save to `path` instead of `FETCH_CHECKPOINT_FILE`
+    pub fn with_path(mut self, path: impl AsRef<Path>) -> Self {
+        self.path = path.as_ref().to_path_buf();
+        self
+    }
+
+    /// Read the checkpoint left by an interrupted run, if there is one
+    pub fn load() -> Result<Option<Self>> {
+        Self::load_from(FETCH_CHECKPOINT_FILE)
+    }
+
+    /// Read the checkpoint saved at `path`, if there is one
+    pub fn load_from(path: impl AsRef<Path>) -> Result<Option<Self>> {
+        let path = path.as_ref();
+        match std::fs::read_to_string(path) {
+            Ok(text) => serde_json::from_str::<Self>(&text)
+                .map(|checkpoint| Some(checkpoint.with_path(path)))
+                .map_err(|e| {
+                    anyhow::anyhow!("Invalid checkpoint {}: {} (delete it to start over)", path.display(), e)
+                }),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Write the checkpoint, replacing the previous one in a single rename
+    pub fn save(&self) -> Result<()> {
+        let mut tmp = self.path.clone().into_os_string();
+        tmp.push(".tmp");
+        std::fs::write(&tmp, serde_json::to_string_pretty(self)?)?;
+        std::fs::rename(&tmp, &self.path)?;
+        Ok(())
+    }
+
+    /// Remove the checkpoint once the run has finished
+    pub fn clear(&self) -> Result<()> {
+        match std::fs::remove_file(&self.path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Results over every topic so far
+    pub fn totals(&self) -> TopicFetchStats {
+        self.topics.iter().fold(TopicFetchStats::default(), |totals, queue| TopicFetchStats {
+            units: totals.units + queue.stats.units,
+            blocked_titles: totals.blocked_titles + queue.stats.blocked_titles,
+            duplicates: totals.duplicates + queue.stats.duplicates,
+            requests: totals.requests + queue.stats.requests,
+            failures: totals.failures.plus(queue.stats.failures),
+        })
+    }
+
+    /// Number of topics already finished
+    pub fn finished_topics(&self) -> usize {
+        self.topics.iter().filter(|queue| queue.finished).count()
+    }
+}
+
+/// Intros at least this long (in bytes) are too long for a single unit
+pub const LONG_INTRO_CHARS: usize = 3000;
+
+/// Units kept from a long intro, best quality first
+pub const LONG_INTRO_MAX_UNITS: usize = 3;
+
+/// Minimum quality score for a unit cut from a long intro
+/// Stricter than regular sections, since we only keep a few of many candidates
+const LONG_INTRO_MIN_QUALITY: i32 = 2;
+
+/// Words to gather per unit when splitting a long intro by sentence
+const LONG_INTRO_UNIT_WORDS: usize = 150;
+
+/// Process article content into suitable units
+/// This demonstrates text processing and content validation with QUALITY SCORING
+fn process_article_content(
+    topic: Topic,
+    title: &str,
+    content: &str,
+    source_url: &str,
+    max_numeric_ratio: f64,
+) -> Vec<ContentUnit> {
+    let mut units = process_article_sections(topic, title, content, source_url);
+
+    // Population tables, results and other lists of figures read badly
+    units.retain(|unit| unit.numeric_ratio() <= max_numeric_ratio);
+    units
+}
+
+/// Cut an article into content units, before the numeric check
+fn process_article_sections(topic: Topic, title: &str, content: &str, source_url: &str) -> Vec<ContentUnit> {
+    let mut units = Vec::new();
+    
+    // First, check content quality score
+    let quality_score = calculate_content_quality_score(content, title);
+    
+    // Only process decent quality, engaging content (score > 0, lowered from 3)
+    if quality_score < 0 {
+        return units; // Skip truly boring content
+    }
+    
+    // Very long intros split badly by paragraph, so cut them by sentence instead
+    if content.len() >= LONG_INTRO_CHARS {
+        return split_long_intro(topic, title, content, source_url);
+    }
+
+    // First, try to use the full content if it's not too long
+    if content.len() > 100 {
+        let mut full_unit = ContentUnit::new(
+            topic,
+            title.to_string(),
+            content.to_string(),
+            source_url.to_string(),
+        );
+        
+        full_unit.clean_content();
+        
+        if full_unit.is_suitable_length() {
+            units.push(full_unit);
+            return units; // Return the full content if it's suitable
+        }
+    }
+    
+    // If full content is too long, split into sections
+    let sections: Vec<&str> = content
+        .split("\n\n")
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty() && s.len() > 30)
+        .collect();
+
+    // Try to create content units from sections
+    let mut i = 0;
+    while i < sections.len() {
+        let mut unit_content = sections[i].to_string();
+        
+        // If current section is short, try to combine with next sections
+        let mut j = i + 1;
+        while j < sections.len() && unit_content.len() < 400 {
+            unit_content.push_str("\n\n");
+            unit_content.push_str(sections[j]);
+            j += 1;
+        }
+        
+        // Check quality of this specific unit content
+        let unit_quality = calculate_content_quality_score(&unit_content, title);
+        if unit_quality < -1 {
+            i = if j > i + 1 { j } else { i + 1 };
+            continue; // Skip very low-quality sections
+        }
+        
+        let mut content_unit = ContentUnit::new(
+            topic,
+            title.to_string(),
+            unit_content,
+            source_url.to_string(),
+        );
+        
+        content_unit.clean_content();
+        
+        if content_unit.is_suitable_length() {
+            units.push(content_unit);
+        }
+        
+        // Move to the next unprocessed section
+        i = if j > i + 1 { j } else { i + 1 };
+    }
+    
+    units
+}
+
+/// Split a long intro into sentence-aligned units and keep only the best few
+/// Units are ranked by quality score; those under `LONG_INTRO_MIN_QUALITY` are dropped.
+/// Each keeps its position in the intro as `part_index`, so the parts can be read in order
+fn split_long_intro(topic: Topic, title: &str, content: &str, source_url: &str) -> Vec<ContentUnit> {
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    let mut current_words = 0;
+
+    for paragraph in content.lines() {
+        for (i, sentence) in split_sentences(paragraph).into_iter().enumerate() {
+            // Keep paragraph breaks inside a unit
+            if !current.is_empty() {
+                current.push_str(if i == 0 { "\n\n" } else { " " });
+            }
+            current.push_str(sentence);
+            current_words += sentence.split_whitespace().count();
+
+            if current_words >= LONG_INTRO_UNIT_WORDS {
+                chunks.push(std::mem::take(&mut current));
+                current_words = 0;
+            }
+        }
+    }
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+
+    let mut ranked: Vec<(i32, ContentUnit)> = chunks
+        .into_iter()
+        .enumerate()
+        .filter_map(|(part, chunk)| {
+            let quality = calculate_content_quality_score(&chunk, title);
+            if quality < LONG_INTRO_MIN_QUALITY {
+                return None;
+            }
+
+            let mut unit = ContentUnit::new(topic, title.to_string(), chunk, source_url.to_string());
+            unit.part_index = Some(part as u32);
+            unit.clean_content();
+            unit.is_suitable_length().then_some((quality, unit))
+        })
+        .collect();
+
+    ranked.sort_by_key(|(quality, _)| std::cmp::Reverse(*quality));
+    ranked
+        .into_iter()
+        .take(LONG_INTRO_MAX_UNITS)
+        .map(|(_, unit)| unit)
+        .collect()
+}
+
+/// Run a topic's search queries and queue the titles worth fetching
+/// Blocklisted titles are counted and left out, as are titles an earlier query found
+async fn search_topic(
+    client: &WikipediaClient,
+    blocklist: &TitleBlocklist,
+    queue: &mut TopicQueue,
+    events: &mpsc::Sender<FetchEvent>,
+) -> Result<()> {
+    let topic = queue.topic;
+    let mut seen = HashSet::new();
+    for query in topic.search_queries() {
+        events
+            .send(FetchEvent::Searching {
+                topic,
+                query: query.to_string(),
+            })
+            .ok();
+        queue.stats.requests += 1;
+        let titles = client.search_articles(query, SEARCH_LIMIT).await.inspect_err(|e| {
+            queue.stats.failures.add(FailureKind::classify(e));
+        })?;
+        for title in titles {
+            if !seen.insert(title.clone()) {
+                continue;
+            }
+            // Skip disambiguation, list pages and other blocklisted titles
+            if blocklist.is_blocked(&title) {
+                queue.stats.blocked_titles += 1;
+            } else {
+                queue.queue.push_back(WorkItem {
+                    query: query.to_string(),
+                    title,
+                });
+            }
+        }
+    }
+
+    queue.searched = true;
+    events
+        .send(FetchEvent::TopicQueued {
+            topic,
+            titles: queue.queue.len(),
+        })
+        .ok();
+    Ok(())
+}
+
+/// Find the next article to fetch on a topic's queue
+/// Titles already in the library or processed by an earlier run are dropped without a
+/// request; `None` once the queue runs out. The article stays at the front of the queue
+/// until it's stored, so a checkpoint saved meanwhile still has it
+fn next_article(db: &Database, queue: &mut TopicQueue, events: &mpsc::Sender<FetchEvent>) -> Result<Option<WorkItem>> {
+    // Already stored, e.g. by another topic, or already tried by this run before it was
+    // interrupted or by an earlier one
+    let item = loop {
+        let Some(item) = queue.queue.front() else {
+            return Ok(None);
+        };
+        if !db.content_title_exists(&item.title)? && !db.fetch_progress_done(queue.topic, &item.query, &item.title)? {
+            break item.clone();
+        }
+        queue.queue.pop_front();
+    };
+
+    events
+        .send(FetchEvent::Fetching {
+            topic: queue.topic,
+            query: item.query.clone(),
+            title: item.title.clone(),
+        })
+        .ok();
+    queue.stats.requests += 1;
+    Ok(Some(item))
+}
+
+/// Store the units of a fetched article, up to `target_count` for its topic
+/// Each article is marked processed once it's handled, unless fetching it failed
+fn store_article(
+    client: &WikipediaClient,
+    db: &Database,
+    blocklist: &TitleBlocklist,
+    queue: &mut TopicQueue,
+    (item, fetched): (WorkItem, Result<Option<Article>>),
+    target_count: usize,
+    events: &mpsc::Sender<FetchEvent>,
+) -> Result<()> {
+    let emit = |event| {
+        // Nobody may be listening any more, which doesn't stop the run
+        events.send(event).ok();
+    };
+    let topic = queue.topic;
+    let WorkItem { query, title } = item;
+    let units_before = queue.stats.units;
+
+    match fetched {
+        // A redirect can lead to an article that's blocked or already stored
+        Ok(Some(article)) if article.title != title && blocklist.is_blocked(&article.title) => {
+            queue.stats.blocked_titles += 1;
+            db.mark_fetch_progress(topic, &query, &title, 0)?;
+        }
+        Ok(Some(article)) if article.title != title && db.content_title_exists(&article.title)? => {
+            queue.stats.duplicates += 1;
+            db.mark_fetch_progress(topic, &query, &title, 0)?;
+            emit(FetchEvent::DuplicateSkipped {
+                topic,
+                title: article.title,
+            });
+        }
+        Ok(Some(article)) => {
+            if article.title != title {
+                emit(FetchEvent::Redirected {
+                    from: title.clone(),
+                    to: article.title.clone(),
+                });
+            }
+            if article.from_body {
+                emit(FetchEvent::FromBody {
+                    title: article.title.clone(),
+                    chars: article.text.len(),
+                });
+            } else if article.text.len() >= LONG_INTRO_CHARS {
+                emit(FetchEvent::LongIntro {
+                    title: article.title.clone(),
+                    chars: article.text.len(),
+                });
+            }
+            let units = process_article_content(
+                topic,
+                &article.title,
+                &article.text,
+                &article.url,
+                client.max_numeric_ratio(),
+            );
+            if units.is_empty() {
+                queue.stats.failures.add(FailureKind::Rejected);
+                emit(FetchEvent::Rejected {
+                    title: article.title.clone(),
+                });
+            }
+            let mut fun_facts = 0;
+
+            for mut unit in units {
+                match db.insert_content(&mut unit) {
+                    Ok(()) => {
+                        queue.stats.units += 1;
+                        emit(FetchEvent::UnitAdded {
+                            topic,
+                            title: article.title.clone(),
+                            units: queue.stats.units,
+                            quality: calculate_content_quality_score(&unit.content, &unit.title),
+                            hook: hook_score(&unit.content),
+                        });
+                        fun_facts += store_fun_facts(db, &unit, FUN_FACTS_PER_ARTICLE - fun_facts, events);
+                    }
+                    Err(e) if e.is::<DuplicateContent>() => {
+                        queue.stats.duplicates += 1;
+                        emit(FetchEvent::DuplicateSkipped {
+                            topic,
+                            title: article.title.clone(),
+                        });
+                    }
+                    Err(e) => {
+                        let kind = FailureKind::classify(&e);
+                        queue.stats.failures.add(kind);
+                        emit(FetchEvent::ArticleFailed {
+                            title: article.title.clone(),
+                            kind,
+                            error: format!("failed to save unit: {}", e),
+                        });
+                    }
+                }
+
+                if queue.stats.units >= target_count {
+                    break;
+                }
+            }
+            db.mark_fetch_progress(topic, &query, &title, queue.stats.units - units_before)?;
+        }
+        Ok(None) => {
+            db.mark_fetch_progress(topic, &query, &title, 0)?;
+            emit(FetchEvent::NoContent { title });
+        }
+        Err(e) => {
+            let kind = FailureKind::classify(&e);
+            queue.stats.failures.add(kind);
+            emit(FetchEvent::ArticleFailed {
+                title,
+                kind,
+                error: e.to_string(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Store up to `limit` fun facts taken from a stored unit, linked back to it
+/// Fun facts come on top of the topic's unit target. Returns how many were stored
+fn store_fun_facts(
+    db: &Database,
+    unit: &ContentUnit,
+    limit: usize,
+    events: &mpsc::Sender<FetchEvent>,
+) -> usize {
+    let mut stored = 0;
+    for sentence in extract_fun_facts(&unit.content).into_iter().take(limit) {
+        let mut fact = ContentUnit::fun_fact(unit, sentence);
+        let event = match db.insert_content(&mut fact) {
+            Ok(()) => {
+                stored += 1;
+                FetchEvent::FunFactAdded {
+                    topic: unit.topic,
+                    text: fact.content,
+                }
+            }
+            // Already kept from an earlier fetch of the same text
+            Err(e) if e.is::<DuplicateContent>() => continue,
+            Err(e) => FetchEvent::ArticleFailed {
+                title: unit.title.clone(),
+                kind: FailureKind::classify(&e),
+                error: format!("failed to save fun fact: {}", e),
+            },
+        };
+        events.send(event).ok();
+    }
+    stored
+}
+
+/// Fetch every topic left in the checkpoint, saving it as the run goes and removing it
+/// once the run is complete
+/// All searches run first; then each round fetches one article per unfinished topic, so
+/// a run stopped part way leaves every topic about equally served. A round's articles are
+/// fetched `client.concurrency()` at a time and stored one by one as they arrive. Errors
+/// within a topic are reported and skip the rest of that topic; only failing to save the
+/// checkpoint stops the run
+pub async fn run_fetch(
+    client: &WikipediaClient,
+    db: &Database,
+    blocklist: &TitleBlocklist,
+    units_per_topic: usize,
+    checkpoint: &mut Checkpoint,
+    events: &mpsc::Sender<FetchEvent>,
+) -> Result<()> {
+    let emit = |event| {
+        events.send(event).ok();
+    };
+    emit(FetchEvent::Started {
+        topics: checkpoint.topics.iter().map(|queue| queue.topic).collect(),
+        finished_earlier: checkpoint
+            .topics
+            .iter()
+            .filter(|queue| queue.finished)
+            .map(|queue| queue.topic)
+            .collect(),
+        units_per_topic,
+    });
+
+    // Queue every topic's articles up front
+    for i in 0..checkpoint.topics.len() {
+        let queue = &mut checkpoint.topics[i];
+        if queue.finished {
+            continue;
+        }
+        emit(FetchEvent::TopicStarted {
+            topic: queue.topic,
+            units: queue.stats.units,
+        });
+        if queue.searched {
+            continue;
+        }
+        if let Err(e) = search_topic(client, blocklist, queue, events).await {
+            queue.finished = true;
+            emit(FetchEvent::TopicFailed {
+                topic: queue.topic,
+                error: e.to_string(),
+            });
+        }
+        checkpoint.save()?;
+    }
+
+    // Then one article per topic per round until every topic is done
+    while checkpoint.finished_topics() < checkpoint.topics.len() {
+        let mut round = Vec::new();
+        for (i, queue) in checkpoint.topics.iter_mut().enumerate() {
+            if queue.finished || queue.stats.units >= units_per_topic {
+                continue;
+            }
+            match next_article(db, queue, events) {
+                Ok(Some(item)) => round.push((i, item)),
+                Ok(None) => {}
+                Err(e) => {
+                    queue.finished = true;
+                    emit(FetchEvent::TopicFailed {
+                        topic: queue.topic,
+                        error: e.to_string(),
+                    });
+                }
+            }
+        }
+
+        // Only the requests overlap; the database sees one article at a time
+        let mut fetched = futures::stream::iter(round)
+            .map(|(i, item)| async move {
+                let article = client.get_article_content(&item.title).await;
+                (i, (item, article))
+            })
+            .buffer_unordered(client.concurrency());
+        while let Some((i, article)) = fetched.next().await {
+            let queue = &mut checkpoint.topics[i];
+            queue.queue.pop_front();
+            if let Err(e) = store_article(client, db, blocklist, queue, article, units_per_topic, events) {
+                queue.finished = true;
+                emit(FetchEvent::TopicFailed {
+                    topic: queue.topic,
+                    error: e.to_string(),
+                });
+            }
+            // Saved per article, so a run stopped mid-round resumes with the right counts
+            checkpoint.save()?;
+        }
+
+        for queue in checkpoint.topics.iter_mut().filter(|queue| !queue.finished) {
+            if queue.stats.units >= units_per_topic || queue.queue.is_empty() {
+                queue.finished = true;
+                emit(FetchEvent::TopicFinished {
+                    topic: queue.topic,
+                    units: queue.stats.units,
+                    blocked_titles: queue.stats.blocked_titles,
+                });
+            }
+        }
+        checkpoint.save()?;
+    }
+    checkpoint.clear()?;
+
+    let totals = checkpoint.totals();
+    emit(FetchEvent::Finished {
+        units: totals.units,
+        blocked_titles: totals.blocked_titles,
+    });
+    Ok(())
+}
+
+/// Read a REST page summary response, skipping disambiguation pages and empty extracts
+pub fn parse_term_summary(term: &str, json: &Value) -> Option<TermSummary> {
+    if json.get("type").and_then(Value::as_str) == Some("disambiguation") {
+        return None;
+    }
+
+    let title = json.get("title").and_then(Value::as_str)?;
+    let extract = json.get("extract").and_then(Value::as_str)?.trim();
+    if extract.is_empty() {
+        return None;
+    }
+    let source_url = json
+        .pointer("/content_urls/desktop/page")
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .unwrap_or_else(|| {
+            format!("https://en.wikipedia.org/wiki/{}", urlencoding::encode(&title.replace(' ', "_")))
+        });
+
+    Some(TermSummary {
+        term: term.to_string(),
+        title: title.to_string(),
+        extract: extract.to_string(),
+        source_url,
+    })
+}
+
+/// Start a fetch with `client` on its own thread and return the channel its events arrive on
+/// The thread opens its own database connection and continues an interrupted run if
+/// there is one. Clones of `client` elsewhere keep sharing its rate cap. The last event
+/// is always `Finished` or `Failed`
+pub fn spawn_fetch(client: WikipediaClient) -> mpsc::Receiver<FetchEvent> {
+    let (events, receiver) = mpsc::channel();
+    std::thread::spawn(move || {
+        let result = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(anyhow::Error::from)
+            .and_then(|runtime| runtime.block_on(fetch_in_background(&client, &events)));
+        if let Err(e) = result {
+            events.send(FetchEvent::Failed { error: e.to_string() }).ok();
+        }
+    });
+    receiver
+}
+
+/// Everything a background fetch does, from loading the configuration to the last topic
+async fn fetch_in_background(client: &WikipediaClient, events: &mpsc::Sender<FetchEvent>) -> Result<()> {
+    let config = Config::load()?;
+    let blocklist = TitleBlocklist::from_config(&config.fetch)?;
+    let db = Database::new(DB_FILE)?
+        .with_storage(config.storage)
+        .with_sensitivity(config.sensitivity);
+    let mut checkpoint = match Checkpoint::load()? {
+        Some(checkpoint) => checkpoint,
+        None => Checkpoint::shuffled(),
+    };
+    run_fetch(client, &db, &blocklist, UNITS_PER_TOPIC, &mut checkpoint, events).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ContentKind;
+    use std::{
+        collections::HashMap,
+        io::{BufRead, BufReader, Write},
+        net::{TcpListener, TcpStream},
+        sync::Mutex,
+    };
+
+    /// A stand-in for the Wikipedia API on a local port, answering every search with two
+    /// titles and every article with a short story
+    /// Requests are logged as "search <query>" or "article <title>"; a search or article
+    /// named `hang_on` never gets an answer, as if the connection had stalled. Searches for
+    /// "lag once" get a maxlag error the first time, and for "lag always" every time.
+    /// Articles whose titles start with "Unavailable", "Garbled", "Shapeless" or "Stub"
+    /// get a 503 page, a body that isn't JSON, JSON without pages, or a one-line extract
+    struct MockWiki {
+        url: String,
+        rest_url: String,
+        requests: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl MockWiki {
+        fn start(hang_on: Option<&str>) -> Self {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            let address = listener.local_addr().unwrap();
+            let url = format!("http://{}/w/api.php", address);
+            let rest_url = format!("http://{}/api/rest_v1", address);
+            let requests = Arc::new(Mutex::new(Vec::new()));
+            let log = Arc::clone(&requests);
+            let hang_on = hang_on.map(str::to_string);
+            std::thread::spawn(move || {
+                for stream in listener.incoming().flatten() {
+                    let log = Arc::clone(&log);
+                    let hang_on = hang_on.clone();
+                    std::thread::spawn(move || serve(stream, &log, hang_on.as_deref()));
+                }
+            });
+            Self { url, rest_url, requests }
+        }
+
+        /// A client that sends everything here, without the usual rate cap
+        fn client(&self) -> WikipediaClient {
+            WikipediaClient::new(&FetchConfig {
+                api_url: self.url.clone(),
+                rest_url: self.rest_url.clone(),
+                max_requests_per_second: 1000.0,
+                ..FetchConfig::default()
+            })
+        }
+
+        fn requests(&self) -> Vec<String> {
+            self.requests.lock().unwrap().clone()
+        }
+    }
+
+    /// Answer one request, closing the connection afterwards
+    fn serve(stream: TcpStream, log: &Mutex<Vec<String>>, hang_on: Option<&str>) {
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line).unwrap();
+        let mut header = String::new();
+        while reader.read_line(&mut header).unwrap() > 2 {
+            header.clear();
+        }
+
+        let target = request_line.split_whitespace().nth(1).unwrap_or("/");
+        if let Some(page) = target.strip_prefix("/api/rest_v1/page/summary/") {
+            log.lock().unwrap().push(format!("summary {}", page));
+            let (status, body) = match page {
+                "Pax_Mongolica" => (
+                    "200 OK",
+                    serde_json::json!({
+                        "type": "standard",
+                        "title": "Pax Mongolica",
+                        "extract": " The Pax Mongolica was a period of relative stability in Eurasia. ",
+                        "content_urls": { "desktop": { "page": "https://en.wikipedia.org/wiki/Pax_Mongolica" } }
+                    }),
+                ),
+                "Mercury" => (
+                    "200 OK",
+                    serde_json::json!({ "type": "disambiguation", "title": "Mercury", "extract": "Mercury may refer to:" }),
+                ),
+                _ => ("404 Not Found", serde_json::json!({ "type": "not_found" })),
+            };
+            let body = body.to_string();
+            let mut stream = stream;
+            write!(
+                stream,
+                "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            )
+            .unwrap();
+            return;
+        }
+        let url = reqwest::Url::parse(&format!("http://mock{}", target)).unwrap();
+        let params: HashMap<String, String> = url.query_pairs().into_owned().collect();
+        let body = if let Some(query) = params.get("search") {
+            let asked = {
+                let mut log = log.lock().unwrap();
+                log.push(format!("search {}", query));
+                log.iter().filter(|entry| **entry == format!("search {}", query)).count()
+            };
+            if hang_on == Some(query.as_str()) {
+                std::thread::sleep(Duration::from_secs(60));
+                return;
+            }
+            if query == "lag always" || (query == "lag once" && asked == 1) {
+                let body = serde_json::json!({ "error": { "code": "maxlag", "lag": 7 } }).to_string();
+                let mut stream = stream;
+                write!(
+                    stream,
+                    "HTTP/1.1 200 OK\r\nRetry-After: 0\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                )
+                .unwrap();
+                return;
+            }
+            serde_json::json!([query, [format!("{} I", query), format!("{} II", query)], [], []])
+        } else {
+            let title = params.get("titles").cloned().unwrap_or_default();
+            log.lock().unwrap().push(format!("article {}", title));
+            if hang_on == Some(title.as_str()) {
+                std::thread::sleep(Duration::from_secs(60));
+                return;
+            }
+            let failure = match title.split_whitespace().next() {
+                Some("Unavailable") => Some(("503 Service Unavailable", "<html>Try again later</html>".to_string())),
+                Some("Garbled") => Some(("200 OK", "<html>Not an API</html>".to_string())),
+                Some("Shapeless") => Some(("200 OK", serde_json::json!({ "batchcomplete": true }).to_string())),
+                Some("Stub") => Some((
+                    "200 OK",
+                    serde_json::json!({ "query": { "pages": { "1": { "title": title, "extract": "A stub." } } } })
+                        .to_string(),
+                )),
+                _ => None,
+            };
+            if let Some((status, body)) = failure {
+                let mut stream = stream;
+                write!(
+                    stream,
+                    "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                )
+                .unwrap();
+                return;
+            }
+            let story = format!(
+                "{} was a place where the people of the valley gathered to trade grain, cloth and stories about their gods and kings. ",
+                title
+            )
+            .repeat(4);
+            serde_json::json!({ "query": { "pages": { "1": { "title": title, "extract": story.trim() } } } })
+        };
+
+        let body = body.to_string();
+        let mut stream = stream;
+        write!(
+            stream,
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            body.len(),
+            body
+        )
+        .unwrap();
+    }
+
+    /// A scratch checkpoint path for one test
+    fn checkpoint_path(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("tellme-checkpoint-{}-{}.json", name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    /// Units stored per topic
+    fn units_by_topic(checkpoint: &Checkpoint) -> Vec<(Topic, usize)> {
+        checkpoint.topics.iter().map(|queue| (queue.topic, queue.stats.units)).collect()
+    }
+
+    /// An intro of about 6000 characters in paragraphs of six sentences
+    fn long_intro() -> String {
+        let sentences: Vec<String> = (0..54)
+            .map(|i| {
+                format!(
+                    "In the year {} BC the legions pressed the siege for another season, and the defenders rebuilt the walls by night.",
+                    200 - i
+                )
+            })
+            .collect();
+        sentences.chunks(6).map(|paragraph| paragraph.join(" ")).collect::<Vec<_>>().join("\n")
+    }
+
+    #[test]
+    fn long_intros_yield_a_few_well_formed_units() {
+        let intro = long_intro();
+        assert!(intro.len() >= 6000, "fixture is only {} bytes", intro.len());
+
+        let units = process_article_content(
+            Topic::AncientRome,
+            "Siege of Carthage",
+            &intro,
+            "https://en.wikipedia.org/wiki/Siege_of_Carthage",
+            1.0,
+        );
+
+        assert!(!units.is_empty() && units.len() <= LONG_INTRO_MAX_UNITS, "{} units", units.len());
+        for unit in &units {
+            assert!(unit.is_suitable_length());
+            assert!(unit.content.starts_with("In the year"), "starts mid-sentence: {}", unit.content);
+            assert!(unit.content.ends_with('.'), "ends mid-sentence: {}", unit.content);
+            assert!(unit.part_index.is_some());
+        }
+    }
+
+    /// Census figures as they come out of a population table, and a story about the same city
+    const POPULATION_TABLE: &str = "Population of Alexandria by year. \
+        Year Population Change 1821 12,528 - 1846 164,359 +1,212% 1882 231,396 +40.8% \
+        1897 319,766 +38.2% 1907 332,246 +3.9% 1917 444,617 +33.8% 1927 573,063 +28.9% \
+        1937 686,807 +19.8% 1947 919,024 +33.8% 1960 1,516,234 +65.0%";
+    const CITY_STORY: &str = "Alexander the Great founded Alexandria in 331 BC on a strip \
+        of land between the sea and Lake Mareotis. Within a century it had become the largest city of \
+        the Mediterranean, home to the Library and the Pharos, one of the seven wonders of the ancient \
+        world, whose light guided ships into the harbour for more than 1,500 years.";
+
+    #[test]
+    fn extracts_that_are_mostly_figures_are_left_out() {
+        let url = "https://en.wikipedia.org/wiki/Alexandria";
+        let process = |text: &str, max_numeric_ratio: f64| {
+            process_article_content(Topic::AncientEgypt, "Alexandria", text, url, max_numeric_ratio)
+        };
+
+        assert!(process(POPULATION_TABLE, crate::config::DEFAULT_MAX_NUMERIC_RATIO).is_empty());
+        assert!(!process(POPULATION_TABLE, 1.0).is_empty());
+        assert!(!process(CITY_STORY, crate::config::DEFAULT_MAX_NUMERIC_RATIO).is_empty());
+    }
+
+    #[test]
+    fn fun_facts_are_stored_under_their_article_and_kept_out_of_regular_picks() {
+        let db = Database::new_in_memory().unwrap();
+        let mut article = ContentUnit::new(
+            Topic::AncientRome,
+            "Sack of Rome".to_string(),
+            "Rome fell to outsiders more than once. In 410 the Visigoths of Alaric besieged and sacked Rome, \
+             the first time in eight centuries that the city had fallen to a foreign enemy. The Great Fire of \
+             Rome was the largest disaster of Nero's reign and destroyed ten of the fourteen districts."
+                .to_string(),
+            "https://en.wikipedia.org/wiki/Sack_of_Rome".to_string(),
+        );
+        db.insert_content(&mut article).unwrap();
+        let (events, received) = mpsc::channel();
+
+        assert_eq!(store_fun_facts(&db, &article, FUN_FACTS_PER_ARTICLE, &events), 2);
+        assert_eq!(received.try_iter().filter(|e| matches!(e, FetchEvent::FunFactAdded { .. })).count(), 2);
+        // Storing the same article again finds nothing new
+        assert_eq!(store_fun_facts(&db, &article, FUN_FACTS_PER_ARTICLE, &events), 0);
+
+        let mut facts = HashSet::new();
+        for _ in 0..50 {
+            let fact = db.get_quick_bite(None).unwrap().unwrap();
+            assert_eq!((fact.kind, fact.parent_id), (ContentKind::FunFact, Some(article.id)));
+            assert_eq!((fact.topic, fact.source_url.as_str()), (article.topic, article.source_url.as_str()));
+            facts.insert(fact.content);
+        }
+        assert_eq!(facts.len(), 2);
+
+        for _ in 0..50 {
+            assert_eq!(db.get_weighted_random_content().unwrap().unwrap().id, article.id);
+            assert_eq!(db.get_content_for_topic(Topic::AncientRome).unwrap().unwrap().id, article.id);
+        }
+        assert_eq!(db.get_content_count().unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn an_interrupted_run_resumes_where_it_stopped() {
+        let topics = vec![Topic::AncientEgypt, Topic::AncientRome];
+        let blocklist = TitleBlocklist::from_patterns([]).unwrap();
+        let (events, _received) = mpsc::channel();
+        let stalled_query = Topic::AncientRome.search_queries()[0];
+
+        // Stalls on the second topic's first search, after the first topic's were saved
+        let path = checkpoint_path("interrupted");
+        let db = Database::new_in_memory().unwrap();
+        let wiki = MockWiki::start(Some(stalled_query));
+        let mut checkpoint = Checkpoint::new(topics.clone()).with_path(&path);
+        let client = wiki.client();
+        let run = run_fetch(&client, &db, &blocklist, 3, &mut checkpoint, &events);
+        assert!(tokio::time::timeout(Duration::from_secs(2), run).await.is_err());
+
+        let mut resumed = Checkpoint::load_from(&path).unwrap().unwrap();
+        assert!(resumed.topics[0].searched && !resumed.topics[1].searched);
+        let wiki = MockWiki::start(None);
+        run_fetch(&wiki.client(), &db, &blocklist, 3, &mut resumed, &events).await.unwrap();
+
+        let requests = wiki.requests();
+        assert_eq!(requests[0], format!("search {}", stalled_query));
+        for query in Topic::AncientEgypt.search_queries() {
+            assert!(!requests.contains(&format!("search {}", query)), "searched {} again", query);
+        }
+        assert!(!path.exists(), "the checkpoint goes once the run is complete");
+
+        // The same run without the interruption ends up in the same place
+        let fresh = Database::new_in_memory().unwrap();
+        let wiki = MockWiki::start(None);
+        let mut uninterrupted = Checkpoint::new(topics).with_path(checkpoint_path("uninterrupted"));
+        run_fetch(&wiki.client(), &fresh, &blocklist, 3, &mut uninterrupted, &events).await.unwrap();
+        assert_eq!(units_by_topic(&resumed), units_by_topic(&uninterrupted));
+        assert_eq!(units_by_topic(&resumed), vec![(Topic::AncientEgypt, 3), (Topic::AncientRome, 3)]);
+        assert_eq!(db.get_content_count_by_topic().unwrap(), fresh.get_content_count_by_topic().unwrap());
+    }
+
+    /// The topic whose searches found `title`
+    fn topic_of(title: &str) -> Topic {
+        let query = title.trim_end_matches(" II").trim_end_matches(" I");
+        *Topic::all().iter().find(|topic| topic.search_queries().contains(&query)).unwrap()
+    }
+
+    #[tokio::test]
+    async fn articles_are_fetched_a_topic_at_a_time_in_turn() {
+        let topics = vec![Topic::AncientEgypt, Topic::AncientRome, Topic::Viking];
+        let blocklist = TitleBlocklist::from_patterns([]).unwrap();
+        let (events, _received) = mpsc::channel();
+        let db = Database::new_in_memory().unwrap();
+        let wiki = MockWiki::start(None);
+        let mut checkpoint = Checkpoint::new(topics.clone()).with_path(checkpoint_path("round-robin"));
+        run_fetch(&wiki.client(), &db, &blocklist, 3, &mut checkpoint, &events).await.unwrap();
+
+        let articles: Vec<Topic> = wiki
+            .requests()
+            .iter()
+            .filter_map(|request| request.strip_prefix("article "))
+            .map(topic_of)
+            .collect();
+        assert_eq!(articles.len(), 9);
+        // However a round's requests interleave, no topic gets a second article before
+        // every other topic has had its first
+        let every_topic: HashSet<Topic> = topics.iter().copied().collect();
+        for (round, fetched) in articles.chunks(topics.len()).enumerate() {
+            assert_eq!(fetched.iter().copied().collect::<HashSet<_>>(), every_topic, "round {}", round);
+        }
+        assert_eq!(units_by_topic(&checkpoint), topics.iter().map(|&topic| (topic, 3)).collect::<Vec<_>>());
+    }
+
+    #[tokio::test]
+    async fn a_run_stopped_part_way_leaves_topics_equally_served() {
+        let topics = vec![Topic::AncientEgypt, Topic::AncientRome, Topic::Viking];
+        let blocklist = TitleBlocklist::from_patterns([]).unwrap();
+        let (events, _received) = mpsc::channel();
+        let path = checkpoint_path("part-way");
+        let db = Database::new_in_memory().unwrap();
+        // Rome's second article never arrives, stalling the second round
+        let stalled_title = format!("{} II", Topic::AncientRome.search_queries()[0]);
+        let wiki = MockWiki::start(Some(&stalled_title));
+        let mut checkpoint = Checkpoint::new(topics.clone()).with_path(&path);
+        let client = wiki.client();
+        let run = run_fetch(&client, &db, &blocklist, 3, &mut checkpoint, &events);
+        assert!(tokio::time::timeout(Duration::from_secs(2), run).await.is_err());
+
+        let stored = db.get_content_count_by_topic().unwrap();
+        assert_eq!(stored[&Topic::AncientRome], 1);
+        assert_eq!((stored[&Topic::AncientEgypt], stored[&Topic::Viking]), (2, 2));
+
+        // The checkpoint counts every stored article, and the stalled one is asked for again
+        let mut resumed = Checkpoint::load_from(&path).unwrap().unwrap();
+        assert_eq!(
+            units_by_topic(&resumed),
+            vec![(Topic::AncientEgypt, 2), (Topic::AncientRome, 1), (Topic::Viking, 2)]
+        );
+        let wiki = MockWiki::start(None);
+        run_fetch(&wiki.client(), &db, &blocklist, 3, &mut resumed, &events).await.unwrap();
+        assert!(wiki.requests().contains(&format!("article {}", stalled_title)));
+        assert!(wiki.requests().iter().all(|request| !request.starts_with("search ")));
+        let stored = db.get_content_count_by_topic().unwrap();
+        assert!(topics.iter().all(|topic| stored[topic] == 3), "{:?}", stored);
+    }
+
+    #[tokio::test]
+    async fn each_kind_of_failure_is_tallied_under_its_own_name() {
+        let blocklist = TitleBlocklist::from_patterns([]).unwrap();
+        let (events, _received) = mpsc::channel();
+        let db = Database::new_in_memory().unwrap();
+        let wiki = MockWiki::start(None);
+        let mut checkpoint = Checkpoint::new(vec![Topic::AncientEgypt]).with_path(checkpoint_path("failures"));
+        let queue = &mut checkpoint.topics[0];
+        queue.searched = true;
+        for title in ["Unavailable Thebes", "Garbled Thebes", "Shapeless Thebes", "Stub Thebes", "Memphis"] {
+            queue.queue.push_back(WorkItem {
+                query: "pharaohs".to_string(),
+                title: title.to_string(),
+            });
+        }
+        run_fetch(&wiki.client(), &db, &blocklist, 1, &mut checkpoint, &events).await.unwrap();
+
+        let stats = &checkpoint.topics[0].stats;
+        assert_eq!((stats.units, stats.requests), (1, 5));
+        let failures = stats.failures;
+        assert_eq!(failures.http_status, 1);
+        assert_eq!(failures.bad_response, 2);
+        assert_eq!(failures.rejected, 1);
+        assert_eq!((failures.network, failures.other), (0, 0));
+
+        // Nothing listens on a port that was just let go of
+        let address = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+        let offline = WikipediaClient::new(&FetchConfig {
+            api_url: format!("http://{}/w/api.php", address),
+            ..FetchConfig::default()
+        });
+        let mut checkpoint = Checkpoint::new(vec![Topic::AncientRome]).with_path(checkpoint_path("offline"));
+        run_fetch(&offline, &db, &blocklist, 1, &mut checkpoint, &events).await.unwrap();
+        let stats = &checkpoint.topics[0].stats;
+        assert_eq!((stats.units, stats.requests, stats.failures.network), (0, 1, 1));
+
+        assert_eq!(FailureKind::classify(&HttpStatus(404).into()), FailureKind::HttpStatus);
+        assert_eq!(FailureKind::classify(&UnexpectedResponse("pages").into()), FailureKind::BadResponse);
+        assert_eq!(FailureKind::classify(&anyhow::anyhow!("disk full")), FailureKind::Other);
+    }
+
+    #[test]
+    fn maxlag_errors_wait_as_asked_and_other_responses_not_at_all() {
+        let lagging = serde_json::json!({ "error": { "code": "maxlag", "lag": 2.5 } });
+        assert_eq!(maxlag_delay(&lagging, Some("10")), Some(Duration::from_secs(10)));
+        assert_eq!(maxlag_delay(&lagging, Some(" 0 ")), Some(Duration::ZERO));
+        // An unreadable header falls back to the reported lag, rounded up
+        assert_eq!(maxlag_delay(&lagging, Some("soon")), Some(Duration::from_secs(3)));
+        assert_eq!(maxlag_delay(&lagging, None), Some(Duration::from_secs(3)));
+
+        let bare = serde_json::json!({ "error": { "code": "maxlag" } });
+        assert_eq!(maxlag_delay(&bare, None), Some(DEFAULT_RETRY_AFTER));
+
+        let other = serde_json::json!({ "error": { "code": "badtitle", "lag": 2 } });
+        assert_eq!(maxlag_delay(&other, Some("10")), None);
+        assert_eq!(maxlag_delay(&serde_json::json!(["q", ["q I"], [], []]), Some("10")), None);
+    }
+
+    #[tokio::test]
+    async fn requests_are_retried_after_a_maxlag_error() {
+        let wiki = MockWiki::start(None);
+        let client = wiki.client();
+
+        let titles = client.search_articles("lag once", 2).await.unwrap();
+        assert_eq!(titles, vec!["lag once I", "lag once II"]);
+        assert_eq!(wiki.requests(), vec!["search lag once", "search lag once"]);
+
+        let error = client.search_articles("lag always", 2).await.unwrap_err();
+        assert!(error.to_string().contains("too busy"), "{}", error);
+        assert_eq!(wiki.requests().len(), 2 + 1 + MAXLAG_RETRIES as usize);
+    }
+
+    #[tokio::test]
+    async fn terms_are_looked_up_on_the_configured_rest_api() {
+        let wiki = MockWiki::start(None);
+        let client = wiki.client();
+
+        let summary = client.get_term_summary("Pax Mongolica").await.unwrap().unwrap();
+        assert_eq!(summary.term, "Pax Mongolica");
+        assert_eq!(summary.extract, "The Pax Mongolica was a period of relative stability in Eurasia.");
+        assert_eq!(summary.source_url, "https://en.wikipedia.org/wiki/Pax_Mongolica");
+        // Disambiguation pages and missing articles explain nothing
+        assert!(client.get_term_summary("Mercury").await.unwrap().is_none());
+        assert!(client.get_term_summary("Treaty of Nowhere").await.unwrap().is_none());
+
+        assert_eq!(
+            wiki.requests(),
+            ["summary Pax_Mongolica", "summary Mercury", "summary Treaty_of_Nowhere"]
+        );
+    }
+
+    #[tokio::test]
+    async fn clones_of_a_client_share_its_rate_cap() {
+        let wiki = MockWiki::start(None);
+        let client = WikipediaClient::new(&FetchConfig {
+            api_url: wiki.url.clone(),
+            rest_url: wiki.rest_url.clone(),
+            max_requests_per_second: 4.0,
+            ..FetchConfig::default()
+        });
+        let (fetcher, lookups) = (client.clone(), client.clone());
+
+        let started = std::time::Instant::now();
+        fetcher.search_articles("Rome", 2).await.unwrap();
+        lookups.get_term_summary("Pax Mongolica").await.unwrap();
+        fetcher.search_articles("Rome", 2).await.unwrap();
+        // The first request goes at once, each after it waits its turn
+        assert!(started.elapsed() >= client.request_interval() * 2, "{:?}", started.elapsed());
+        assert_eq!(wiki.requests().len(), 3);
+    }
+}
@@ -0,0 +1,367 @@
+// import.rs - Import content shared as JSON Lines
+// Each line is one content unit. Topic labels from other tellme builds
+// (or other tools) don't always match our enum, so every row goes through
+// a mapping step before it is stored.
+
+use crate::{content::ValidationIssue, database::DuplicateContent, ContentUnit, Database, Result, Topic};
+use serde::Deserialize;
+use std::io::BufRead;
+
+/// Labels used by older builds and other tools, mapped onto our topics
+/// Matching is case-insensitive and ignores spaces, dashes and underscores
+const TOPIC_ALIASES: &[(&str, Topic)] = &[
+    ("stoneage", Topic::Prehistoric),
+    ("prehistory", Topic::Prehistoric),
+    ("egypt", Topic::AncientEgypt),
+    ("greece", Topic::AncientGreece),
+    ("ancientgreek", Topic::AncientGreece),
+    ("rome", Topic::AncientRome),
+    ("romanempire", Topic::AncientRome),
+    ("china", Topic::AncientChina),
+    ("byzantium", Topic::Byzantine),
+    ("byzantineempire", Topic::Byzantine),
+    ("middleages", Topic::Medieval),
+    ("vikings", Topic::Viking),
+    ("norse", Topic::Viking),
+    ("islamicgoldenage", Topic::Islamic),
+    ("mongols", Topic::Mongol),
+    ("mongolempire", Topic::Mongol),
+    ("exploration", Topic::AgeOfExploration),
+    ("ageofdiscovery", Topic::AgeOfExploration),
+    ("colonialism", Topic::Colonial),
+    ("ageofenlightenment", Topic::Enlightenment),
+    ("industrialrevolution", Topic::Industrial),
+    ("19thcentury", Topic::NineteenthCentury),
+    ("victorian", Topic::NineteenthCentury),
+    ("ww1", Topic::WorldWarOne),
+    ("wwi", Topic::WorldWarOne),
+    ("worldwar1", Topic::WorldWarOne),
+    ("firstworldwar", Topic::WorldWarOne),
+    ("greatwar", Topic::WorldWarOne),
+    ("interwar", Topic::InterwarPeriod),
+    ("ww2", Topic::WorldWarTwo),
+    ("wwii", Topic::WorldWarTwo),
+    ("worldwar2", Topic::WorldWarTwo),
+    ("secondworldwar", Topic::WorldWarTwo),
+    ("coldwarera", Topic::ColdWar),
+    ("modern", Topic::Contemporary),
+    ("moderntimes", Topic::Contemporary),
+];
+
+/// Move content stored under topic names from older builds onto current topics
+/// Stored labels are resolved like import labels, by name or through the alias table, but
+/// never by keyword. Returns the number of units migrated and the labels left as they were
+pub fn migrate_legacy_topics(db: &Database) -> Result<(usize, Vec<(String, i64)>)> {
+    let mut mapping = Vec::new();
+    let mut unmapped = Vec::new();
+    for (label, count) in db.get_unknown_topic_labels()? {
+        match TopicResolution::resolve(&label, "", "", true) {
+            TopicResolution::Exact(topic) | TopicResolution::Alias(topic) => {
+                mapping.push((label, topic))
+            }
+            _ => unmapped.push((label, count)),
+        }
+    }
+
+    let mapping: Vec<(&str, Topic)> = mapping
+        .iter()
+        .map(|(label, topic)| (label.as_str(), *topic))
+        .collect();
+    Ok((db.remap_topics(&mapping)?, unmapped))
+}
+
+/// One line of an import file
+#[derive(Debug, Deserialize)]
+struct ImportRecord {
+    topic: String,
+    title: String,
+    content: String,
+    #[serde(default)]
+    source_url: String,
+}
+
+/// How an imported topic label was resolved
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TopicResolution {
+    /// The label named one of our topics
+    Exact(Topic),
+    /// The label was found in the alias table
+    Alias(Topic),
+    /// The label was unknown, so the content was classified by keywords
+    Classified(Topic),
+    /// No topic could be determined
+    Unmapped,
+}
+
+impl TopicResolution {
+    /// Resolve a topic label, falling back to the keyword classifier unless `strict`
+    pub fn resolve(label: &str, title: &str, content: &str, strict: bool) -> Self {
+        if let Some(topic) = Topic::from_label(label) {
+            return Self::Exact(topic);
+        }
+
+        let key: String = label
+            .chars()
+            .filter(|c| c.is_alphanumeric())
+            .collect::<String>()
+            .to_lowercase();
+        if let Some((_, topic)) = TOPIC_ALIASES.iter().find(|(alias, _)| *alias == key) {
+            return Self::Alias(*topic);
+        }
+
+        if strict {
+            return Self::Unmapped;
+        }
+
+        match Topic::classify(&format!("{}\n{}", title, content)) {
+            Some(topic) => Self::Classified(topic),
+            None => Self::Unmapped,
+        }
+    }
+}
+
+/// Options controlling an import run
+#[derive(Debug, Clone, Default)]
+pub struct ImportOptions {
+    /// Reject rows whose topic isn't an exact or alias match instead of classifying them,
+    /// and rows that fail the suitability rules instead of importing them with a warning
+    pub strict: bool,
+    /// Skip rows whose title is already in the library
+    pub skip_duplicates: bool,
+    /// Link imported content to this pack, so removing the pack removes the content
+    pub pack_id: Option<i64>,
+}
+
+/// A row that failed the suitability rules, and what happened to it
+#[derive(Debug, Clone)]
+pub struct ValidationFailure {
+    pub line: usize,
+    pub title: String,
+    pub issues: Vec<ValidationIssue>,
+    /// Whether the row was imported anyway (non-strict mode)
+    pub imported: bool,
+}
+
+/// Counts describing what an import did
+#[derive(Debug, Clone, Default)]
+pub struct ImportReport {
+    pub imported: usize,
+    pub exact: usize,
+    pub aliased: usize,
+    pub classified: usize,
+    pub unmapped: usize,
+    /// Lines that weren't valid JSON records
+    pub invalid: usize,
+    /// Rows skipped because their title (with `skip_duplicates`) or their text was already
+    /// in the library
+    pub duplicates: usize,
+    /// Rows that failed the suitability rules, in file order
+    pub validation_failures: Vec<ValidationFailure>,
+}
+
+impl ImportReport {
+    /// Number of rows rejected because they failed validation
+    pub fn rejected_by_validation(&self) -> usize {
+        self.validation_failures.iter().filter(|f| !f.imported).count()
+    }
+
+    /// Number of rows imported despite failing validation
+    pub fn imported_with_warnings(&self) -> usize {
+        self.validation_failures.iter().filter(|f| f.imported).count()
+    }
+}
+
+/// Import JSON Lines content from `reader` into the database
+/// Remapped rows keep their original label in the `imported_topic` column, and every
+/// row is checked against the fetcher's suitability rules
+pub fn import_content<R: BufRead>(
+    db: &Database,
+    reader: R,
+    options: &ImportOptions,
+) -> Result<ImportReport> {
+    let mut report = ImportReport::default();
+
+    for (line_number, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let record: ImportRecord = match serde_json::from_str(&line) {
+            Ok(record) => record,
+            Err(e) => {
+                eprintln!("  ✗ Line {}: invalid record: {}", line_number + 1, e);
+                report.invalid += 1;
+                continue;
+            }
+        };
+
+        let resolution =
+            TopicResolution::resolve(&record.topic, &record.title, &record.content, options.strict);
+        let (topic, original_label) = match resolution {
+            TopicResolution::Exact(topic) => (topic, None),
+            TopicResolution::Alias(topic) | TopicResolution::Classified(topic) => {
+                (topic, Some(record.topic.as_str()))
+            }
+            TopicResolution::Unmapped => {
+                eprintln!(
+                    "  ✗ Line {}: unknown topic '{}' for '{}'",
+                    line_number + 1,
+                    record.topic,
+                    record.title
+                );
+                report.unmapped += 1;
+                continue;
+            }
+        };
+
+        let mut unit = ContentUnit::new(
+            topic,
+            record.title.clone(),
+            record.content.clone(),
+            record.source_url.clone(),
+        );
+        unit.clean_content();
+
+        if options.skip_duplicates && db.content_title_exists(&unit.title)? {
+            report.duplicates += 1;
+            continue;
+        }
+
+        let issues = unit.validate();
+        if !issues.is_empty() {
+            report.validation_failures.push(ValidationFailure {
+                line: line_number + 1,
+                title: record.title.clone(),
+                issues,
+                imported: !options.strict,
+            });
+            if options.strict {
+                continue;
+            }
+        }
+
+        // The same text is stored once, whether or not duplicate titles are skipped
+        match db.insert_imported_content(&mut unit, original_label, options.pack_id) {
+            Err(e) if e.is::<DuplicateContent>() => {
+                report.duplicates += 1;
+                continue;
+            }
+            result => result?,
+        }
+
+        report.imported += 1;
+        match resolution {
+            TopicResolution::Exact(_) => report.exact += 1,
+            TopicResolution::Alias(_) => report.aliased += 1,
+            TopicResolution::Classified(_) => report.classified += 1,
+            TopicResolution::Unmapped => {}
+        }
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A JSONL line with enough words to pass the suitability rules
+    fn record(topic: &str, title: &str, subject: &str) -> String {
+        let content = format!("{} was an important part of history. ", subject).repeat(5);
+        serde_json::json!({ "topic": topic, "title": title, "content": content.trim() }).to_string()
+    }
+
+    fn fixture() -> String {
+        [
+            record("AncientEgypt", "Giza", "The Great Pyramid of Giza"),
+            record("WW2", "Normandy", "The landing in Normandy"),
+            record("Pharaohs", "Tutankhamun", "The tomb of the pharaoh Tutankhamun with its mummy"),
+            "not json".to_string(),
+        ]
+        .join("\n")
+    }
+
+    fn id_of(db: &Database, title: &str) -> i64 {
+        db.search_content(title, 1).unwrap()[0].id
+    }
+
+    #[test]
+    fn labels_resolve_by_name_alias_or_keywords() {
+        assert_eq!(TopicResolution::resolve("AncientEgypt", "", "", false), TopicResolution::Exact(Topic::AncientEgypt));
+        assert_eq!(TopicResolution::resolve("World-War 2", "", "", false), TopicResolution::Alias(Topic::WorldWarTwo));
+        assert_eq!(
+            TopicResolution::resolve("Pharaohs", "Pyramid", "A pharaoh's pyramid", false),
+            TopicResolution::Classified(Topic::AncientEgypt)
+        );
+        assert_eq!(TopicResolution::resolve("Pharaohs", "Pyramid", "A pharaoh's pyramid", true), TopicResolution::Unmapped);
+        assert_eq!(TopicResolution::resolve("Miscellany", "", "", false), TopicResolution::Unmapped);
+    }
+
+    #[test]
+    fn import_counts_each_resolution_and_keeps_remapped_labels() {
+        let db = Database::new_in_memory().unwrap();
+        let report = import_content(&db, fixture().as_bytes(), &ImportOptions::default()).unwrap();
+
+        assert_eq!((report.imported, report.exact, report.aliased, report.classified), (3, 1, 1, 1));
+        assert_eq!((report.invalid, report.unmapped, report.duplicates), (1, 0, 0));
+
+        let counts = db.get_content_count_by_topic().unwrap();
+        assert_eq!(counts.get(&Topic::AncientEgypt), Some(&2));
+        assert_eq!(counts.get(&Topic::WorldWarTwo), Some(&1));
+        assert_eq!(db.get_imported_topic(id_of(&db, "Giza")).unwrap(), None);
+        assert_eq!(db.get_imported_topic(id_of(&db, "Normandy")).unwrap().as_deref(), Some("WW2"));
+        assert_eq!(db.get_imported_topic(id_of(&db, "Tutankhamun")).unwrap().as_deref(), Some("Pharaohs"));
+    }
+
+    #[test]
+    fn strict_import_rejects_labels_it_would_have_to_guess() {
+        let db = Database::new_in_memory().unwrap();
+        let options = ImportOptions { strict: true, ..Default::default() };
+        let report = import_content(&db, fixture().as_bytes(), &options).unwrap();
+
+        assert_eq!((report.imported, report.exact, report.aliased, report.classified), (2, 1, 1, 0));
+        assert_eq!(report.unmapped, 1);
+        assert_eq!(db.get_content_count().unwrap(), 2);
+    }
+
+    #[test]
+    fn importing_the_same_file_twice_stores_each_story_once() {
+        let db = Database::new_in_memory().unwrap();
+        import_content(&db, fixture().as_bytes(), &ImportOptions::default()).unwrap();
+        let again = import_content(&db, fixture().as_bytes(), &ImportOptions::default()).unwrap();
+        assert_eq!((again.imported, again.duplicates), (0, 3));
+    }
+
+    #[test]
+    fn content_under_old_topic_names_moves_to_the_current_topics() {
+        let dir = std::env::temp_dir().join(format!("tellme-legacy-topics-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("tellme.db");
+        let _ = std::fs::remove_file(&path);
+        let db = Database::new(path.to_str().unwrap()).unwrap();
+        import_content(&db, fixture().as_bytes(), &ImportOptions::default()).unwrap();
+        let (giza, normandy) = (id_of(&db, "Giza"), id_of(&db, "Normandy"));
+
+        // Rows and counters written by a build whose topics had other names
+        let old = rusqlite::Connection::open(&path).unwrap();
+        old.execute("UPDATE content SET topic = '\"Egypt\"' WHERE id = ?1", [giza]).unwrap();
+        old.execute("UPDATE content SET topic = '\"WW2\"' WHERE id = ?1", [normandy]).unwrap();
+        old.execute("UPDATE content SET topic = '\"Atlantis\"' WHERE title = 'Tutankhamun'", []).unwrap();
+        old.execute("INSERT INTO topic_counters (profile_id, topic, fully_read) VALUES (1, '\"WW2\"', 3)", []).unwrap();
+        drop(old);
+        assert_eq!(db.get_unknown_topic_labels().unwrap().len(), 3);
+
+        let (migrated, unmapped) = migrate_legacy_topics(&db).unwrap();
+        assert_eq!(migrated, 2);
+        assert_eq!(unmapped, vec![("Atlantis".to_string(), 1)]);
+        assert_eq!(db.get_unknown_topic_labels().unwrap(), unmapped);
+
+        let counts = db.get_content_count_by_topic().unwrap();
+        assert_eq!((counts.get(&Topic::AncientEgypt), counts.get(&Topic::WorldWarTwo)), (Some(&1), Some(&1)));
+        assert_eq!(db.get_interaction_counts_by_type().unwrap(), (3, 0));
+        assert_eq!(db.get_content_for_topic(Topic::WorldWarTwo).unwrap().map(|content| content.id), Some(normandy));
+        drop(db);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -6,10 +6,19 @@ pub mod database;
 pub mod content;
 pub mod ui;
 pub mod auto_update;
+pub mod config;
+pub mod export;
+pub mod import;
+pub mod pending;
+pub mod packs;
+pub mod fetch;
+pub mod moderation;
+pub mod selection;
 
 // Re-export commonly used types for convenience
-pub use content::{ContentUnit, Topic, UserInteraction};
+pub use content::{ContentKind, ContentUnit, TermSummary, Topic, TopicMeta, UserInteraction};
 pub use database::Database;
+pub use config::Config;
 
 // Error type alias for easier error handling throughout the app
 pub type Result<T> = anyhow::Result<T>;
@@ -17,6 +26,24 @@ pub type Result<T> = anyhow::Result<T>;
 // Constants used throughout the application
 pub const DATA_DIR: &str = "tellme_data";
 pub const DB_FILE: &str = "tellme_data/tellme.db";
+pub const CONFIG_FILE: &str = "tellme_data/tellme.toml";
+pub const PENDING_FILE: &str = "tellme_data/pending_interactions.jsonl";
+pub const FETCH_CHECKPOINT_FILE: &str = "tellme_data/fetch_checkpoint.json";
+
+/// Environment variable naming the profile to use when `--profile` isn't given
+pub const PROFILE_ENV: &str = "TELLME_PROFILE";
+
+/// Environment variable that, when set, makes the reader print startup phase timings on exit
+pub const STARTUP_TIMINGS_ENV: &str = "TELLME_STARTUP_TIMINGS";
+
+/// SHA-256 of `bytes` as lowercase hex
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    use sha2::{Digest, Sha256};
+    Sha256::digest(bytes)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
 
 /// Create the data directory if it doesn't exist
 /// This demonstrates file system operations and error handling
@@ -26,4 +53,45 @@ pub fn ensure_data_dir() -> anyhow::Result<()> {
         std::fs::create_dir_all(data_dir)?;
     }
     Ok(())
-} 
\ No newline at end of file
+} 
+
+/// Work out which profile was asked for, from `--profile <name>` (or `--profile=<name>`)
+/// or the `TELLME_PROFILE` environment variable
+/// The flag is removed from `args` so the remaining arguments can be parsed as usual
+pub fn requested_profile(args: &mut Vec<String>) -> anyhow::Result<Option<String>> {
+    let requested = take_flag_value(args, "--profile", "a profile name")?;
+    Ok(requested.or_else(|| std::env::var(PROFILE_ENV).ok().filter(|name| !name.is_empty())))
+}
+
+/// Frame rate asked for with `--fps <n>` (or `--fps=<n>`), overriding `ui.frame_rate`
+/// The flag is removed from `args` like `--profile`
+pub fn requested_frame_rate(args: &mut Vec<String>) -> anyhow::Result<Option<u32>> {
+    take_flag_value(args, "--fps", "a number of frames per second")?
+        .map(|value| match value.parse::<u32>() {
+            Ok(fps) if fps > 0 => Ok(fps),
+            _ => Err(anyhow::anyhow!("--fps needs a positive number, got '{}'", value)),
+        })
+        .transpose()
+}
+
+/// Remove `flag <value>` or `flag=<value>` from `args`, returning the last value given
+fn take_flag_value(args: &mut Vec<String>, flag: &str, what: &str) -> anyhow::Result<Option<String>> {
+    let prefix = format!("{}=", flag);
+    let mut value = None;
+    let mut i = 0;
+    while i < args.len() {
+        if args[i] == flag {
+            if i + 1 >= args.len() {
+                anyhow::bail!("{} needs {}", flag, what);
+            }
+            value = Some(args.remove(i + 1));
+            args.remove(i);
+        } else if let Some(rest) = args[i].strip_prefix(&prefix) {
+            value = Some(rest.to_string());
+            args.remove(i);
+        } else {
+            i += 1;
+        }
+    }
+    Ok(value)
+}
@@ -3,159 +3,1435 @@
 // and integration of all application components
 
 use anyhow::Result;
-use std::time::Duration;
 use tellme::{
-    database::Database,
-    ui::{handle_events, init_terminal, render_ui, restore_terminal, App},
-    UserInteraction, DB_FILE,
-    auto_update::UpdateChecker,
+    database::{BackfillColumn, Database, DuplicateContent, RetentionReport, SOFT_DELETE_RETENTION_DAYS},
+    fetch::WikipediaClient,
+    moderation::{apply_blocklist_fetch, fetch_blocklist, sync_blocklist, BlockSource, BlocklistFetch},
+    import::{import_content, migrate_legacy_topics, ImportOptions, ImportReport},
+    packs::{fetch_pack, install_pack},
+    pending::PendingQueue,
+    ui::{
+        handle_events, init_terminal, render_ui, restore_terminal, topic_filter_label, AboutInfo, App,
+        FetchProgress,
+    },
+    config::DEFAULT_EXPLORE_EXPLOIT_BALANCE,
+    Config, ContentUnit, TermSummary, Topic, TopicMeta, UserInteraction, DB_FILE,
+    auto_update::{UpdateChecker, UpdateInfo},
 };
 
 /// Main application entry point
 /// This demonstrates Rust's main function and async/await patterns
 #[tokio::main]
 async fn main() -> Result<()> {
+    // Subcommands run without starting the TUI
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let requested_profile = tellme::requested_profile(&mut args)?;
+    let requested_frame_rate = tellme::requested_frame_rate(&mut args)?;
+    if let Some(command) = args.first() {
+        return run_command(command, &args[1..], requested_profile).await;
+    }
+
     println!("🏛️  tellme - Fascinating History from All Ages");
     println!("==========================================");
-    
-    // Check for updates from GitHub (quick timeout)
-    println!("Checking for updates...");
-    let update_checker = UpdateChecker::new();
-    if let Some(update_info) = update_checker.quick_update_check().await {
-        println!("\n{}\n", update_info.display_notification());
-        
-        // Wait for user to acknowledge update notification
-        println!("Press Enter to continue...");
-        let mut input = String::new();
-        std::io::stdin().read_line(&mut input).ok();
-    }
-    
-    // Initialize data directory and database
+    let startup_timings = record_startup_timings();
+    let opening = startup_phase("Opening the library");
+
+    // Check for updates from GitHub in the background; the reader mentions it if one turns up
+    let update_check = tokio::spawn(async { UpdateChecker::new().quick_update_check().await });
+
+    // Initialize data directory, configuration and database
     tellme::ensure_data_dir()?;
-    
-    // Check if we have any content in the database
-    let db = Database::new(DB_FILE)?;
-    let content_count = db.get_content_count()?;
-    
-    if content_count == 0 {
-        eprintln!("No content found in database!");
-        eprintln!("Please run the data fetcher first:");
-        eprintln!("cargo run --bin fetch_data");
-        eprintln!();
-        eprintln!("This will download and process Wikipedia articles for all topics.");
-        return Ok(());
+    let config = Config::load()?;
+    let db = Database::new_with_privacy(DB_FILE, config.privacy.mode)?
+        .with_recommendation(config.recommendation.clone())
+        .with_sensitivity(config.sensitivity.clone());
+    let db = match requested_profile {
+        Some(name) => db.with_profile(&name)?,
+        None => pick_profile(db)?,
+    };
+    // Safe mode can only leave out stories that have a score
+    if config.sensitivity.safe_mode {
+        db.backfill(&[BackfillColumn::Sensitivity])?;
     }
-
-    println!("Found {} content units in database", content_count);
-    println!("Starting tellme...");
+    // Chronological order can only find stories whose year was estimated
+    db.backfill(&[BackfillColumn::EstimatedYear])?;
 
     // Initialize terminal
     let mut terminal = init_terminal()
         .map_err(|e| anyhow::anyhow!("Failed to initialize terminal: {}", e))?;
 
     // Create application state
-    let mut app = App::new();
-    
-    // Load initial content
-    if let Some(content) = db.get_weighted_random_content()? {
-        app.set_content(content);
-    } else {
-        app.set_status("No content available. Please run fetch_data first.".to_string());
+    let mut ui_config = config.ui.clone();
+    if let Some(fps) = requested_frame_rate {
+        ui_config.frame_rate = fps;
     }
+    let focus_on_start = ui_config.focus_on_start;
+    let mut app = App::new().with_ui_config(ui_config);
+    app.strategy = db.strategy_name();
+    app.topic_filter_share = db.topic_filter_share();
+    if focus_on_start {
+        app.toggle_focus();
+    }
+    drop(opening);
 
-    // Main event loop
-    let result = run_app(&mut terminal, &mut app, &db).await;
+    // Show the loading screen straight away, then do the slower work off the UI thread
+    let result = match load_startup(
+        &mut terminal,
+        &mut app,
+        |app| handle_events(app, app.ui.frame_interval()),
+        move |progress| prepare_startup(db, progress),
+    )
+    .await
+    {
+        Ok(None) => Ok(None),
+        Ok(Some(startup)) => {
+            // An empty library opens on the fetch screen
+            match startup.first_content {
+                Some(content) => app.set_content(content),
+                None if startup.content_count == 0 => app.open_fetch(),
+                None => {}
+            }
+            if !startup.notes.is_empty() {
+                app.flash_status(startup.notes.join(" • "));
+            }
+            run_app(&mut terminal, &mut app, &startup.db, update_check).await
+        }
+        Err(e) => Err(e),
+    };
 
     // Restore terminal
     restore_terminal(&mut terminal)
         .map_err(|e| anyhow::anyhow!("Failed to restore terminal: {}", e))?;
 
+    if let Some(timings) = startup_timings {
+        eprint!("{}", timings);
+    }
+
+    if let Some(update) = result? {
+        println!("{}", update.summary());
+    }
     // Print final message
     println!("Thanks for using tellme! Keep learning!");
 
-    result
+    Ok(())
+}
+
+/// Everything the reader needs before showing the first story
+struct Startup {
+    db: Database,
+    content_count: i64,
+    first_content: Option<ContentUnit>,
+    /// Short messages about housekeeping done on the way, shown once the reader is up
+    notes: Vec<String>,
+}
+
+/// Run the startup work on a blocking thread while the loading screen keeps rendering
+/// `handle` waits for and handles input between frames. The phase in progress is shown
+/// in the status bar, and Q still quits (returning None)
+async fn load_startup<B: ratatui::backend::Backend, T: Send + 'static>(
+    terminal: &mut ratatui::Terminal<B>,
+    app: &mut App,
+    mut handle: impl FnMut(&mut App) -> std::io::Result<()>,
+    work: impl FnOnce(std::sync::mpsc::Sender<&'static str>) -> Result<T> + Send + 'static,
+) -> Result<Option<T>> {
+    let (progress, phases) = std::sync::mpsc::channel();
+    let task = tokio::task::spawn_blocking(move || work(progress));
+
+    terminal.draw(|frame| render_ui(frame, app))?;
+    while !task.is_finished() {
+        if let Some(phase) = phases.try_iter().last() {
+            app.set_status(format!("{}...", phase));
+        }
+        handle(app)?;
+        if app.should_quit {
+            // The blocking work can't be interrupted, but its result is no longer needed
+            return Ok(None);
+        }
+        terminal.draw(|frame| render_ui(frame, app))?;
+    }
+
+    task.await?.map(Some)
+}
+
+/// Startup phase log, held back while the terminal belongs to the reader
+#[derive(Clone, Default)]
+struct TimingsLog(std::sync::Arc<std::sync::Mutex<Vec<u8>>>);
+
+impl std::io::Write for TimingsLog {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner()).extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for TimingsLog {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", String::from_utf8_lossy(&self.0.lock().unwrap_or_else(|e| e.into_inner())))
+    }
+}
+
+/// When `STARTUP_TIMINGS_ENV` is set, log each startup phase's span as it closes, with
+/// how long it took, to a log that is printed once the terminal is restored
+fn record_startup_timings() -> Option<TimingsLog> {
+    std::env::var_os(tellme::STARTUP_TIMINGS_ENV)?;
+    let log = TimingsLog::default();
+    tracing::subscriber::set_global_default(timings_subscriber(&log)).ok()?;
+    Some(log)
+}
+
+/// Subscriber writing a line to `log` for every span that closes, with its timings
+fn timings_subscriber(log: &TimingsLog) -> impl tracing::Subscriber + Send + Sync {
+    let writer = log.clone();
+    tracing_subscriber::fmt()
+        .with_span_events(tracing_subscriber::fmt::format::FmtSpan::CLOSE)
+        .with_target(false)
+        .with_ansi(false)
+        .with_writer(move || writer.clone())
+        .finish()
+}
+
+/// Enter the tracing span for a startup phase; it closes when dropped
+fn startup_phase(phase: &'static str) -> tracing::span::EnteredSpan {
+    tracing::info_span!("startup", phase).entered()
+}
+
+/// Reports startup phases as they begin, each in its own span
+struct StartupPhases {
+    progress: std::sync::mpsc::Sender<&'static str>,
+    current: Option<tracing::span::EnteredSpan>,
+}
+
+impl StartupPhases {
+    /// Finish the current phase, if any, and start `phase`
+    fn start(&mut self, phase: &'static str) {
+        self.stop();
+        self.progress.send(phase).ok();
+        self.current = Some(startup_phase(phase));
+    }
+
+    /// Finish the current phase
+    fn stop(&mut self) {
+        self.current.take();
+    }
+}
+
+/// Blocks that don't compile, for the status bar, e.g. "ignoring invalid block 'regex:(['"
+fn invalid_blocks_note(invalid: &[String]) -> String {
+    let quoted: Vec<String> = invalid.iter().map(|entry| format!("'{}'", entry)).collect();
+    let plural = if invalid.len() == 1 { "" } else { "s" };
+    format!("ignoring invalid block{} {}", plural, quoted.join(", "))
+}
+
+/// Warning about interactions stamped after the current time; until the clock is right
+/// again they don't count as recent (see `CLOCK_SKEW_TOLERANCE_MINUTES`)
+fn clock_skew_warning(future: i64) -> String {
+    format!(
+        "{} interaction{} dated in the future — is the system clock right?",
+        future,
+        if future == 1 { " is" } else { "s are" }
+    )
+}
+
+/// Housekeeping and the first selection, reporting each phase on `progress` as it starts
+fn prepare_startup(db: Database, progress: std::sync::mpsc::Sender<&'static str>) -> Result<Startup> {
+    let mut notes = Vec::new();
+    let mut timer = StartupPhases { progress, current: None };
+
+    // Interactions stamped in the future mean the clock went back since they were recorded
+    timer.start("Checking the clock");
+    match db.count_future_interactions() {
+        Ok(0) => {}
+        Ok(future) => notes.push(clock_skew_warning(future)),
+        Err(e) => notes.push(format!("Failed to check interaction times: {}", e)),
+    }
+
+    // Save interactions that couldn't be written last time
+    timer.start("Saving queued interactions");
+    match PendingQueue::default().drain_into(&db) {
+        Ok(0) => {}
+        Ok(recorded) => notes.push(format!("Saved {} interactions queued earlier", recorded)),
+        Err(e) => notes.push(format!("Failed to save queued interactions: {}", e)),
+    }
+
+    // Permanently remove content that has been soft-deleted for a while
+    timer.start("Purging deleted content");
+    match db.purge_deleted_content(chrono::Duration::days(SOFT_DELETE_RETENTION_DAYS)) {
+        Ok(0) => {}
+        Ok(purged) => notes.push(format!("Purged {} deleted content units", purged)),
+        Err(e) => notes.push(format!("Failed to purge deleted content: {}", e)),
+    }
+
+    // Blocks that don't compile are skipped quietly while reading, so mention them here
+    match db.invalid_blocks() {
+        Ok(invalid) if invalid.is_empty() => {}
+        Ok(invalid) => notes.push(format!("Blocklist: {}", invalid_blocks_note(&invalid))),
+        Err(e) => notes.push(format!("Failed to load the blocklist: {}", e)),
+    }
+
+    timer.start("Counting content");
+    let content_count = db.get_content_count()?;
+
+    // Load initial content
+    let first_content = if content_count > 0 {
+        timer.start("Choosing a story");
+        let content = db.get_weighted_random_content()?;
+        if let Some(ref content) = content {
+            if let Err(e) = db.mark_shown(content.id) {
+                notes.push(format!("Failed to record content view: {}", e));
+            }
+        }
+        content
+    } else {
+        None
+    };
+    timer.stop();
+
+    Ok(Startup {
+        db,
+        content_count,
+        first_content,
+        notes,
+    })
+}
+
+/// Ask which profile to read as when more than one exists
+/// An empty answer (or closed stdin) keeps the default profile
+fn pick_profile(db: Database) -> Result<Database> {
+    let profiles = db.list_profiles()?;
+    if profiles.len() < 2 {
+        return Ok(db);
+    }
+
+    println!("\nWho's reading?");
+    for (i, profile) in profiles.iter().enumerate() {
+        println!("  {}) {}", i + 1, profile.name);
+    }
+
+    loop {
+        print!("Profile [1]: ");
+        std::io::Write::flush(&mut std::io::stdout())?;
+
+        let mut input = String::new();
+        if std::io::stdin().read_line(&mut input)? == 0 || input.trim().is_empty() {
+            return Ok(db);
+        }
+
+        let input = input.trim();
+        let chosen = input
+            .parse::<usize>()
+            .ok()
+            .and_then(|n| n.checked_sub(1))
+            .and_then(|i| profiles.get(i))
+            .or_else(|| profiles.iter().find(|p| p.name == input));
+        match chosen {
+            Some(profile) => return db.with_profile(&profile.name),
+            None => println!("No profile '{}'", input),
+        }
+    }
+}
+
+/// Dispatch a command-line subcommand
+/// `profile` is the one asked for with `--profile`, for commands that read a profile's history
+async fn run_command(command: &str, args: &[String], profile: Option<String>) -> Result<()> {
+    match command {
+        "import" => run_import(args),
+        "maintain" => run_maintain(args),
+        "profile" => run_profile(args),
+        "packs" => run_packs(args).await,
+        "topics" => run_topics(args),
+        "recommender-state" => run_recommender_state(profile),
+        "stats" => run_stats(args, profile),
+        "block" => run_block(args).await,
+        _ => {
+            eprintln!("Unknown command: {}", command);
+            eprintln!("Usage:");
+            eprintln!("  tellme                              Start reading");
+            eprintln!("  tellme import <file.jsonl> [--strict]  Import content from JSON Lines");
+            eprintln!("  tellme maintain [--mark-all-seen | --forget-seen | --backfill | --rescore-sensitivity | --remap-topics | --prune [--dry-run] | --migrate [--dry-run] [--backup-before-migrate]]  Database maintenance");
+            eprintln!("  tellme profile create|list|delete [name]  Manage reader profiles");
+            eprintln!("  tellme packs install|list|remove [manifest|name]  Manage content packs");
+            eprintln!("  tellme topics [--json]              List topics and their metadata");
+            eprintln!("  tellme recommender-state            Print the recommender's scores as JSON");
+            eprintln!("  tellme stats [--json]               Library size and reading totals");
+            eprintln!("  tellme block add|remove|list|sync [title pattern or content hash]  Keep stories out of reading");
+            eprintln!();
+            eprintln!("Use --profile <name> or {} to pick a profile.", tellme::PROFILE_ENV);
+            std::process::exit(2);
+        }
+    }
+}
+
+/// One entry of `tellme topics --json`
+#[derive(serde::Serialize)]
+struct TopicListing {
+    #[serde(flatten)]
+    meta: &'static TopicMeta,
+    query_count: usize,
+}
+
+/// `tellme topics [--json]`
+/// The JSON form is meant for other front ends, so its field names stay stable
+fn run_topics(args: &[String]) -> Result<()> {
+    let listings = topic_listings();
+
+    if args.iter().any(|a| a == "--json") {
+        println!("{}", serde_json::to_string_pretty(&listings)?);
+        return Ok(());
+    }
+
+    let mut group = "";
+    for listing in &listings {
+        let meta = listing.meta;
+        if meta.group != group {
+            group = meta.group;
+            println!("{}", group);
+        }
+        println!(
+            "  {} {:<20} {:<18} {} ({} queries)",
+            meta.icon, meta.name, meta.id, meta.color, listing.query_count
+        );
+    }
+    Ok(())
+}
+
+/// Every topic with its metadata, in `Topic::all` order
+fn topic_listings() -> Vec<TopicListing> {
+    Topic::all()
+        .iter()
+        .map(|topic| TopicListing {
+            meta: topic.meta(),
+            query_count: topic.search_queries().len(),
+        })
+        .collect()
+}
+
+/// `tellme recommender-state`
+/// Prints what the recommender computed for the profile, for attaching to bug reports
+fn run_recommender_state(profile: Option<String>) -> Result<()> {
+    tellme::ensure_data_dir()?;
+    let config = Config::load()?;
+    let mut db = Database::new_with_privacy(DB_FILE, config.privacy.mode)?
+        .with_recommendation(config.recommendation);
+    if let Some(name) = profile {
+        db = db.with_profile(&name)?;
+    }
+
+    println!("{}", serde_json::to_string_pretty(&db.dump_recommender_state()?)?);
+    Ok(())
+}
+
+/// Library size and reading totals, as `tellme stats --json` prints them
+#[derive(serde::Serialize)]
+struct StatsReport {
+    profile: String,
+    total_content: i64,
+    unseen_content: i64,
+    hidden_content: i64,
+    total_interactions: i64,
+    fully_read: i64,
+    skipped: i64,
+    avg_reading_time_seconds: Option<f64>,
+}
+
+/// `tellme stats [--json]`
+/// The JSON form is meant for other front ends, so its field names stay stable
+fn run_stats(args: &[String], profile: Option<String>) -> Result<()> {
+    tellme::ensure_data_dir()?;
+    let mut db = Database::new(DB_FILE)?;
+    if let Some(name) = profile {
+        db = db.with_profile(&name)?;
+    }
+
+    let interactions = db.get_interaction_stats()?;
+    let report = StatsReport {
+        profile: db.profile().name.clone(),
+        total_content: db.get_content_count()?,
+        unseen_content: db.get_unseen_count()?,
+        hidden_content: db.hidden_count()?,
+        total_interactions: interactions.total,
+        fully_read: interactions.fully_read,
+        skipped: interactions.skipped,
+        avg_reading_time_seconds: interactions.avg_reading_time_seconds,
+    };
+
+    if args.iter().any(|a| a == "--json") {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+
+    println!("Profile: {}", report.profile);
+    println!(
+        "Stories: {} ({} not yet seen, {} hidden)",
+        report.total_content, report.unseen_content, report.hidden_content
+    );
+    println!(
+        "Interactions: {} ({} read to the end, {} skipped)",
+        report.total_interactions, report.fully_read, report.skipped
+    );
+    if let Some(seconds) = report.avg_reading_time_seconds {
+        println!("Average reading time: {:.0}s", seconds);
+    }
+    let future = db.count_future_interactions()?;
+    if future > 0 {
+        println!("Warning: {}", clock_skew_warning(future));
+    }
+    Ok(())
+}
+
+/// `tellme import <file.jsonl> [--strict]`
+fn run_import(args: &[String]) -> Result<()> {
+    let strict = args.iter().any(|a| a == "--strict");
+    let path = args
+        .iter()
+        .find(|a| !a.starts_with("--"))
+        .ok_or_else(|| anyhow::anyhow!("Usage: tellme import <file.jsonl> [--strict]"))?;
+
+    tellme::ensure_data_dir()?;
+    let config = Config::load()?;
+    let db = Database::new(DB_FILE)?
+        .with_storage(config.storage)
+        .with_sensitivity(config.sensitivity);
+    let file = std::io::BufReader::new(std::fs::File::open(path)?);
+
+    println!("Importing {}{}...", path, if strict { " (strict)" } else { "" });
+    let report = import_content(&db, file, &ImportOptions { strict, ..Default::default() })?;
+    print_import_report(&report);
+    Ok(())
+}
+
+/// Print the summary and validation report for an import
+fn print_import_report(report: &ImportReport) {
+    println!("\n=== Import Summary ===");
+    println!("Imported:            {}", report.imported);
+    println!("  exact topic:       {}", report.exact);
+    println!("  mapped by alias:   {}", report.aliased);
+    println!("  classified:        {}", report.classified);
+    println!("  with warnings:     {}", report.imported_with_warnings());
+    println!("Rejected (unmapped): {}", report.unmapped);
+    println!("Rejected (invalid):  {}", report.rejected_by_validation());
+    println!("Invalid lines:       {}", report.invalid);
+    if report.duplicates > 0 {
+        println!("Already in library:  {}", report.duplicates);
+    }
+
+    if !report.validation_failures.is_empty() {
+        println!("\n=== Validation Report ===");
+        for failure in &report.validation_failures {
+            let reasons: Vec<String> = failure.issues.iter().map(|i| i.to_string()).collect();
+            println!(
+                "  {} line {} '{}': {}",
+                if failure.imported { "⚠" } else { "✗" },
+                failure.line,
+                failure.title,
+                reasons.join("; ")
+            );
+        }
+    }
+}
+
+/// `tellme packs install <manifest path or URL> [--strict] | list | remove <name>`
+async fn run_packs(args: &[String]) -> Result<()> {
+    const USAGE: &str =
+        "Usage: tellme packs install <manifest path or URL> [--strict] | list | remove <name>";
+
+    tellme::ensure_data_dir()?;
+    let target = args.iter().skip(1).find(|a| !a.starts_with("--")).map(|s| s.as_str());
+
+    match (args.first().map(|s| s.as_str()), target) {
+        (Some("install"), Some(source)) => {
+            let strict = args.iter().any(|a| a == "--strict");
+            println!("Fetching pack from {}...", source);
+            let pack = fetch_pack(source).await?;
+
+            let config = Config::load()?;
+            let db = Database::new(DB_FILE)?
+                .with_storage(config.storage)
+                .with_sensitivity(config.sensitivity);
+            println!(
+                "Installing '{}' {}{}...",
+                pack.manifest.name,
+                pack.manifest.version,
+                if strict { " (strict)" } else { "" }
+            );
+            let report = install_pack(&db, &pack, strict)?;
+            print_import_report(&report);
+        }
+        (Some("list"), None) => {
+            let db = Database::new(DB_FILE)?;
+            let packs = db.list_packs()?;
+            if packs.is_empty() {
+                println!("No packs installed");
+            }
+            for pack in packs {
+                println!(
+                    "{} {} ({} stories) - {}",
+                    pack.name, pack.version, pack.content_count, pack.description
+                );
+            }
+        }
+        (Some("remove"), Some(name)) => {
+            let db = Database::new(DB_FILE)?;
+            match db.remove_pack(name)? {
+                Some(removed) => println!("Removed pack '{}' and its {} stories", name, removed),
+                None => anyhow::bail!("No pack named '{}'", name),
+            }
+        }
+        _ => anyhow::bail!(USAGE),
+    }
+
+    Ok(())
+}
+
+/// `tellme block add <entry> | remove <entry> | list | sync`
+/// An entry is a content hash or a title pattern (a substring, or `regex:` and a regex)
+async fn run_block(args: &[String]) -> Result<()> {
+    const USAGE: &str =
+        "Usage: tellme block add <title pattern or content hash> | remove <entry> | list | sync";
+
+    tellme::ensure_data_dir()?;
+    let db = Database::new(DB_FILE)?;
+    let entry = args.get(1).map(|s| s.as_str());
+
+    match (args.first().map(|s| s.as_str()), entry) {
+        (Some("add"), Some(entry)) => {
+            if db.add_block(entry, BlockSource::Local)? {
+                println!("Blocked '{}'", entry);
+            } else {
+                println!("'{}' was already blocked", entry);
+            }
+        }
+        (Some("remove"), Some(entry)) => {
+            if !db.remove_block(entry, BlockSource::Local)? {
+                anyhow::bail!("No local block '{}' (blocks from the remote list can only be removed there)", entry);
+            }
+            println!("Unblocked '{}'", entry);
+        }
+        (Some("list"), None) => {
+            let blocks = db.list_blocks()?;
+            if blocks.is_empty() {
+                println!("Nothing is blocked");
+            }
+            for block in blocks {
+                println!("{:<6} {}", block.source.as_str(), block.entry);
+            }
+        }
+        (Some("sync"), None) => {
+            let config = Config::load()?;
+            let Some(url) = config.moderation.blocklist_url else {
+                anyhow::bail!("No moderation.blocklist_url configured");
+            };
+            match sync_blocklist(&db, &url).await? {
+                Some(sync) => println!("Remote blocklist: {} added, {} removed", sync.added, sync.removed),
+                None => println!("Remote blocklist unchanged"),
+            }
+        }
+        _ => anyhow::bail!(USAGE),
+    }
+
+    for entry in db.invalid_blocks()? {
+        eprintln!("Warning: Ignoring invalid block '{}'", entry);
+    }
+    println!("{} stories blocked", db.apply_blocks()?);
+    Ok(())
+}
+
+/// `tellme profile create <name> | list | delete <name>`
+/// Profiles share the content library but keep separate reading histories
+fn run_profile(args: &[String]) -> Result<()> {
+    const USAGE: &str = "Usage: tellme profile create <name> | list | delete <name>";
+
+    tellme::ensure_data_dir()?;
+    let db = Database::new(DB_FILE)?;
+    let name = args.get(1).map(|s| s.as_str());
+
+    match (args.first().map(|s| s.as_str()), name) {
+        (Some("create"), Some(name)) => {
+            let profile = db.create_profile(name)?;
+            println!("Created profile '{}'", profile.name);
+        }
+        (Some("list"), None) => {
+            for profile in db.list_profiles()? {
+                println!("{}", profile.name);
+            }
+        }
+        (Some("delete"), Some(name)) => {
+            if db.delete_profile(name)? {
+                println!("Deleted profile '{}' and its reading history", name);
+            } else {
+                anyhow::bail!("No profile named '{}'", name);
+            }
+        }
+        _ => anyhow::bail!(USAGE),
+    }
+
+    Ok(())
 }
 
+/// `tellme maintain [--mark-all-seen | --forget-seen | --backfill | --rescore-sensitivity | --remap-topics | --prune | --migrate]`
+/// Without flags, permanently purges content that was deleted long enough ago, prunes
+/// by the retention policy when it's enabled, and trims the library down to the
+/// configured size limit. `--prune` applies the retention policy even when it's off.
+fn run_maintain(args: &[String]) -> Result<()> {
+    tellme::ensure_data_dir()?;
+    let config = Config::load()?;
+    let has_flag = |flag: &str| args.iter().any(|a| a == flag);
+
+    let actions = [
+        "--mark-all-seen",
+        "--forget-seen",
+        "--backfill",
+        "--rescore-sensitivity",
+        "--remap-topics",
+        "--prune",
+        "--migrate",
+    ];
+    if actions.iter().filter(|flag| has_flag(flag)).count() > 1 {
+        anyhow::bail!("Only one of {} can be given", actions.join(", "));
+    }
+    if has_flag("--dry-run") && !has_flag("--migrate") && !has_flag("--prune") {
+        anyhow::bail!("--dry-run only works together with --migrate or --prune");
+    }
+    if has_flag("--backup-before-migrate") && !has_flag("--migrate") {
+        anyhow::bail!("--backup-before-migrate only works together with --migrate");
+    }
+
+    // Migrating has to look at the database before opening it applies anything
+    if has_flag("--migrate") {
+        return run_migrate(has_flag("--dry-run"), has_flag("--backup-before-migrate"));
+    }
+
+    let db = Database::new(DB_FILE)?.with_sensitivity(config.sensitivity.clone());
+
+    if has_flag("--mark-all-seen") {
+        let changed = db.mark_all_seen()?;
+        println!("Marked {} content units as seen", changed);
+    } else if has_flag("--forget-seen") {
+        let changed = db.forget_seen()?;
+        println!("Reset seen state on {} content units", changed);
+    } else if has_flag("--backfill") {
+        for (column, filled) in db.backfill(BackfillColumn::all())? {
+            println!("Filled in {} on {} content units", column.column_name(), filled);
+        }
+    } else if has_flag("--rescore-sensitivity") {
+        let scored = db.rescore_sensitivity()?;
+        println!("Scored sensitivity of {} content units", scored);
+    } else if has_flag("--remap-topics") {
+        let (migrated, unmapped) = migrate_legacy_topics(&db)?;
+        println!("Moved {} content units from old topic names", migrated);
+        for (label, count) in unmapped {
+            println!("  No current topic for '{}' ({} content units)", label, count);
+        }
+    } else if has_flag("--prune") {
+        print_retention(&db.apply_retention(&config.storage.retention, has_flag("--dry-run"))?);
+    } else {
+        let purged = db.purge_deleted_content(chrono::Duration::days(SOFT_DELETE_RETENTION_DAYS))?;
+        println!("Purged {} deleted content units", purged);
+        if config.storage.retention.enabled {
+            print_retention(&db.apply_retention(&config.storage.retention, false)?);
+        }
+        if let Some(max) = config.storage.max_content_units {
+            let evicted = db.enforce_size_limit(max)?;
+            println!("Evicted {} content units to stay within {}", evicted, max);
+        }
+    }
+
+    println!("{} content units not yet seen", db.get_unseen_count()?);
+
+    let unknown_topics = db.get_unknown_topic_labels()?;
+    if !unknown_topics.is_empty() && !has_flag("--remap-topics") {
+        let labels: Vec<&str> = unknown_topics.iter().map(|(label, _)| label.as_str()).collect();
+        println!(
+            "Content is stored under old topic names ({}); run with --remap-topics to move it",
+            labels.join(", ")
+        );
+    }
+
+    let orphans = db.count_orphaned_interactions()?;
+    if orphans > 0 {
+        println!("{} recorded interactions point at content that no longer exists", orphans);
+    }
+    Ok(())
+}
+
+/// Summarise what the retention policy pruned; a dry run lists every story it would take
+fn print_retention(report: &RetentionReport) {
+    for topic in &report.topics {
+        println!(
+            "  {}: {} of {} stories {}{}",
+            topic.topic,
+            topic.pruned.len(),
+            topic.stored,
+            if report.dry_run { "would be pruned" } else { "pruned" },
+            if topic.kept_by_floor > 0 {
+                format!(", {} kept to stay at the per-topic minimum", topic.kept_by_floor)
+            } else {
+                String::new()
+            }
+        );
+        if report.dry_run {
+            for (id, title) in &topic.pruned {
+                println!("    [{}] {}", id, title);
+            }
+        }
+    }
+
+    let action = match (report.dry_run, report.soft_deleted) {
+        (true, _) => "Would prune",
+        (false, true) => "Soft-deleted",
+        (false, false) => "Removed",
+    };
+    println!(
+        "{} {} low-quality stories nobody has read ({} kept by the per-topic minimum)",
+        action,
+        report.pruned(),
+        report.kept_by_floor()
+    );
+}
+
+/// `tellme maintain --migrate [--dry-run] [--backup-before-migrate]`
+/// Lists pending schema migrations with their SQL, then applies them unless it's a dry run
+fn run_migrate(dry_run: bool, backup: bool) -> Result<()> {
+    if !std::path::Path::new(DB_FILE).exists() {
+        println!("No database yet; it will be created with the current schema");
+        return Ok(());
+    }
+
+    let pending = Database::pending_migrations(DB_FILE)?;
+    if pending.is_empty() {
+        println!("Schema is up to date (version {})", Database::schema_version(DB_FILE)?);
+        return Ok(());
+    }
+
+    println!("{} pending migrations:", pending.len());
+    for migration in &pending {
+        println!("  {:>3}  {}", migration.version, migration.description);
+        println!("       {};", migration.sql());
+    }
+    if dry_run {
+        println!("Dry run: nothing was changed");
+        return Ok(());
+    }
+
+    if backup {
+        let path = Database::pre_migrate_backup_path(DB_FILE, Database::schema_version(DB_FILE)?);
+        Database::backup(DB_FILE, &path)?;
+        println!("Backed up the database to {}", path.display());
+    }
+
+    // Opening the database applies and logs the pending migrations
+    let db = Database::new_without_backup(DB_FILE)?;
+    let versions: Vec<u32> = pending.iter().map(|migration| migration.version).collect();
+    for record in db.get_migrations_log()?.iter().filter(|r| versions.contains(&r.version)) {
+        println!(
+            "Applied {} ({}) in {} ms",
+            record.version, record.description, record.duration_ms
+        );
+    }
+    Ok(())
+}
+
+/// Stories listed in the recommender preview
+const PREVIEW_PICKS: usize = 10;
+
+/// Most matches listed by the search prompt
+const SEARCH_RESULTS: usize = 20;
+
 /// Main application loop
 /// This demonstrates the event loop pattern and state management
 async fn run_app(
     terminal: &mut ratatui::Terminal<ratatui::backend::CrosstermBackend<std::io::Stdout>>,
     app: &mut App,
     db: &Database,
-) -> Result<()> {
+    update_check: tokio::task::JoinHandle<Option<UpdateInfo>>,
+) -> Result<Option<UpdateInfo>> {
+    let frame_interval = app.ui.frame_interval();
     let mut last_update = std::time::Instant::now();
-    let update_interval = Duration::from_millis(50); // 20 FPS
+    let mut update_check = Some(update_check);
+    let mut update = None;
+    let mut fetch_events = None;
+    let mut lookup: Option<(String, tokio::task::JoinHandle<Result<Option<TermSummary>>>)> = None;
+    // Term lookups and background fetches share one client, so one rate cap covers both
+    let wiki = WikipediaClient::new(&Config::load().map(|config| config.fetch).unwrap_or_default());
+    // The remote blocklist is checked at startup and then every `refresh_hours`
+    let moderation = Config::load().map(|config| config.moderation).unwrap_or_default();
+    let mut blocklist_check: Option<tokio::task::JoinHandle<Result<BlocklistFetch>>> = None;
+    let mut next_blocklist_check = std::time::Instant::now();
+    // Content the pin marker was last looked up for
+    let mut pin_checked: Option<i64> = None;
 
     loop {
-        // Handle input events
-        handle_events(app)?;
+        // Handle input events; waiting for them is what paces the loop
+        handle_events(app, frame_interval)?;
 
         // Check if user wants to quit
         if app.should_quit {
             break;
         }
 
-        // Update typewriter effect
+        // Run the next step of a macro key, if one is in progress
+        app.step_macro();
+
+        // Update the focus timer, then the typewriter effect unless content is paused for a break
+        app.update_focus();
         let now = std::time::Instant::now();
-        if now.duration_since(last_update) >= update_interval {
-            app.update_typewriter();
-            last_update = now;
-        }
-
-        // Check if we need new content
-        if !app.has_content() && !app.should_quit {
-            // Record interaction with previous content if any
-            if let Some(ref content) = app.current_content {
-                let reading_time = app.get_reading_time();
-                let interaction = if app.fully_displayed && reading_time >= 3 {
-                    // Consider it "fully read" if they saw it all and spent some time
-                    UserInteraction::fully_read(content.id, reading_time)
-                } else {
-                    // Otherwise, consider it skipped
-                    UserInteraction::skipped(content.id, reading_time)
+        if !app.on_break() {
+            app.update_typewriter(now.duration_since(last_update));
+        }
+        last_update = now;
+        app.expire_status();
+
+        // Mention an update once the background check has found one
+        if let Some(check) = update_check.take_if(|check| check.is_finished()) {
+            update = check.await.ok().flatten();
+            if let Some(ref info) = update {
+                app.set_status(info.summary());
+            }
+        }
+
+        // Gather what the about screen shows
+        if app.about_requested {
+            app.about_requested = false;
+            let update = match (&update, &update_check) {
+                (Some(info), _) => format!("{} is available: {}", info.latest_version, info.release_url),
+                (None, Some(_)) => "Still checking…".to_string(),
+                (None, None) => "None found (or GitHub couldn't be reached)".to_string(),
+            };
+            app.about = Some(AboutInfo {
+                db_path: std::fs::canonicalize(DB_FILE)
+                    .map_or_else(|_| DB_FILE.to_string(), |path| path.display().to_string()),
+                profile: db.profile().name.clone(),
+                content_count: db.get_content_count().unwrap_or(0),
+                hidden_count: db.hidden_count().unwrap_or(0),
+                update,
+            });
+        }
+
+        // Start a fetch in the background and follow its progress
+        if app.fetch_requested {
+            app.fetch_requested = false;
+            fetch_events = Some(tellme::fetch::spawn_fetch(wiki.clone()));
+            app.fetch = Some(FetchProgress::default());
+        }
+        if let Some(events) = &fetch_events {
+            for event in events.try_iter() {
+                app.apply_fetch_event(event);
+            }
+            if !app.fetching() {
+                fetch_events = None;
+            }
+        }
+
+        // Sync the remote blocklist in the background; a failed fetch just waits for the next check
+        if let Some(url) = &moderation.blocklist_url {
+            if blocklist_check.is_none() && std::time::Instant::now() >= next_blocklist_check {
+                next_blocklist_check += moderation.refresh_interval().to_std().unwrap_or_default();
+                let etag = db.get_blocklist_sync(url).ok().flatten().and_then(|(etag, _)| etag);
+                let url = url.clone();
+                blocklist_check =
+                    Some(tokio::spawn(async move { fetch_blocklist(&url, etag.as_deref()).await }));
+            }
+            if let Some(task) = blocklist_check.take_if(|task| task.is_finished()) {
+                if let Ok(Ok(fetched)) = task.await {
+                    match apply_blocklist_fetch(db, url, fetched) {
+                        Ok(Some(sync)) if sync.added + sync.removed > 0 => {
+                            let mut message =
+                                format!("Blocklist updated: {} added, {} removed", sync.added, sync.removed);
+                            if !sync.invalid.is_empty() {
+                                message = format!("{}; {}", message, invalid_blocks_note(&sync.invalid));
+                            }
+                            app.flash_status(message);
+                        }
+                        Ok(_) => {}
+                        Err(e) => app.flash_status(format!("Failed to update the blocklist: {}", e)),
+                    }
+                }
+            }
+        }
+
+        // Search the library as the query changes, and read the match picked
+        if app.search_requested {
+            app.search_requested = false;
+            if let Some(query) = app.search.as_ref().map(|search| search.query.clone()) {
+                match db.search_content(&query, SEARCH_RESULTS) {
+                    Ok(results) => app.show_search_results(results),
+                    Err(e) => app.flash_status(format!("Search failed: {}", e)),
+                }
+            }
+        }
+        if let Some(content) = app.search_choice.take() {
+            record_current_interaction(app, db);
+            if let Err(e) = db.mark_shown(content.id) {
+                eprintln!("Warning: Failed to record content view: {}", e);
+            }
+            app.set_content(content);
+        }
+
+        // Explain a term from the cache, or look it up in the background
+        if let Some(term) = app.lookup_requested.take() {
+            let cached = db.get_cached_term(&term).unwrap_or_else(|e| {
+                app.flash_status(format!("Failed to read cached explanation: {}", e));
+                None
+            });
+            match cached {
+                Some(summary) => app.apply_term_lookup(&term, Ok(Some(summary))),
+                None => {
+                    let (client, query) = (wiki.clone(), term.clone());
+                    let task = tokio::spawn(async move { client.get_term_summary(&query).await });
+                    lookup = Some((term, task));
+                }
+            }
+        }
+        if let Some((term, task)) = lookup.take_if(|(_, task)| task.is_finished()) {
+            let result = task.await.unwrap_or_else(|e| Err(e.into()));
+            if let Ok(Some(summary)) = &result {
+                if let Err(e) = db.cache_term(summary) {
+                    app.flash_status(format!("Failed to cache explanation: {}", e));
+                }
+            }
+            app.apply_term_lookup(&term, result);
+        }
+
+        // Keep an explanation as a story of its own, in the current story's topic
+        if app.save_term_requested {
+            app.save_term_requested = false;
+            if let (Some(summary), Some(content)) = (app.shown_term(), &app.current_content) {
+                let mut unit = summary.to_content_unit(content.topic);
+                let message = match db.content_title_exists(&unit.title) {
+                    Ok(true) => format!("\"{}\" is already in your library", unit.title),
+                    Ok(false) => match db.insert_content(&mut unit) {
+                        Ok(()) => format!("Saved \"{}\" as a story", unit.title),
+                        Err(e) if e.is::<DuplicateContent>() => {
+                            format!("\"{}\" is already in your library", unit.title)
+                        }
+                        Err(e) => format!("Failed to save explanation: {}", e),
+                    },
+                    Err(e) => format!("Failed to save explanation: {}", e),
                 };
-                
-                if let Err(e) = db.record_interaction(&interaction) {
-                    eprintln!("Warning: Failed to record interaction: {}", e);
+                app.flash_status(message);
+            }
+        }
+
+        // Remember the reveal speed preset for next time
+        if app.reveal_speed_changed {
+            app.reveal_speed_changed = false;
+            if let Err(e) = Config::persist_value("ui", "reveal_speed", app.ui.reveal_speed.as_str()) {
+                app.flash_status(format!("Failed to save reveal speed: {}", e));
+            }
+        }
+
+        // Remember whether locked topics are read in order
+        if app.guided_order_changed {
+            app.guided_order_changed = false;
+            if let Err(e) = Config::persist_value("ui", "guided_topic_order", app.ui.guided_topic_order) {
+                app.flash_status(format!("Failed to save topic order: {}", e));
+            }
+        }
+
+        // Nudge how adventurous topic selection is, and remember it for next time
+        if app.balance_nudge != 0.0 {
+            let balance = db.set_explore_exploit_balance(db.explore_exploit_balance() + app.balance_nudge);
+            app.balance_nudge = 0.0;
+            let leaning = if balance <= 0.0 {
+                "favourite topics only"
+            } else if balance >= 1.0 {
+                "exploring only"
+            } else {
+                match balance.total_cmp(&DEFAULT_EXPLORE_EXPLOIT_BALANCE) {
+                    std::cmp::Ordering::Less => "favouring favourite topics",
+                    std::cmp::Ordering::Equal => "the usual mix",
+                    std::cmp::Ordering::Greater => "exploring more",
+                }
+            };
+            app.flash_status(format!("Explore/exploit balance {:.1}: {}", balance, leaning));
+            if let Err(e) = Config::persist_value("recommendation", "explore_exploit_balance", balance) {
+                app.flash_status(format!("Failed to save balance: {}", e));
+            }
+        }
+
+        // Record how the user engaged with the current content before moving on
+        if app.next_requested {
+            app.next_requested = false;
+            record_current_interaction(app, db);
+            app.current_content = None;
+        }
+
+        // Go back to the previous story, recording the one being left as usual
+        if app.back_requested {
+            app.back_requested = false;
+            if app.can_go_back() {
+                record_current_interaction(app, db);
+                app.show_previous();
+            } else {
+                app.flash_status("This is the first story this session".to_string());
+            }
+        }
+
+        // Switch to content from the newly chosen topic
+        if app.topic_filter_changed {
+            app.topic_filter_changed = false;
+            record_current_interaction(app, db);
+            app.current_content = None;
+            load_next_content(app, db);
+            if !app.topic_filter_empty {
+                match (app.quick_bites, app.topic_filter) {
+                    _ if app.chronological.is_some() => app.flash_status(format!(
+                        "Oldest first from {}",
+                        app.topic_filter.map_or("all topics".to_string(), |topic| topic.to_string())
+                    )),
+                    (true, Some(topic)) => app.flash_status(format!("Quick bites from {}", topic)),
+                    (true, None) => app.flash_status("Quick bites from all topics".to_string()),
+                    (false, Some(topic)) => match topic_filter_label(app.topic_filter_share) {
+                        Some("filtered") => app.flash_status(format!("Showing only {}", topic)),
+                        Some(_) => app.flash_status(format!("Showing mostly {}", topic)),
+                        None => app.flash_status("Showing all topics".to_string()),
+                    },
+                    (false, None) => app.flash_status("Showing all topics".to_string()),
+                }
+            }
+        }
+
+        // Load trend data for the trends screen
+        if app.trend_requested {
+            app.trend_requested = false;
+            match db.get_topic_trend(app.trend_bucket) {
+                Ok(trend) => app.trend = Some(trend),
+                Err(e) => app.flash_status(format!("Failed to load trends: {}", e)),
+            }
+        }
+
+        // Work out the recommender preview
+        if app.preview_requested {
+            app.preview_requested = false;
+            match db.preview_next_selections(PREVIEW_PICKS) {
+                Ok(preview) => app.preview = preview,
+                Err(e) => app.flash_status(format!("Failed to preview picks: {}", e)),
+            }
+        }
+
+        // Load read counts for the activity calendar
+        if app.calendar_requested {
+            app.calendar_requested = false;
+            match db.get_activity_calendar(app.calendar_year) {
+                Ok(calendar) => app.calendar = Some(calendar),
+                Err(e) => app.flash_status(format!("Failed to load calendar: {}", e)),
+            }
+        }
+
+        // Soft-delete the current content and move on
+        if app.delete_requested {
+            app.delete_requested = false;
+            if let Some(content) = app.current_content.take() {
+                match db.delete_content(content.id) {
+                    Ok(true) => {
+                        app.forget_in_history(content.id);
+                        app.deleted_history.push(content);
+                        load_next_content(app, db);
+                        app.set_status("Deleted — press u to undo".to_string());
+                    }
+                    // Already deleted or removed elsewhere, so there's nothing to undo either
+                    Ok(false) => {
+                        app.abort_macro();
+                        app.set_status("Nothing to delete".to_string());
+                        app.current_content = Some(content);
+                    }
+                    Err(e) => {
+                        app.abort_macro();
+                        app.set_status(format!("Failed to delete content: {}", e));
+                        app.current_content = Some(content);
+                    }
                 }
             }
+        }
 
-            // Load new content
-            app.set_status("Loading new content...".to_string());
-            
-            match db.get_weighted_random_content() {
-                Ok(Some(content)) => {
-                    app.set_content(content);
+        // "Read elsewhere": a strong skip that also hides similar stories for the session
+        if app.dismiss_requested {
+            app.dismiss_requested = false;
+            if let Some(content) = app.current_content.take() {
+                let interaction = UserInteraction::dismissed(content.id, app.get_reading_time());
+                if let Err(e) = PendingQueue::default().record_or_queue(db, &interaction) {
+                    eprintln!("Warning: Failed to record dismissal: {}", e);
                 }
-                Ok(None) => {
-                    app.set_status("No more content available.".to_string());
+                let similar = db.find_near_duplicates(&content).unwrap_or_default();
+                db.suppress_for_session(&similar);
+                app.forget_in_history(content.id);
+                load_next_content(app, db);
+                match similar.len() {
+                    0 => app.flash_status("Dismissed".to_string()),
+                    n => app.flash_status(format!("Dismissed, hiding {} similar stories", n)),
+                }
+            }
+        }
+
+        // Hide the current content for good and move on
+        if app.hide_requested {
+            app.hide_requested = false;
+            if let Some(content) = app.current_content.take() {
+                match db.hide_content(content.id) {
+                    Ok(_) => {
+                        app.forget_in_history(content.id);
+                        load_next_content(app, db);
+                        app.flash_status("Hidden — it won't be shown again".to_string());
+                    }
+                    Err(e) => {
+                        app.abort_macro();
+                        app.set_status(format!("Failed to hide content: {}", e));
+                        app.current_content = Some(content);
+                    }
                 }
-                Err(e) => {
-                    app.set_status(format!("Error loading content: {}", e));
+            }
+        }
+
+        // Bookmark the current content, or remove its bookmark
+        if app.pin_requested {
+            app.pin_requested = false;
+            if let Some(id) = app.current_content.as_ref().map(|c| c.id) {
+                let toggled = db.is_bookmarked(id).and_then(|bookmarked| {
+                    if bookmarked {
+                        db.remove_bookmark(id).map(|_| (false, "Bookmark removed"))
+                    } else {
+                        db.add_bookmark(id).map(|_| (true, "Bookmarked — it will come back now and then"))
+                    }
+                });
+                match toggled {
+                    Ok((bookmarked, message)) => {
+                        app.current_is_bookmarked = bookmarked;
+                        app.flash_status(message.to_string());
+                    }
+                    Err(e) => {
+                        app.abort_macro();
+                        app.flash_status(format!("Failed to bookmark content: {}", e));
+                    }
                 }
             }
         }
 
+        // Restore the most recently deleted content and show it again
+        if app.undo_requested {
+            app.undo_requested = false;
+            match app.deleted_history.pop() {
+                Some(content) => match db.restore_content(content.id) {
+                    Ok(true) => {
+                        record_current_interaction(app, db);
+                        let title = content.title.clone();
+                        app.set_content(content);
+                        app.set_status(format!("Restored '{}'", title));
+                    }
+                    // Purged or restored elsewhere in the meantime
+                    Ok(false) => {
+                        app.abort_macro();
+                        app.set_status(format!("Nothing to restore for '{}'", content.title));
+                    }
+                    Err(e) => {
+                        app.abort_macro();
+                        app.set_status(format!("Failed to restore content: {}", e));
+                        app.deleted_history.push(content);
+                    }
+                },
+                None => app.set_status("Nothing to undo".to_string()),
+            }
+        }
+
+        // Check if we need new content (an empty topic filter waits for the user to change it,
+        // and nothing new loads during a focus break)
+        if !app.has_content() && !app.should_quit && !app.topic_filter_empty && !app.on_break() {
+            load_next_content(app, db);
+        }
+
+        // Look up whether newly shown content is bookmarked or sensitive, for the status bar markers
+        let current_id = app.current_content.as_ref().map(|c| c.id);
+        if current_id != pin_checked {
+            pin_checked = current_id;
+            app.current_is_bookmarked = match current_id {
+                Some(id) => db.is_bookmarked(id).unwrap_or(false),
+                None => false,
+            };
+            app.current_is_sensitive = match current_id {
+                Some(id) => db.is_sensitive(id).unwrap_or(false),
+                None => false,
+            };
+        }
+
         // Render the UI
         terminal.draw(|frame| render_ui(frame, app))?;
-
-        // Small delay to prevent excessive CPU usage
-        tokio::time::sleep(Duration::from_millis(16)).await; // ~60 FPS
     }
 
     // Record final interaction if there was content being viewed
+    record_current_interaction(app, db);
+
+    Ok(update)
+}
+
+/// Record how the user engaged with the content currently on screen
+/// If the database can't take the write, the interaction is queued on disk instead
+fn record_current_interaction(app: &mut App, db: &Database) {
+    // Content brought back with Left was recorded when it was first left
+    if app.revisiting {
+        return;
+    }
     if let Some(ref content) = app.current_content {
         let reading_time = app.get_reading_time();
         let interaction = if app.fully_displayed && reading_time >= 3 {
+            // Consider it "fully read" if they saw it all and spent some time
             UserInteraction::fully_read(content.id, reading_time)
         } else {
-            UserInteraction::skipped(content.id, reading_time)
+            // Otherwise, consider it skipped, remembering how far the reader got
+            UserInteraction::skipped_at(
+                content.id,
+                reading_time,
+                app.displayed_chars,
+                app.displayed_percent().min(100) as u8,
+            )
         };
-        
-        if let Err(e) = db.record_interaction(&interaction) {
-            eprintln!("Warning: Failed to record final interaction: {}", e);
+
+        match PendingQueue::default().record_or_queue(db, &interaction) {
+            Ok(true) => {}
+            Ok(false) => app.flash_status("Database busy — progress saved for later".to_string()),
+            Err(e) => app.flash_status(format!("Failed to record interaction: {}", e)),
         }
     }
+}
 
-    Ok(())
-} 
\ No newline at end of file
+/// Load the next recommended content into the app
+fn load_next_content(app: &mut App, db: &Database) {
+    app.set_status("Loading new content...".to_string());
+
+    // Now and then, offer to finish something skipped most of the way through
+    if !app.quick_bites && app.chronological.is_none() && app.topic_filter.is_none() {
+        match db.pick_resume_candidate() {
+            Ok(Some(candidate)) => {
+                if let Err(e) = db.mark_shown(candidate.content.id) {
+                    eprintln!("Warning: Failed to record content view: {}", e);
+                }
+                app.resume_content(candidate.content, candidate.displayed_chars);
+                return;
+            }
+            Ok(None) => {}
+            Err(e) => eprintln!("Warning: Failed to look up stories to resume: {}", e),
+        }
+    }
+
+    let next = if let Some(after) = app.chronological {
+        db.get_next_chronological(app.topic_filter, after).map(|next| {
+            next.map(|(content, year)| {
+                app.chronological = Some((year, content.id));
+                content
+            })
+        })
+    } else {
+        let next = match app.topic_filter {
+            _ if app.quick_bites => db.get_quick_bite(app.topic_filter),
+            Some(_) if !db.roll_for_topic_filter() => db.get_weighted_random_content(),
+            Some(topic) if app.ui.guided_topic_order => db.get_guided_content_for_topic(topic),
+            Some(topic) => db.get_content_for_topic(topic),
+            None => db.get_weighted_random_content(),
+        };
+        // A soft topic filter moves on to other topics once its own has nothing left
+        match next {
+            Ok(None) if app.topic_filter.is_some() && !app.quick_bites && db.has_soft_topic_filter() => {
+                db.get_weighted_random_content()
+            }
+            next => next,
+        }
+    };
+
+    match next {
+        Ok(Some(content)) => {
+            if let Err(e) = db.mark_shown(content.id) {
+                eprintln!("Warning: Failed to record content view: {}", e);
+            }
+            app.set_content(content);
+        }
+        Ok(None) => match app.topic_filter {
+            _ if app.chronological.is_some() => {
+                app.topic_filter_empty = true;
+                app.set_status("No later dated stories — press y to leave chronological order".to_string());
+            }
+            _ if app.quick_bites => {
+                app.topic_filter_empty = true;
+                app.set_status("No fun facts to show — press f for regular reading".to_string());
+            }
+            Some(topic) => {
+                app.topic_filter_empty = true;
+                app.set_status(format!("No content for {} — press t to change", topic));
+            }
+            None => app.set_status("No more content available — press n to fetch more".to_string()),
+        },
+        Err(e) => {
+            app.set_status(format!("Error loading content: {}", e));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::{backend::TestBackend, Terminal};
+    use std::sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    };
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn the_loading_screen_renders_before_slow_startup_work_finishes() {
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        let mut app = App::new();
+        let done = Arc::new(AtomicBool::new(false));
+        let mut drawn_before_done = None;
+
+        // A stand-in for the database work that takes a while to pick the first story
+        let work_done = Arc::clone(&done);
+        let work = move |progress: std::sync::mpsc::Sender<&'static str>| {
+            progress.send("Choosing a story").unwrap();
+            std::thread::sleep(Duration::from_millis(300));
+            work_done.store(true, Ordering::SeqCst);
+            Ok(42)
+        };
+        // Input handling runs after each frame, starting with the first
+        let handle = |_: &mut App| {
+            drawn_before_done.get_or_insert(!done.load(Ordering::SeqCst));
+            std::thread::sleep(Duration::from_millis(10));
+            Ok(())
+        };
+
+        let result = load_startup(&mut terminal, &mut app, handle, work).await.unwrap();
+        assert_eq!(result, Some(42));
+        assert_eq!(drawn_before_done, Some(true));
+
+        let buffer = terminal.backend().buffer();
+        let status: String = (0..80).map(|x| buffer.get(x, 2).symbol.as_str()).collect();
+        assert!(status.contains("Choosing a story..."), "{status}");
+    }
+
+    #[test]
+    fn each_startup_phase_is_reported_and_closes_its_span_when_the_next_begins() {
+        let log = TimingsLog::default();
+        let (progress, phases) = std::sync::mpsc::channel();
+
+        tracing::subscriber::with_default(timings_subscriber(&log), || {
+            let mut timer = StartupPhases { progress, current: None };
+            timer.start("Checking the clock");
+            timer.start("Counting content");
+            assert_eq!(log.to_string().lines().count(), 1, "{log}");
+            timer.stop();
+        });
+
+        assert_eq!(phases.try_iter().collect::<Vec<_>>(), ["Checking the clock", "Counting content"]);
+        let lines: Vec<String> = log.to_string().lines().map(str::to_string).collect();
+        assert_eq!(lines.len(), 2, "{log}");
+        for (line, phase) in lines.iter().zip(["Checking the clock", "Counting content"]) {
+            assert!(line.contains(&format!("startup{{phase=\"{}\"}}: close time.busy=", phase)), "{line}");
+        }
+    }
+
+    #[test]
+    fn topic_metadata_json_matches_the_snapshot() {
+        // Front ends depend on these ids and field names; update the snapshot only on purpose
+        let json = serde_json::to_string_pretty(&topic_listings()).unwrap();
+        assert_eq!(json.trim_end(), include_str!("snapshots/topics.json").trim_end());
+    }
+}
@@ -0,0 +1,305 @@
+// moderation.rs - Blocking specific stories
+// Blocks are kept in the database, each tagged with where it came from: added
+// by hand on this machine, or taken from a remote list (`moderation.blocklist_url`)
+// that someone maintains for a whole classroom. Syncing the remote list only
+// ever adds and removes remote entries, so local blocks survive any list change.
+
+use crate::{fetch::TitleBlocklist, Database, Result};
+use std::collections::HashSet;
+
+/// Where a block came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockSource {
+    /// Added on this machine with `tellme block add`
+    Local,
+    /// Taken from the remote blocklist; removed again when it leaves the list
+    Remote,
+}
+
+impl BlockSource {
+    /// Name stored in the database
+    pub fn as_str(self) -> &'static str {
+        match self {
+            BlockSource::Local => "local",
+            BlockSource::Remote => "remote",
+        }
+    }
+
+    /// Parse a stored name, treating anything unknown as local so it's never synced away
+    pub fn from_stored(name: &str) -> Self {
+        match name {
+            "remote" => BlockSource::Remote,
+            _ => BlockSource::Local,
+        }
+    }
+}
+
+/// One stored block
+#[derive(Debug, Clone)]
+pub struct BlockedEntry {
+    /// A content hash, a title substring or `regex:` followed by a title regex
+    pub entry: String,
+    pub source: BlockSource,
+    pub added_at: String,
+}
+
+/// Result of fetching the remote blocklist
+#[derive(Debug, Clone)]
+pub enum BlocklistFetch {
+    /// The server says the list hasn't changed since the ETag we sent
+    NotModified,
+    Updated {
+        entries: Vec<String>,
+        etag: Option<String>,
+    },
+}
+
+/// What syncing with the remote blocklist changed
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BlocklistSync {
+    pub added: usize,
+    pub removed: usize,
+    /// Stored blocks that don't compile (a bad regex), which are ignored
+    pub invalid: Vec<String>,
+}
+
+/// Fetch the remote blocklist and bring the remote blocks in line with it
+/// Returns None when the list hasn't changed since the last sync
+pub async fn sync_blocklist(db: &Database, url: &str) -> Result<Option<BlocklistSync>> {
+    let etag = db.get_blocklist_sync(url)?.and_then(|(etag, _)| etag);
+    let fetched = fetch_blocklist(url, etag.as_deref()).await?;
+    apply_blocklist_fetch(db, url, fetched)
+}
+
+/// Store a fetched blocklist, returning what changed if it did
+pub fn apply_blocklist_fetch(db: &Database, url: &str, fetched: BlocklistFetch) -> Result<Option<BlocklistSync>> {
+    match fetched {
+        BlocklistFetch::NotModified => {
+            db.record_blocklist_sync(url, None)?;
+            Ok(None)
+        }
+        BlocklistFetch::Updated { entries, etag } => {
+            let sync = db.sync_remote_blocks(&entries)?;
+            db.record_blocklist_sync(url, etag.as_deref())?;
+            Ok(Some(sync))
+        }
+    }
+}
+
+/// Fetch the remote blocklist, sending `etag` so an unchanged list isn't downloaded again
+pub async fn fetch_blocklist(url: &str, etag: Option<&str>) -> Result<BlocklistFetch> {
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(15))
+        .build()?;
+    let mut request = client.get(url);
+    if let Some(etag) = etag {
+        request = request.header(reqwest::header::IF_NONE_MATCH, etag);
+    }
+
+    let response = request.send().await?;
+    if response.status() == reqwest::StatusCode::NOT_MODIFIED {
+        return Ok(BlocklistFetch::NotModified);
+    }
+    let response = response.error_for_status()?;
+    let etag = response
+        .headers()
+        .get(reqwest::header::ETAG)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    let text = response.text().await?;
+
+    Ok(BlocklistFetch::Updated {
+        entries: parse_blocklist(&text),
+        etag,
+    })
+}
+
+/// Entries of a newline-delimited blocklist
+/// Blank lines and lines starting with `#` are skipped, and hashes are normalised
+pub fn parse_blocklist(text: &str) -> Vec<String> {
+    let mut seen = HashSet::new();
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(normalize_entry)
+        .filter(|entry| seen.insert(entry.clone()))
+        .collect()
+}
+
+/// An entry as stored: content hashes lowercased without a `sha256:` prefix, anything
+/// else as given
+pub fn normalize_entry(entry: &str) -> String {
+    let entry = entry.trim();
+    match as_content_hash(entry) {
+        Some(hash) => hash,
+        None => entry.to_string(),
+    }
+}
+
+/// The content hash an entry names, if it is one (see `ContentUnit::content_hash`)
+fn as_content_hash(entry: &str) -> Option<String> {
+    let hex = entry.strip_prefix("sha256:").unwrap_or(entry);
+    (hex.len() == 64 && hex.chars().all(|c| c.is_ascii_hexdigit())).then(|| hex.to_lowercase())
+}
+
+/// Compiled blocks, for checking content against all of them at once
+pub struct ContentBlocklist {
+    hashes: HashSet<String>,
+    titles: TitleBlocklist,
+    invalid: Vec<String>,
+}
+
+impl ContentBlocklist {
+    /// Compile stored entries; invalid title regexes are set aside (see `invalid`) instead
+    /// of failing, so one bad line in a shared list doesn't switch off every other block
+    pub fn new<'a>(entries: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut hashes = HashSet::new();
+        let mut patterns = Vec::new();
+        let mut invalid = Vec::new();
+
+        for entry in entries {
+            if let Some(hash) = as_content_hash(entry) {
+                hashes.insert(hash);
+            } else if entry.strip_prefix("regex:").is_some_and(|expr| regex::Regex::new(expr).is_err()) {
+                invalid.push(entry.to_string());
+            } else {
+                patterns.push(entry);
+            }
+        }
+
+        let titles = TitleBlocklist::from_patterns(patterns).expect("regexes were checked above");
+        Self { hashes, titles, invalid }
+    }
+
+    /// Entries that were left out because they don't compile
+    pub fn invalid(&self) -> &[String] {
+        &self.invalid
+    }
+
+    /// Whether content with this title and content hash is blocked
+    pub fn is_blocked(&self, title: &str, content_hash: Option<&str>) -> bool {
+        content_hash.is_some_and(|hash| self.hashes.contains(hash)) || self.titles.is_blocked(title)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ContentUnit;
+    use std::{
+        io::{BufRead, BufReader, Write},
+        net::TcpListener,
+        sync::{Arc, Mutex},
+    };
+
+    /// A remote blocklist on a local port, served with its version as the ETag
+    /// A request sending the current version back gets 304 Not Modified
+    struct MockList {
+        url: String,
+        list: Arc<Mutex<(u32, String)>>,
+    }
+
+    impl MockList {
+        fn start(text: &str) -> Self {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            let url = format!("http://{}/blocklist.txt", listener.local_addr().unwrap());
+            let list = Arc::new(Mutex::new((1, text.to_string())));
+            let served = Arc::clone(&list);
+            std::thread::spawn(move || {
+                for mut stream in listener.incoming().flatten() {
+                    let mut reader = BufReader::new(stream.try_clone().unwrap());
+                    let mut if_none_match = None;
+                    let mut line = String::new();
+                    while reader.read_line(&mut line).unwrap() > 2 {
+                        if let Some(etag) = line.to_lowercase().strip_prefix("if-none-match:") {
+                            if_none_match = Some(etag.trim().to_string());
+                        }
+                        line.clear();
+                    }
+                    let (version, text) = served.lock().unwrap().clone();
+                    let etag = format!("\"v{}\"", version);
+                    let response = if if_none_match.as_deref() == Some(etag.as_str()) {
+                        "HTTP/1.1 304 Not Modified\r\nConnection: close\r\n\r\n".to_string()
+                    } else {
+                        format!(
+                            "HTTP/1.1 200 OK\r\nETag: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                            etag,
+                            text.len(),
+                            text
+                        )
+                    };
+                    stream.write_all(response.as_bytes()).unwrap();
+                }
+            });
+            Self { url, list }
+        }
+
+        /// Publish a new version of the list
+        fn publish(&self, text: &str) {
+            let mut list = self.list.lock().unwrap();
+            *list = (list.0 + 1, text.to_string());
+        }
+    }
+
+    fn blocks(db: &Database) -> Vec<(&'static str, String)> {
+        let mut blocks: Vec<_> = db
+            .list_blocks()
+            .unwrap()
+            .into_iter()
+            .map(|block| (block.source.as_str(), block.entry))
+            .collect();
+        blocks.sort();
+        blocks
+    }
+
+    fn add_story(db: &Database, title: &str) -> i64 {
+        let mut unit = ContentUnit::new(
+            crate::Topic::AncientEgypt,
+            title.to_string(),
+            format!("The story of {}.", title),
+            String::new(),
+        );
+        db.insert_content(&mut unit).unwrap();
+        unit.id
+    }
+
+    #[tokio::test]
+    async fn syncing_adds_and_removes_remote_blocks_and_leaves_local_ones_alone() {
+        let db = Database::new_in_memory().unwrap();
+        add_story(&db, "Giza");
+        add_story(&db, "Karnak");
+        db.add_block("Karnak", BlockSource::Local).unwrap();
+        let remote = MockList::start("# Classroom list\nGiza\n\nKarnak\nregex:([\n");
+
+        let sync = sync_blocklist(&db, &remote.url).await.unwrap().unwrap();
+        assert_eq!((sync.added, sync.removed), (3, 0));
+        assert_eq!(sync.invalid, ["regex:(["]);
+        assert_eq!(db.get_content_count().unwrap(), 0);
+        // Unchanged since the last sync, so nothing is downloaded or changed
+        assert_eq!(sync_blocklist(&db, &remote.url).await.unwrap(), None);
+
+        remote.publish("Luxor\n");
+        let sync = sync_blocklist(&db, &remote.url).await.unwrap().unwrap();
+        assert_eq!((sync.added, sync.removed), (1, 3));
+        assert!(sync.invalid.is_empty());
+        assert_eq!(blocks(&db), [("local", "Karnak".to_string()), ("remote", "Luxor".to_string())]);
+        // Giza is back; Karnak stays blocked by its local entry
+        assert_eq!(db.get_content_count().unwrap(), 1);
+    }
+
+    #[test]
+    fn blocklists_skip_comments_blank_lines_and_repeats() {
+        let hash = "A".repeat(64);
+        let text = format!("  Giza \n# Karnak\n\nsha256:{}\n{}\nGiza\n", hash, hash.to_lowercase());
+        assert_eq!(parse_blocklist(&text), ["Giza".to_string(), hash.to_lowercase()]);
+    }
+
+    #[test]
+    fn invalid_regexes_are_set_aside_without_disabling_other_blocks() {
+        let blocklist = ContentBlocklist::new(["regex:^Siege", "regex:(", "Giza"]);
+        assert_eq!(blocklist.invalid(), ["regex:("]);
+        assert!(blocklist.is_blocked("Siege of Paris", None));
+        assert!(blocklist.is_blocked("Pyramids of Giza", None));
+        assert!(!blocklist.is_blocked("Karnak", None));
+    }
+}
@@ -0,0 +1,223 @@
+// packs.rs - Curated content packs
+// A pack is a small JSON manifest describing a themed set of content
+// (an "Ancient Rome pack", say) whose JSON Lines content is either embedded
+// in the manifest or stored next to it. Installing checks the content against
+// the manifest's checksum and then goes through the regular import path.
+
+use crate::{
+    import::{import_content, ImportOptions, ImportReport},
+    sha256_hex, Database, Result,
+};
+use serde::Deserialize;
+use std::path::Path;
+
+/// A pack manifest, as published alongside (or wrapping) the pack's content
+#[derive(Debug, Clone, Deserialize)]
+pub struct PackManifest {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub version: String,
+    /// SHA-256 of the content JSON Lines as hex, optionally prefixed with `sha256:`
+    pub checksum: String,
+    /// Where the content lives; relative paths are resolved against the manifest's location
+    #[serde(default)]
+    pub content_url: Option<String>,
+    /// The content itself, as JSON Lines text
+    #[serde(default)]
+    pub content: Option<String>,
+}
+
+/// A pack whose content has been downloaded and matched its checksum
+#[derive(Debug, Clone)]
+pub struct FetchedPack {
+    /// Path or URL of the manifest
+    pub source: String,
+    pub manifest: PackManifest,
+    /// Verified SHA-256 of `content`, as lowercase hex
+    pub checksum: String,
+    pub content: Vec<u8>,
+}
+
+/// Read a manifest and its content from a path or http(s) URL, verifying the checksum
+pub async fn fetch_pack(source: &str) -> Result<FetchedPack> {
+    let manifest_bytes = read_location(source).await?;
+    let manifest: PackManifest = serde_json::from_slice(&manifest_bytes)
+        .map_err(|e| anyhow::anyhow!("Invalid pack manifest {}: {}", source, e))?;
+
+    let content = match (&manifest.content, &manifest.content_url) {
+        (Some(content), None) => content.clone().into_bytes(),
+        (None, Some(url)) => read_location(&resolve_location(source, url)?).await?,
+        _ => anyhow::bail!(
+            "Pack manifest {} must have exactly one of `content` or `content_url`",
+            source
+        ),
+    };
+
+    let expected = manifest
+        .checksum
+        .trim()
+        .trim_start_matches("sha256:")
+        .to_lowercase();
+    let checksum = sha256_hex(&content);
+    if expected != checksum {
+        anyhow::bail!(
+            "Checksum mismatch for pack '{}': manifest says {}, content is {}",
+            manifest.name,
+            expected,
+            checksum
+        );
+    }
+
+    Ok(FetchedPack {
+        source: source.to_string(),
+        manifest,
+        checksum,
+        content,
+    })
+}
+
+/// Record a fetched pack and import its content, skipping titles already in the library
+/// If the import fails part-way, the pack and whatever it imported so far are removed again
+pub fn install_pack(db: &Database, pack: &FetchedPack, strict: bool) -> Result<ImportReport> {
+    let manifest = &pack.manifest;
+    let record = db.add_pack(
+        &manifest.name,
+        &manifest.description,
+        &manifest.version,
+        &pack.source,
+        &pack.checksum,
+    )?;
+
+    let options = ImportOptions {
+        strict,
+        skip_duplicates: true,
+        pack_id: Some(record.id),
+    };
+    match import_content(db, pack.content.as_slice(), &options) {
+        Ok(report) => Ok(report),
+        Err(e) => {
+            db.remove_pack(&record.name)?;
+            Err(e)
+        }
+    }
+}
+
+fn is_url(location: &str) -> bool {
+    location.starts_with("http://") || location.starts_with("https://")
+}
+
+/// Resolve `location` relative to the manifest at `base`
+fn resolve_location(base: &str, location: &str) -> Result<String> {
+    if is_url(location) {
+        Ok(location.to_string())
+    } else if is_url(base) {
+        Ok(reqwest::Url::parse(base)?.join(location)?.to_string())
+    } else {
+        let dir = Path::new(base).parent().unwrap_or(Path::new(""));
+        Ok(dir.join(location).to_string_lossy().into_owned())
+    }
+}
+
+/// Read a local file or download a URL
+async fn read_location(location: &str) -> Result<Vec<u8>> {
+    if is_url(location) {
+        let response = reqwest::get(location).await?.error_for_status()?;
+        Ok(response.bytes().await?.to_vec())
+    } else {
+        std::fs::read(location).map_err(|e| anyhow::anyhow!("Can't read {}: {}", location, e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ContentUnit, Topic, UserInteraction};
+
+    /// JSON Lines content for a pack, with enough words per story to be imported
+    fn pack_content() -> String {
+        ["The Forum", "The Colosseum"]
+            .iter()
+            .map(|title| {
+                let content = format!("{} was at the heart of Roman life for centuries. ", title).repeat(5);
+                serde_json::json!({ "topic": "AncientRome", "title": title, "content": content.trim() }).to_string()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// A manifest for `content` whose checksum says `checksum`
+    fn manifest(content: serde_json::Value, checksum: &str) -> String {
+        let mut manifest = serde_json::json!({
+            "name": "Ancient Rome",
+            "description": "Stories from the city",
+            "version": "1.0",
+            "checksum": checksum,
+        });
+        manifest.as_object_mut().unwrap().extend(content.as_object().unwrap().clone());
+        manifest.to_string()
+    }
+
+    /// A fresh directory holding a manifest with its content in a file next to it
+    fn fixture_pack(name: &str, checksum: Option<&str>) -> (std::path::PathBuf, String) {
+        let dir = std::env::temp_dir().join(format!("tellme-pack-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let content = pack_content();
+        let checksum = checksum.map_or_else(|| format!("sha256:{}", sha256_hex(content.as_bytes())), str::to_string);
+        std::fs::write(dir.join("rome.jsonl"), &content).unwrap();
+        let manifest_path = dir.join("rome.json");
+        std::fs::write(&manifest_path, manifest(serde_json::json!({ "content_url": "rome.jsonl" }), &checksum)).unwrap();
+        let manifest_path = manifest_path.to_string_lossy().into_owned();
+        (dir, manifest_path)
+    }
+
+    fn fetch(source: &str) -> Result<FetchedPack> {
+        tokio::runtime::Runtime::new().unwrap().block_on(fetch_pack(source))
+    }
+
+    #[test]
+    fn a_local_pack_installs_and_removes_cleanly() {
+        let (dir, manifest_path) = fixture_pack("install", None);
+        let db = Database::new_in_memory().unwrap();
+        let mut own = ContentUnit::new(Topic::AncientEgypt, "Giza".to_string(), "The pyramids.".to_string(), String::new());
+        db.insert_content(&mut own).unwrap();
+
+        let report = install_pack(&db, &fetch(&manifest_path).unwrap(), false).unwrap();
+        assert_eq!(report.imported, 2);
+        let packs = db.list_packs().unwrap();
+        assert_eq!((packs.len(), packs[0].content_count), (1, 2));
+        assert!(install_pack(&db, &fetch(&manifest_path).unwrap(), false).is_err(), "installed twice");
+
+        let forum = db.search_content("Forum", 1).unwrap()[0].id;
+        db.record_interaction(&UserInteraction::fully_read(forum, 60)).unwrap();
+        assert_eq!(db.remove_pack("Ancient Rome").unwrap(), Some(2));
+        assert!(db.list_packs().unwrap().is_empty());
+        assert_eq!(db.get_stored_content_count().unwrap(), 1, "only the pack's own content goes");
+        assert_eq!(db.count_orphaned_interactions().unwrap(), 0);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn a_pack_whose_content_fails_its_checksum_is_rejected() {
+        let (dir, manifest_path) = fixture_pack("checksum", Some("sha256:0000"));
+        let err = fetch(&manifest_path).unwrap_err();
+        assert!(err.to_string().contains("Checksum mismatch"), "{err}");
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn embedded_content_needs_no_second_file() {
+        let content = pack_content();
+        let dir = std::env::temp_dir().join(format!("tellme-pack-embedded-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("rome.json");
+        let checksum = sha256_hex(content.as_bytes());
+        std::fs::write(&path, manifest(serde_json::json!({ "content": content }), &checksum)).unwrap();
+
+        let pack = fetch(&path.to_string_lossy()).unwrap();
+        assert_eq!(pack.content, content.as_bytes());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,286 @@
+// pending.rs - On-disk queue for interactions that couldn't be saved
+// When the database is locked or the disk is full, interactions are appended
+// here as JSON Lines instead of being lost, and written to the database the
+// next time it accepts writes. In aggregate privacy mode only the per-topic
+// count is queued, never which story was read or when.
+
+use crate::{
+    config::PrivacyMode,
+    database::{ContentNotFound, Profile, TopicCounter},
+    Database, Result, Topic, UserInteraction, PENDING_FILE,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::OpenOptions,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+/// Most interactions kept in the queue; the oldest are dropped beyond this
+pub const MAX_PENDING_INTERACTIONS: usize = 10_000;
+
+/// An interaction waiting to be written, with the profile it belongs to
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueuedInteraction {
+    pub profile: String,
+    /// Row id of the profile, so a profile created later under the same name doesn't
+    /// inherit the entry; entries queued by older builds don't have it
+    #[serde(default)]
+    pub profile_id: Option<i64>,
+    #[serde(flatten)]
+    pub record: QueuedRecord,
+}
+
+/// What a queued entry writes, depending on the privacy mode it was queued in
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QueuedRecord {
+    /// The whole interaction, in full privacy mode
+    Interaction(UserInteraction),
+    /// Only the per-topic counter to add to, in aggregate privacy mode
+    TopicCount { topic: Topic, counter: TopicCounter },
+}
+
+/// JSON Lines file holding interactions that failed to save
+#[derive(Debug, Clone)]
+pub struct PendingQueue {
+    path: PathBuf,
+}
+
+impl Default for PendingQueue {
+    fn default() -> Self {
+        Self::new(PENDING_FILE)
+    }
+}
+
+impl PendingQueue {
+    /// Use the queue file at `path`
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Whether anything is waiting to be written
+    pub fn has_pending(&self) -> bool {
+        std::fs::metadata(&self.path).is_ok_and(|m| m.len() > 0)
+    }
+
+    /// Add an entry for `profile` to the queue, dropping the oldest entries if it is full
+    pub fn push(&self, profile: &Profile, record: QueuedRecord) -> Result<()> {
+        let line = serde_json::to_string(&QueuedInteraction {
+            profile: profile.name.clone(),
+            profile_id: Some(profile.id),
+            record,
+        })?;
+
+        let existing = self.read_lines()?;
+        if existing.len() >= MAX_PENDING_INTERACTIONS {
+            // Rewrite with room for the new entry, keeping the most recent ones
+            let keep = &existing[existing.len() + 1 - MAX_PENDING_INTERACTIONS..];
+            let mut text = keep.join("\n");
+            text.push('\n');
+            text.push_str(&line);
+            text.push('\n');
+            std::fs::write(&self.path, text)?;
+            return Ok(());
+        }
+
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        writeln!(file, "{}", line)?;
+        Ok(())
+    }
+
+    /// Write every queued interaction to the database in one transaction, then empty the queue
+    /// Unreadable lines are skipped. Returns the number of interactions recorded.
+    pub fn drain_into(&self, db: &Database) -> Result<usize> {
+        let queued: Vec<QueuedInteraction> = self
+            .read_lines()?
+            .iter()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect();
+        if queued.is_empty() {
+            self.clear()?;
+            return Ok(0);
+        }
+
+        let recorded = db.record_queued_interactions(&queued)?;
+        self.clear()?;
+        Ok(recorded)
+    }
+
+    /// Record an interaction, queueing it here if the database write fails
+    /// A successful write also drains anything queued earlier; if that fails the queue is
+    /// kept for the next write. In aggregate privacy mode only the topic count is queued,
+    /// and the content is kept out of the rest of the session as if it had been saved.
+    /// Returns true if the interaction reached the database, false if it was queued
+    /// instead; an error means it couldn't be queued either, or that the content doesn't exist.
+    pub fn record_or_queue(&self, db: &Database, interaction: &UserInteraction) -> Result<bool> {
+        match db.record_interaction(interaction) {
+            Ok(()) => {}
+            // Retrying later won't make unknown content appear
+            Err(e) if e.is::<ContentNotFound>() => return Err(e),
+            Err(_) => {
+                let record = match db.privacy() {
+                    PrivacyMode::Full => QueuedRecord::Interaction(interaction.clone()),
+                    PrivacyMode::Aggregate => {
+                        db.suppress_for_session(&[interaction.content_id()]);
+                        QueuedRecord::TopicCount {
+                            topic: db.topic_of(interaction.content_id())?,
+                            counter: TopicCounter::of(interaction),
+                        }
+                    }
+                };
+                self.push(db.profile(), record)?;
+                return Ok(false);
+            }
+        }
+
+        // The interaction itself is saved, so a failed drain only delays the rest
+        if self.has_pending() {
+            let _ = self.drain_into(db);
+        }
+        Ok(true)
+    }
+
+    /// Non-empty lines currently in the queue file
+    fn read_lines(&self) -> Result<Vec<String>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+        let text = std::fs::read_to_string(&self.path)?;
+        Ok(text
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| line.to_string())
+            .collect())
+    }
+
+    /// Empty the queue
+    fn clear(&self) -> Result<()> {
+        if self.path.exists() {
+            std::fs::remove_file(&self.path)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ContentUnit, Topic};
+
+    /// An empty scratch directory for one test
+    fn scratch(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("tellme-pending-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn add_story(db: &Database) -> i64 {
+        let mut unit = ContentUnit::new(Topic::AncientEgypt, "Giza".to_string(), "The pyramids.".to_string(), String::new());
+        db.insert_content(&mut unit).unwrap();
+        unit.id
+    }
+
+    #[test]
+    fn failed_writes_are_queued_and_drained_on_reopen() {
+        let dir = scratch("reopen");
+        let db_path = dir.join("tellme.db");
+        let queue = PendingQueue::new(dir.join("pending.jsonl"));
+
+        let db = Database::new(db_path.to_str().unwrap()).unwrap();
+        let id = add_story(&db);
+        db.set_read_only(true);
+        assert!(!queue.record_or_queue(&db, &UserInteraction::fully_read(id, 60)).unwrap());
+        assert!(!queue.record_or_queue(&db, &UserInteraction::skipped(id, 5)).unwrap());
+        assert!(queue.has_pending());
+        drop(db);
+
+        let db = Database::new(db_path.to_str().unwrap()).unwrap();
+        assert_eq!(queue.drain_into(&db).unwrap(), 2);
+        assert!(!queue.has_pending());
+        assert_eq!(db.get_monthly_summary().unwrap()[0].fully_read, 1);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn a_successful_write_drains_the_queue() {
+        let dir = scratch("drain");
+        let queue = PendingQueue::new(dir.join("pending.jsonl"));
+        let db = Database::new_in_memory().unwrap();
+        let id = add_story(&db);
+
+        queue.push(db.profile(), QueuedRecord::Interaction(UserInteraction::skipped(id, 5))).unwrap();
+        assert!(queue.record_or_queue(&db, &UserInteraction::fully_read(id, 60)).unwrap());
+        assert!(!queue.has_pending());
+        let month = &db.get_monthly_summary().unwrap()[0];
+        assert_eq!((month.fully_read, month.skipped), (1, 1));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn a_failed_drain_does_not_fail_the_write_that_succeeded() {
+        // A directory where the queue file should be can be seen but not read
+        let dir = scratch("unreadable");
+        let queue = PendingQueue::new(&dir);
+        let db = Database::new_in_memory().unwrap();
+        let id = add_story(&db);
+
+        assert!(queue.has_pending());
+        assert!(queue.record_or_queue(&db, &UserInteraction::fully_read(id, 60)).unwrap());
+        assert_eq!(db.get_monthly_summary().unwrap()[0].fully_read, 1);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn aggregate_mode_queues_only_the_topic_count() {
+        let dir = scratch("aggregate");
+        let db_path = dir.join("tellme.db");
+        let queue = PendingQueue::new(dir.join("pending.jsonl"));
+
+        let db = Database::new_with_privacy(db_path.to_str().unwrap(), PrivacyMode::Aggregate).unwrap();
+        let id = add_story(&db);
+        db.set_read_only(true);
+        assert!(!queue.record_or_queue(&db, &UserInteraction::fully_read(id, 60)).unwrap());
+        // Kept out of the session as a saved interaction would be
+        assert!(db.get_weighted_random_content().unwrap().is_none());
+        drop(db);
+
+        let queued = std::fs::read_to_string(dir.join("pending.jsonl")).unwrap();
+        assert!(!queued.contains("content_id") && !queued.contains("timestamp"), "{}", queued);
+
+        let db = Database::new(db_path.to_str().unwrap()).unwrap();
+        assert_eq!(queue.drain_into(&db).unwrap(), 1);
+        assert_eq!(db.get_interaction_counts_by_type().unwrap(), (1, 0));
+        assert!(db.get_monthly_summary().unwrap().is_empty());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn entries_are_only_replayed_for_the_profile_they_were_queued_under() {
+        let dir = scratch("profiles");
+        let queue = PendingQueue::new(dir.join("pending.jsonl"));
+        let db = Database::new_in_memory().unwrap();
+        let id = add_story(&db);
+        let sam = db.create_profile("sam").unwrap();
+
+        queue.push(&sam, QueuedRecord::Interaction(UserInteraction::fully_read(id, 60))).unwrap();
+        // Entries from before profile ids were queued go by name alone
+        let old = format!(
+            "{{\"profile\":\"sam\",\"interaction\":{}}}\n",
+            serde_json::to_string(&UserInteraction::skipped(id, 5)).unwrap()
+        );
+        let mut file = OpenOptions::new().append(true).open(dir.join("pending.jsonl")).unwrap();
+        file.write_all(old.as_bytes()).unwrap();
+
+        // A new sam isn't the sam the read was queued for
+        db.delete_profile("sam").unwrap();
+        db.create_profile("sam").unwrap();
+        assert_eq!(queue.drain_into(&db).unwrap(), 1);
+        let db = db.with_profile("sam").unwrap();
+        assert_eq!(db.get_interaction_counts_by_type().unwrap(), (0, 1));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,616 @@
+// selection.rs - Topic scoring for the recommender
+// No database access here: `Database` gathers the inputs and every frontend
+// picks through it, so the weighting lives in one place and can be inspected
+// (see `tellme recommender-state`). How the topic is then chosen is up to a
+// `SelectionStrategy`, picked in the configuration.
+
+use crate::{
+    config::{RecommendationConfig, StrategyKind, DEFAULT_EXPLORE_EXPLOIT_BALANCE},
+    Topic,
+};
+use rand::Rng;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+
+/// Libraries with selectable content in fewer topics than this get proportionally
+/// gentler recency penalties, so the few topics there are don't lock each other out
+const SMALL_LIBRARY_TOPICS: usize = 4;
+
+/// Share of a topic's score kept after it was shown, by how many picks ago (last first)
+const RECENCY_PENALTIES: [f64; 5] = [0.1, 0.3, 0.6, 0.8, 0.9];
+
+/// Everything topic scoring looks at
+#[derive(Debug, Clone, Copy)]
+pub struct SelectionInputs<'a> {
+    /// Share of interactions that were full reads, per topic with any history
+    pub preferences: &'a HashMap<Topic, f64>,
+    pub interaction_counts: &'a HashMap<Topic, i64>,
+    /// Topics of the last stories shown, most recent first
+    pub recent_topics: &'a [Topic],
+    /// Topics with something left to show; nothing else is scored
+    pub selectable: &'a HashSet<Topic>,
+    pub settings: &'a RecommendationConfig,
+    /// Explore/exploit balance in effect, which can differ from `settings` during a session
+    pub balance: f64,
+}
+
+/// How a topic's weight came about
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Explanation {
+    /// Preference the score started from (`default_preference` without history)
+    pub preference: f64,
+    /// Score after the balance evened out preferences
+    pub base: f64,
+    /// Share of the score kept after recency penalties (1.0 = not shown recently)
+    pub recency_factor: f64,
+    /// Added for topics with few interactions
+    pub exploration_bonus: f64,
+    /// Whether `min_topic_weight` raised the score
+    pub floored: bool,
+}
+
+/// Weight of every selectable topic, in `Topic::all()` order, with how it was reached
+/// At a balance of 0 only the best-liked topic is returned, however recently it was shown
+pub fn score_topics(inputs: &SelectionInputs) -> Vec<(Topic, f64, Explanation)> {
+    let settings = inputs.settings;
+
+    // In a small library the recency penalties are scaled down, down to none at all
+    // when a single topic is left, so repeating a topic beats finding nothing
+    let diversity = (inputs.selectable.len().saturating_sub(1) as f64
+        / (SMALL_LIBRARY_TOPICS - 1) as f64)
+        .min(1.0);
+
+    // The explore/exploit balance scales penalties and bonuses from none (0.0) through
+    // as designed (0.5) to double (1.0); above 0.5 it also evens out preferences
+    let strength = inputs.balance / DEFAULT_EXPLORE_EXPLOIT_BALANCE;
+    let flattening = (strength - 1.0).max(0.0);
+
+    let bases = Topic::all()
+        .iter()
+        .filter(|topic| inputs.selectable.contains(topic))
+        .map(|&topic| {
+            let preference = inputs
+                .preferences
+                .get(&topic)
+                .copied()
+                .unwrap_or(settings.default_preference);
+            (topic, preference, preference + (1.0 - preference) * flattening)
+        });
+
+    // Pure exploitation: only the best-liked topic
+    if inputs.balance <= 0.0 {
+        return bases
+            .max_by(|a, b| a.2.total_cmp(&b.2))
+            .map(|(topic, preference, base)| {
+                let explanation = Explanation {
+                    preference,
+                    base,
+                    recency_factor: 1.0,
+                    exploration_bonus: 0.0,
+                    floored: false,
+                };
+                (topic, base, explanation)
+            })
+            .into_iter()
+            .collect();
+    }
+
+    bases
+        .map(|(topic, preference, base)| {
+            // Heavier penalties the more recently the topic was shown
+            let recency_factor = inputs
+                .recent_topics
+                .iter()
+                .zip(RECENCY_PENALTIES)
+                .filter(|(recent, _)| **recent == topic)
+                .map(|(_, kept)| (1.0 - (1.0 - kept) * diversity * strength).max(0.0))
+                .product::<f64>();
+
+            // Exploration bonus for topics with few interactions
+            let interactions = inputs.interaction_counts.get(&topic).copied().unwrap_or(0);
+            let exploration_bonus = if interactions < i64::from(settings.exploration_threshold) {
+                settings.exploration_bonus * strength
+            } else {
+                0.0
+            };
+
+            // Ensure minimum score for variety
+            let score = base * recency_factor + exploration_bonus;
+            let floored = score < settings.min_topic_weight;
+            let explanation = Explanation {
+                preference,
+                base,
+                recency_factor,
+                exploration_bonus,
+                floored,
+            };
+            (topic, score.max(settings.min_topic_weight), explanation)
+        })
+        .collect()
+}
+
+/// Draw a topic with probability proportional to its weight
+/// `None` when there's nothing to draw from; zero weights are only picked if all are zero
+pub fn pick_topic(scores: &[(Topic, f64, Explanation)], rng: &mut impl Rng) -> Option<Topic> {
+    let total: f64 = scores.iter().map(|(_, weight, _)| weight).sum();
+    if total <= 0.0 {
+        return scores.get(rng.gen_range(0..scores.len().max(1))).map(|(topic, _, _)| *topic);
+    }
+
+    let mut point = rng.gen::<f64>() * total;
+    for (topic, weight, _) in scores {
+        point -= weight;
+        if point <= 0.0 {
+            return Some(*topic);
+        }
+    }
+    // Rounding can leave a sliver past the last weight
+    scores.iter().rev().find(|(_, weight, _)| *weight > 0.0).map(|(topic, _, _)| *topic)
+}
+
+/// What a selection strategy chooses from
+pub struct SelectionContext<'a> {
+    pub inputs: SelectionInputs<'a>,
+    pub rng: &'a mut dyn rand::RngCore,
+}
+
+/// A way of choosing the topic of the next story
+/// Only topics in `inputs.selectable` have anything to show; `None` means none of them
+/// should be picked. `Database` asks again without a topic that turned out to be empty
+pub trait SelectionStrategy: Send {
+    fn name(&self) -> &'static str;
+    fn choose(&mut self, ctx: &mut SelectionContext) -> Option<Topic>;
+}
+
+/// The strategy for a configured kind
+pub fn strategy(kind: StrategyKind) -> Box<dyn SelectionStrategy> {
+    match kind {
+        StrategyKind::Diversity => Box::new(DiversityStrategy),
+        StrategyKind::Random => Box::new(RandomStrategy),
+        StrategyKind::RoundRobin => Box::new(RoundRobinStrategy),
+    }
+}
+
+/// Weighted draw from `score_topics`: preferences, recency penalties and exploration,
+/// except that `exploration_rate` of the time any selectable topic is equally likely
+pub struct DiversityStrategy;
+
+impl SelectionStrategy for DiversityStrategy {
+    fn name(&self) -> &'static str {
+        StrategyKind::Diversity.as_str()
+    }
+
+    fn choose(&mut self, ctx: &mut SelectionContext) -> Option<Topic> {
+        let rate = exploration_rate(ctx.inputs.settings);
+        if rate > 0.0 && ctx.rng.gen_bool(rate) {
+            return RandomStrategy.choose(ctx);
+        }
+        pick_topic(&score_topics(&ctx.inputs), &mut ctx.rng)
+    }
+}
+
+/// Share of diversity picks made with every selectable topic equally likely
+fn exploration_rate(settings: &RecommendationConfig) -> f64 {
+    settings.exploration_rate.clamp(0.0, 1.0)
+}
+
+/// Chance of `DiversityStrategy` choosing each selectable topic, in `Topic::all()` order:
+/// `(1 - rate) * weight / total + rate / selectable`, from `scores` as `score_topics`
+/// gave them and `exploration_rate` as the rate
+pub fn diversity_chances(inputs: &SelectionInputs, scores: &[(Topic, f64, Explanation)]) -> Vec<(Topic, f64)> {
+    let rate = exploration_rate(inputs.settings);
+    let total: f64 = scores.iter().map(|(_, weight, _)| weight).sum();
+    let selectable = inputs.selectable.len() as f64;
+    selectable_in_order(inputs)
+        .map(|topic| {
+            // `pick_topic` treats all-zero weights as equal ones
+            let drawn = match scores.iter().find(|(scored, _, _)| *scored == topic) {
+                Some((_, weight, _)) if total > 0.0 => weight / total,
+                Some(_) => 1.0 / scores.len() as f64,
+                None => 0.0,
+            };
+            (topic, (1.0 - rate) * drawn + rate / selectable)
+        })
+        .collect()
+}
+
+/// Any selectable topic, all equally likely, whatever was read before
+pub struct RandomStrategy;
+
+impl SelectionStrategy for RandomStrategy {
+    fn name(&self) -> &'static str {
+        StrategyKind::Random.as_str()
+    }
+
+    fn choose(&mut self, ctx: &mut SelectionContext) -> Option<Topic> {
+        let topics: Vec<Topic> = selectable_in_order(&ctx.inputs).collect();
+        topics.get(ctx.rng.gen_range(0..topics.len().max(1))).copied()
+    }
+}
+
+/// The selectable topic after the last one shown, in `Topic::all()` order, wrapping
+/// around; the first topic when nothing was shown yet
+pub struct RoundRobinStrategy;
+
+impl SelectionStrategy for RoundRobinStrategy {
+    fn name(&self) -> &'static str {
+        StrategyKind::RoundRobin.as_str()
+    }
+
+    fn choose(&mut self, ctx: &mut SelectionContext) -> Option<Topic> {
+        let rank = |topic: Topic| Topic::all().iter().position(|t| *t == topic);
+        let last = ctx.inputs.recent_topics.first().and_then(|&topic| rank(topic));
+        let mut topics = selectable_in_order(&ctx.inputs).peekable();
+        let first = topics.peek().copied();
+        topics.find(|&topic| rank(topic) > last).or(first)
+    }
+}
+
+/// Selectable topics in `Topic::all()` order, so choices don't depend on hashing
+fn selectable_in_order<'a>(inputs: &'a SelectionInputs) -> impl Iterator<Item = Topic> + 'a {
+    Topic::all().iter().copied().filter(|topic| inputs.selectable.contains(topic))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    /// Picks drawn when measuring how often a topic comes up
+    const DRAWS: usize = 10_000;
+
+    /// Owned data behind a `SelectionInputs`
+    struct Fixture {
+        preferences: HashMap<Topic, f64>,
+        counts: HashMap<Topic, i64>,
+        recent: Vec<Topic>,
+        selectable: HashSet<Topic>,
+        settings: RecommendationConfig,
+        balance: f64,
+    }
+
+    impl Fixture {
+        /// `topics` selectable, without any history, under the default settings
+        fn new(topics: &[Topic]) -> Self {
+            Self {
+                preferences: HashMap::new(),
+                counts: HashMap::new(),
+                recent: Vec::new(),
+                selectable: topics.iter().copied().collect(),
+                settings: RecommendationConfig::default(),
+                balance: DEFAULT_EXPLORE_EXPLOIT_BALANCE,
+            }
+        }
+
+        /// Give `topic` a history of `interactions` with this share of full reads
+        fn history(mut self, topic: Topic, preference: f64, interactions: i64) -> Self {
+            self.preferences.insert(topic, preference);
+            self.counts.insert(topic, interactions);
+            self
+        }
+
+        fn inputs(&self) -> SelectionInputs<'_> {
+            SelectionInputs {
+                preferences: &self.preferences,
+                interaction_counts: &self.counts,
+                recent_topics: &self.recent,
+                selectable: &self.selectable,
+                settings: &self.settings,
+                balance: self.balance,
+            }
+        }
+
+        /// Share of `DRAWS` picks by `strategy` that land on `topic`, from a fixed seed
+        fn share(&self, strategy: &mut dyn SelectionStrategy, topic: Topic) -> f64 {
+            let mut rng = StdRng::seed_from_u64(42);
+            let hits = (0..DRAWS)
+                .filter(|_| {
+                    let mut ctx = SelectionContext { inputs: self.inputs(), rng: &mut rng };
+                    strategy.choose(&mut ctx) == Some(topic)
+                })
+                .count();
+            hits as f64 / DRAWS as f64
+        }
+    }
+
+    #[test]
+    fn a_higher_exploration_bonus_favours_little_read_topics() {
+        let mut fixture = Fixture::new(&[Topic::AncientEgypt, Topic::AncientRome])
+            .history(Topic::AncientEgypt, 0.5, 20);
+        fixture.settings.exploration_rate = 0.0;
+
+        fixture.settings.exploration_bonus = 0.0;
+        let without = fixture.share(&mut DiversityStrategy, Topic::AncientRome);
+        fixture.settings.exploration_bonus = 0.6;
+        let with = fixture.share(&mut DiversityStrategy, Topic::AncientRome);
+
+        // 0.3 / 0.8 of the weight without a bonus, 0.9 / 1.4 with it
+        assert!((without - 0.375).abs() < 0.03, "share without a bonus: {without}");
+        assert!((with - 0.643).abs() < 0.03, "share with a bonus: {with}");
+    }
+
+    /// Topics and weights scored for `fixture`
+    fn weights(fixture: &Fixture) -> Vec<(Topic, f64)> {
+        score_topics(&fixture.inputs()).into_iter().map(|(topic, weight, _)| (topic, weight)).collect()
+    }
+
+    #[test]
+    fn a_balance_of_zero_serves_the_favourite_even_right_after_it() {
+        let mut fixture = Fixture::new(&[Topic::AncientEgypt, Topic::AncientRome, Topic::Viking])
+            .history(Topic::AncientEgypt, 0.9, 20)
+            .history(Topic::AncientRome, 0.4, 20);
+        fixture.recent = vec![Topic::AncientEgypt, Topic::AncientEgypt];
+        fixture.balance = 0.0;
+
+        assert_eq!(weights(&fixture), vec![(Topic::AncientEgypt, 0.9)]);
+        fixture.settings.exploration_rate = 0.0;
+        assert_eq!(fixture.share(&mut DiversityStrategy, Topic::AncientEgypt), 1.0);
+    }
+
+    #[test]
+    fn at_a_balance_of_zero_the_exploration_rate_still_reaches_every_topic() {
+        let mut fixture = Fixture::new(&[Topic::AncientEgypt, Topic::AncientRome, Topic::Viking])
+            .history(Topic::AncientEgypt, 0.9, 20)
+            .history(Topic::AncientRome, 0.4, 20);
+        fixture.balance = 0.0;
+
+        // 85% from the favourite alone, 15% spread over all three topics
+        let chances = diversity_chances(&fixture.inputs(), &score_topics(&fixture.inputs()));
+        let expected = [(Topic::AncientEgypt, 0.9), (Topic::AncientRome, 0.05), (Topic::Viking, 0.05)];
+        for ((topic, chance), (expected_topic, expected)) in chances.into_iter().zip(expected) {
+            assert_eq!(topic, expected_topic);
+            assert!((chance - expected).abs() < 1e-9, "{topic}: {chance} instead of {expected}");
+            let share = fixture.share(&mut DiversityStrategy, topic);
+            assert!((share - expected).abs() < 0.02, "{topic}: drawn {share} of the time instead of {expected}");
+        }
+
+        fixture.settings.exploration_rate = 0.0;
+        let chances = diversity_chances(&fixture.inputs(), &score_topics(&fixture.inputs()));
+        assert_eq!(chances[0], (Topic::AncientEgypt, 1.0));
+        fixture.settings.exploration_rate = 1.0;
+        let chances = diversity_chances(&fixture.inputs(), &score_topics(&fixture.inputs()));
+        assert!(chances.iter().all(|(_, chance)| (chance - 1.0 / 3.0).abs() < 1e-9), "{chances:?}");
+    }
+
+    #[test]
+    fn a_balance_of_one_evens_out_preferences_and_doubles_the_penalties() {
+        let mut fixture = Fixture::new(&[Topic::AncientEgypt, Topic::AncientRome, Topic::Viking, Topic::Medieval])
+            .history(Topic::AncientEgypt, 0.9, 20)
+            .history(Topic::AncientRome, 0.1, 20)
+            .history(Topic::Viking, 0.5, 20)
+            .history(Topic::Medieval, 0.5, 20);
+        fixture.recent = vec![Topic::AncientEgypt, Topic::AncientRome, Topic::Viking];
+        fixture.balance = 1.0;
+
+        let scores = score_topics(&fixture.inputs());
+        assert!(scores.iter().all(|(_, _, explanation)| explanation.base == 1.0), "{scores:?}");
+        // Doubled penalties wipe out the last two topics shown and leave 20% of the third
+        let weight = |topic| scores.iter().find(|(t, _, _)| *t == topic).unwrap().1;
+        assert_eq!(weight(Topic::AncientEgypt), fixture.settings.min_topic_weight);
+        assert_eq!(weight(Topic::AncientRome), fixture.settings.min_topic_weight);
+        assert!((weight(Topic::Viking) - 0.2).abs() < 1e-9);
+        assert_eq!(weight(Topic::Medieval), 1.0);
+    }
+
+    #[test]
+    fn without_any_history_every_topic_weighs_the_same() {
+        let mut fixture = Fixture::new(Topic::all());
+        for balance in [0.5, 1.0] {
+            fixture.balance = balance;
+            let weights = weights(&fixture);
+            assert_eq!(weights.len(), Topic::all().len());
+            assert!(weights.iter().all(|(_, weight)| *weight == weights[0].1), "{weights:?}");
+        }
+        // Nothing stands out to exploit, but a topic is still chosen
+        fixture.balance = 0.0;
+        assert_eq!(weights(&fixture).len(), 1);
+
+        let empty = Fixture::new(&[]);
+        assert!(weights(&empty).is_empty());
+        assert_eq!(pick_topic(&score_topics(&empty.inputs()), &mut StdRng::seed_from_u64(1)), None);
+    }
+
+    /// The explanation `score_topics` gave `topic`
+    fn explanation(fixture: &Fixture, topic: Topic) -> Explanation {
+        score_topics(&fixture.inputs()).into_iter().find(|(t, _, _)| *t == topic).unwrap().2
+    }
+
+    #[test]
+    fn recency_penalties_fall_off_with_how_long_ago_a_topic_was_shown() {
+        let recent = [
+            Topic::AncientEgypt,
+            Topic::AncientGreece,
+            Topic::AncientRome,
+            Topic::AncientChina,
+            Topic::Byzantine,
+        ];
+        let mut fixture = Fixture::new(&[recent.as_slice(), &[Topic::Medieval]].concat());
+        for topic in recent.iter().copied().chain([Topic::Medieval]) {
+            fixture = fixture.history(topic, 0.5, 20);
+        }
+        fixture.recent = recent.to_vec();
+
+        for (&topic, kept) in recent.iter().zip(RECENCY_PENALTIES) {
+            assert!((explanation(&fixture, topic).recency_factor - kept).abs() < 1e-9, "{topic}");
+        }
+        assert_eq!(explanation(&fixture, Topic::Medieval).recency_factor, 1.0);
+        let expected = [0.05, 0.15, 0.3, 0.4, 0.45, 0.5];
+        for ((_, weight), expected) in weights(&fixture).into_iter().zip(expected) {
+            assert!((weight - expected).abs() < 1e-9, "{weight} instead of {expected}");
+        }
+    }
+
+    #[test]
+    fn small_libraries_soften_the_recency_penalties() {
+        let mut fixture = Fixture::new(&[Topic::AncientEgypt, Topic::AncientRome]);
+        fixture.recent = vec![Topic::AncientEgypt];
+        // One topic besides the last one shown: a third of the usual 90% off
+        assert!((explanation(&fixture, Topic::AncientEgypt).recency_factor - 0.7).abs() < 1e-9);
+
+        fixture.selectable = HashSet::from([Topic::AncientEgypt]);
+        assert_eq!(explanation(&fixture, Topic::AncientEgypt).recency_factor, 1.0);
+    }
+
+    #[test]
+    fn little_read_topics_get_a_bonus_and_no_topic_drops_below_the_floor() {
+        let fixture = Fixture::new(&[Topic::AncientEgypt, Topic::AncientRome, Topic::Viking])
+            .history(Topic::AncientEgypt, 0.5, 2)
+            .history(Topic::AncientRome, 0.5, 3)
+            .history(Topic::Viking, 0.0, 20);
+
+        let egypt = explanation(&fixture, Topic::AncientEgypt);
+        assert_eq!((egypt.preference, egypt.exploration_bonus), (0.5, 0.2));
+        assert_eq!(explanation(&fixture, Topic::AncientRome).exploration_bonus, 0.0);
+        let viking = explanation(&fixture, Topic::Viking);
+        assert!(viking.floored && viking.exploration_bonus == 0.0);
+        assert_eq!(
+            weights(&fixture),
+            vec![(Topic::AncientEgypt, 0.7), (Topic::AncientRome, 0.5), (Topic::Viking, 0.05)]
+        );
+        // Topics without history start from the default preference, and count as little read
+        let fresh = explanation(&Fixture::new(&[Topic::Mongol]), Topic::Mongol);
+        assert_eq!((fresh.preference, fresh.exploration_bonus), (0.3, 0.2));
+    }
+
+    #[test]
+    fn every_rule_together_gives_the_weights_worked_out_by_hand() {
+        let mut fixture = Fixture::new(&[
+            Topic::AncientEgypt,
+            Topic::AncientGreece,
+            Topic::AncientRome,
+            Topic::Medieval,
+            Topic::Viking,
+        ])
+        .history(Topic::AncientEgypt, 0.9, 20)
+        .history(Topic::AncientGreece, 0.2, 1)
+        .history(Topic::AncientRome, 0.5, 10)
+        .history(Topic::Medieval, 0.0, 10)
+        .history(Topic::Byzantine, 1.0, 20);
+        fixture.recent = vec![
+            Topic::Medieval,
+            Topic::AncientRome,
+            Topic::AncientEgypt,
+            Topic::Byzantine,
+            Topic::AncientRome,
+        ];
+        fixture.balance = 0.6;
+
+        // A balance of 0.6 scales penalties and bonuses by 1.2 and moves preferences a
+        // fifth of the way to 1. Rome keeps 16% and 88% after its two showings, the
+        // little-read Greece and Viking (no history at all) get 0.24 extra, and Medieval,
+        // wiped out by the last showing, is held at the floor. Byzantine has nothing left
+        // to show, so it isn't scored however much it is liked
+        let expected = [
+            (Topic::AncientEgypt, 0.92 * 0.52),
+            (Topic::AncientGreece, 0.36 + 0.24),
+            (Topic::AncientRome, 0.6 * 0.16 * 0.88),
+            (Topic::Medieval, 0.05),
+            (Topic::Viking, 0.44 + 0.24),
+        ];
+        let weights = weights(&fixture);
+        assert_eq!(weights.len(), expected.len(), "{weights:?}");
+        for ((topic, weight), (expected_topic, expected)) in weights.into_iter().zip(expected) {
+            assert_eq!(topic, expected_topic);
+            assert!((weight - expected).abs() < 1e-12, "{topic}: {weight} instead of {expected}");
+        }
+        assert!(explanation(&fixture, Topic::Medieval).floored);
+    }
+
+    /// Scores with the given weights, in order
+    fn scored(weights: &[(Topic, f64)]) -> Vec<(Topic, f64, Explanation)> {
+        let explanation = explanation(&Fixture::new(&[Topic::AncientEgypt]), Topic::AncientEgypt);
+        weights.iter().map(|&(topic, weight)| (topic, weight, explanation)).collect()
+    }
+
+    #[test]
+    fn picks_are_drawn_in_proportion_to_the_weights() {
+        let mut rng = StdRng::seed_from_u64(7);
+        assert_eq!(pick_topic(&[], &mut rng), None);
+        let never_zero = scored(&[(Topic::AncientEgypt, 0.0), (Topic::AncientRome, 1.0), (Topic::Viking, 0.0)]);
+        assert!((0..100).all(|_| pick_topic(&never_zero, &mut rng) == Some(Topic::AncientRome)));
+
+        let skewed = scored(&[(Topic::AncientEgypt, 1.0), (Topic::AncientRome, 3.0)]);
+        let egypt = (0..DRAWS).filter(|_| pick_topic(&skewed, &mut rng) == Some(Topic::AncientEgypt)).count();
+        assert!((egypt as f64 / DRAWS as f64 - 0.25).abs() < 0.02, "{egypt} of {DRAWS}");
+
+        // All weights zero: any of them, equally likely
+        let flat = scored(&[(Topic::AncientEgypt, 0.0), (Topic::AncientRome, 0.0)]);
+        let egypt = (0..DRAWS).filter(|_| pick_topic(&flat, &mut rng) == Some(Topic::AncientEgypt)).count();
+        assert!((egypt as f64 / DRAWS as f64 - 0.5).abs() < 0.02, "{egypt} of {DRAWS}");
+    }
+
+    /// Topics `strategy` picks in a row, each pick becoming the most recent topic
+    fn sequence(fixture: &mut Fixture, strategy: &mut dyn SelectionStrategy, picks: usize) -> Vec<Option<Topic>> {
+        let mut rng = StdRng::seed_from_u64(3);
+        (0..picks)
+            .map(|_| {
+                let topic = strategy.choose(&mut SelectionContext { inputs: fixture.inputs(), rng: &mut rng });
+                fixture.recent.splice(0..0, topic);
+                topic
+            })
+            .collect()
+    }
+
+    #[test]
+    fn round_robin_takes_selectable_topics_in_turn_and_wraps_around() {
+        let mut fixture = Fixture::new(&[Topic::Viking, Topic::AncientEgypt, Topic::Medieval]);
+        assert_eq!(
+            sequence(&mut fixture, &mut RoundRobinStrategy, 4),
+            [Topic::AncientEgypt, Topic::Medieval, Topic::Viking, Topic::AncientEgypt].map(Some)
+        );
+
+        // Carries on after the last topic shown even when that one has run out
+        fixture.recent = vec![Topic::AncientRome];
+        assert_eq!(sequence(&mut fixture, &mut RoundRobinStrategy, 1), [Some(Topic::Medieval)]);
+        fixture.selectable.clear();
+        assert_eq!(sequence(&mut fixture, &mut RoundRobinStrategy, 1), [None]);
+    }
+
+    #[test]
+    fn random_picks_ignore_history_and_stay_within_selectable_topics() {
+        let fixture = Fixture::new(&[Topic::AncientEgypt, Topic::AncientRome, Topic::Viking, Topic::Medieval])
+            .history(Topic::AncientEgypt, 1.0, 50)
+            .history(Topic::AncientRome, 0.0, 50);
+        for topic in [Topic::AncientEgypt, Topic::AncientRome, Topic::Viking, Topic::Medieval] {
+            let share = fixture.share(&mut RandomStrategy, topic);
+            assert!((share - 0.25).abs() < 0.02, "{topic}: {share}");
+        }
+        assert_eq!(fixture.share(&mut RandomStrategy, Topic::Mongol), 0.0);
+        assert_eq!(sequence(&mut Fixture::new(&[]), &mut RandomStrategy, 1), [None]);
+    }
+
+    #[test]
+    fn over_a_long_session_each_strategy_shows_its_own_pattern() {
+        let topics = [Topic::AncientEgypt, Topic::AncientRome, Topic::Viking, Topic::Medieval];
+        let session = |strategy: &mut dyn SelectionStrategy| {
+            let mut fixture = Fixture::new(&topics)
+                .history(Topic::AncientEgypt, 0.9, 20)
+                .history(Topic::AncientRome, 0.2, 20)
+                .history(Topic::Viking, 0.2, 20)
+                .history(Topic::Medieval, 0.2, 20);
+            let picks: Vec<Topic> = sequence(&mut fixture, strategy, 2000).into_iter().flatten().collect();
+            let share = |topic| picks.iter().filter(|pick| **pick == topic).count() as f64 / picks.len() as f64;
+            let repeats = picks.windows(2).filter(|pair| pair[0] == pair[1]).count() as f64 / picks.len() as f64;
+            (picks.len(), share(Topic::AncientEgypt), share(Topic::Viking), repeats)
+        };
+
+        // Diversity leans towards the favourite but rarely serves it twice running
+        let (picks, favourite, other, repeats) = session(&mut DiversityStrategy);
+        assert_eq!(picks, 2000);
+        assert!(favourite > 0.35 && favourite > 1.5 * other, "{favourite} vs {other}");
+        assert!(repeats < 0.15, "{repeats}");
+
+        // Random treats every topic alike, and repeats about one pick in four
+        let (_, favourite, other, repeats) = session(&mut RandomStrategy);
+        assert!((favourite - 0.25).abs() < 0.04 && (other - 0.25).abs() < 0.04, "{favourite}, {other}");
+        assert!((repeats - 0.25).abs() < 0.04, "{repeats}");
+
+        // Round robin is exactly even and never repeats
+        assert_eq!(session(&mut RoundRobinStrategy), (2000, 0.25, 0.25, 0.0));
+
+        // The same seed gives the same session
+        let mut first = Fixture::new(&topics);
+        let mut second = Fixture::new(&topics);
+        assert_eq!(sequence(&mut first, &mut DiversityStrategy, 50), sequence(&mut second, &mut DiversityStrategy, 50));
+    }
+}
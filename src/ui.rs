@@ -2,25 +2,280 @@
 // This module demonstrates ratatui usage, event handling,
 // and asynchronous programming patterns in Rust
 
-use crate::ContentUnit;
+use crate::{
+    auto_update::GITHUB_REPO,
+    config::{MacroKey, ReaderAction, RevealSpeed, StrategyKind, TextAlignment, UiConfig, MAX_TOPIC_SLOTS},
+    database::{ActivityCalendar, TopicTrend, TrendBucket},
+    content::{explainable_terms, format_year, sentence_ends},
+    fetch::FetchEvent,
+    ContentKind, ContentUnit, TermSummary, Topic,
+};
 use crossterm::{
-    event::{self, Event, KeyCode, KeyEventKind},
+    event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use ratatui::{
     backend::CrosstermBackend,
-    layout::{Alignment, Constraint, Direction, Layout},
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Paragraph, Wrap},
+    widgets::{
+        Block, Borders, Clear, Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState, Sparkline,
+        Wrap,
+    },
     Frame, Terminal,
 };
 use std::{
+    cell::Cell,
+    collections::VecDeque,
     io::{self, Stdout},
     time::{Duration, Instant},
 };
 
+/// Content shorter than this many words doesn't get a progress gauge
+const PROGRESS_MIN_WORDS: usize = 80;
+
+/// How far one press of [ or ] moves the explore/exploit balance
+const BALANCE_STEP: f64 = 0.1;
+
+/// How many recently shown titles are remembered for spotting repeated titles
+const RECENT_TITLES: usize = 5;
+
+/// Most stories kept for going back with Left
+const HISTORY_LIMIT: usize = 50;
+
+/// Chronological position before the first story, so the oldest dated one comes first
+const CHRONOLOGICAL_START: (i32, i64) = (i32::MIN, 0);
+
+/// How long a flashed status message stays visible
+const STATUS_FLASH_DURATION: Duration = Duration::from_secs(2);
+
+/// Widest and tallest the term explanation popup gets
+const EXPLAIN_POPUP_WIDTH: u16 = 72;
+const EXPLAIN_POPUP_HEIGHT: u16 = 18;
+
+/// Widest the topic picker popup gets
+const TOPIC_PICKER_WIDTH: u16 = 44;
+
+/// Widest the about box gets
+const ABOUT_WIDTH: u16 = 72;
+
+/// Smallest terminal the reader lays out in; anything smaller just asks for more room
+/// (the height is the margins and three bars around at least one line of content)
+const MIN_TERMINAL_WIDTH: u16 = 24;
+const MIN_TERMINAL_HEIGHT: u16 = 8;
+
+/// Where a term lookup ("explain this") stands
+#[derive(Debug, Clone)]
+pub enum ExplainState {
+    /// Picking one of the terms
+    Choosing,
+    /// Waiting for the explanation of this term
+    LookingUp(String),
+    Found(TermSummary),
+    /// Wikipedia has no article for this term
+    NotFound(String),
+}
+
+/// Popup listing the names found in the current content, and the explanation of one of them
+#[derive(Debug, Clone)]
+pub struct Explain {
+    pub terms: Vec<String>,
+    pub selected: usize,
+    pub state: ExplainState,
+}
+
+/// Search prompt over the library, with the matching stories so far
+#[derive(Debug, Clone, Default)]
+pub struct Search {
+    pub query: String,
+    /// Best match first
+    pub results: Vec<ContentUnit>,
+    pub selected: usize,
+}
+
+/// Which part of a focus cycle the timer is in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FocusPhase {
+    /// Reading; content is shown as usual
+    Focus,
+    /// Resting; content is paused behind the break screen
+    Break,
+}
+
+/// Pomodoro-style timer alternating focus sessions and breaks
+#[derive(Debug, Clone)]
+pub struct FocusTimer {
+    pub phase: FocusPhase,
+    /// When the current phase began
+    pub phase_started: Instant,
+    /// Focus sessions finished so far
+    pub completed: u32,
+}
+
+impl FocusTimer {
+    /// Start a focus session now
+    pub fn start() -> Self {
+        Self {
+            phase: FocusPhase::Focus,
+            phase_started: Instant::now(),
+            completed: 0,
+        }
+    }
+
+    /// Time left in the current phase, given the phase lengths
+    pub fn remaining(&self, focus: Duration, rest: Duration) -> Duration {
+        let length = match self.phase {
+            FocusPhase::Focus => focus,
+            FocusPhase::Break => rest,
+        };
+        length.saturating_sub(self.phase_started.elapsed())
+    }
+}
+
+/// Format a duration as minutes and seconds, like `24:05`
+fn format_countdown(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    format!("{:02}:{:02}", seconds / 60, seconds % 60)
+}
+
+/// Details for the about screen, gathered when it opens
+#[derive(Debug, Clone)]
+pub struct AboutInfo {
+    pub db_path: String,
+    pub profile: String,
+    pub content_count: i64,
+    /// Stories hidden for good, which `content_count` leaves out
+    pub hidden_count: i64,
+    /// What the update check found, or that it hasn't finished
+    pub update: String,
+}
+
+/// A macro key's actions part way through
+#[derive(Debug, Clone)]
+pub struct RunningMacro {
+    pending: VecDeque<ReaderAction>,
+    ran: Vec<ReaderAction>,
+}
+
+impl RunningMacro {
+    /// The actions run so far, for the status bar
+    fn ran_summary(&self) -> String {
+        self.ran.iter().map(|action| action.as_str()).collect::<Vec<_>>().join(" → ")
+    }
+}
+
+/// Where a topic stands in a fetch started from the reader
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TopicFetchState {
+    Waiting,
+    Fetching,
+    Done,
+    /// Finished by an earlier, interrupted run that this one continues
+    DoneEarlier,
+    Failed,
+}
+
+/// Fetch progress of one topic
+#[derive(Debug, Clone)]
+pub struct TopicFetchProgress {
+    pub topic: Topic,
+    pub units: usize,
+    pub state: TopicFetchState,
+}
+
+/// Progress of a fetch started from the reader, built up from its events
+#[derive(Debug, Clone, Default)]
+pub struct FetchProgress {
+    /// Topics in the order they are fetched
+    pub topics: Vec<TopicFetchProgress>,
+    pub units_per_topic: usize,
+    /// Stories stored by this run so far
+    pub units_added: usize,
+    pub fun_facts_added: usize,
+    /// What the fetcher is doing right now
+    pub activity: String,
+    /// Whether the run has ended, successfully or not
+    pub finished: bool,
+    /// Why the run stopped early, if it did
+    pub error: Option<String>,
+}
+
+impl FetchProgress {
+    /// Update the progress with the next event from the fetcher
+    pub fn apply(&mut self, event: FetchEvent) {
+        match event {
+            FetchEvent::Started {
+                topics,
+                finished_earlier,
+                units_per_topic,
+            } => {
+                self.units_per_topic = units_per_topic;
+                self.topics = topics
+                    .into_iter()
+                    .map(|topic| TopicFetchProgress {
+                        topic,
+                        units: 0,
+                        state: if finished_earlier.contains(&topic) {
+                            TopicFetchState::DoneEarlier
+                        } else {
+                            TopicFetchState::Waiting
+                        },
+                    })
+                    .collect();
+            }
+            FetchEvent::TopicStarted { topic, units, .. } => {
+                self.update_topic(topic, units, TopicFetchState::Fetching);
+            }
+            FetchEvent::Searching { query, .. } => self.activity = format!("Searching for {}", query),
+            FetchEvent::TopicQueued { topic, titles } => {
+                self.activity = format!("Found {} articles for {}", titles, topic);
+            }
+            FetchEvent::Fetching { title, .. } => self.activity = format!("Reading '{}'", title),
+            FetchEvent::UnitAdded { topic, units, .. } => {
+                self.units_added += 1;
+                self.update_topic(topic, units, TopicFetchState::Fetching);
+            }
+            FetchEvent::FunFactAdded { .. } => self.fun_facts_added += 1,
+            FetchEvent::DuplicateSkipped { .. }
+            | FetchEvent::Redirected { .. }
+            | FetchEvent::FromBody { .. }
+            | FetchEvent::LongIntro { .. }
+            | FetchEvent::NoContent { .. }
+            | FetchEvent::Rejected { .. }
+            | FetchEvent::ArticleFailed { .. } => {}
+            FetchEvent::TopicFinished { topic, units, .. } => {
+                self.update_topic(topic, units, TopicFetchState::Done);
+            }
+            FetchEvent::TopicFailed { topic, error } => {
+                let units = self.topic(topic).map_or(0, |t| t.units);
+                self.update_topic(topic, units, TopicFetchState::Failed);
+                self.activity = format!("{}: {}", topic, error);
+            }
+            FetchEvent::Finished { .. } => {
+                self.finished = true;
+                self.activity.clear();
+            }
+            FetchEvent::Failed { error } => {
+                self.finished = true;
+                self.error = Some(error);
+            }
+        }
+    }
+
+    fn topic(&self, topic: Topic) -> Option<&TopicFetchProgress> {
+        self.topics.iter().find(|t| t.topic == topic)
+    }
+
+    fn update_topic(&mut self, topic: Topic, units: usize, state: TopicFetchState) {
+        if let Some(progress) = self.topics.iter_mut().find(|t| t.topic == topic) {
+            progress.units = units;
+            progress.state = state;
+        }
+    }
+}
+
 /// Main application state
 /// This struct demonstrates state management in TUI applications
 pub struct App {
@@ -30,12 +285,145 @@ pub struct App {
     pub displayed_chars: usize,
     /// Whether the current content is fully displayed
     pub fully_displayed: bool,
+    /// Rows the content is scrolled down by, once the reader has scrolled by hand
+    pub scroll_offset: u16,
+    /// Whether the content view follows the typewriter to the newest text; scrolling by
+    /// hand stops it
+    pub scroll_follow: bool,
+    /// Furthest the content can scroll and the height of its view, as last drawn
+    pub scroll_limits: Cell<(u16, u16)>,
+    /// Whether the source has delivered all of the current content; until it has,
+    /// the typewriter reveals what has arrived and then waits for more
+    pub content_complete: bool,
+    /// Whether the current content picks up where an earlier, almost finished read stopped
+    pub resumed: bool,
     /// Start time for measuring reading duration
     pub start_time: Instant,
     /// Whether the app should quit
     pub should_quit: bool,
     /// Status message to display
     pub status_message: String,
+    /// Set when the user asks for the next content (handled in main loop)
+    pub next_requested: bool,
+    /// Set when the user asks to delete the current content (handled in main loop)
+    pub delete_requested: bool,
+    /// Set when the user says they've read the current content elsewhere (handled in main loop)
+    pub dismiss_requested: bool,
+    /// Set when the user asks never to see the current content again (handled in main loop)
+    pub hide_requested: bool,
+    /// Set when the user asks to bookmark (pin) or unbookmark the current content (handled in main loop)
+    pub pin_requested: bool,
+    /// Whether the current content is bookmarked by the profile (kept up to date by the main loop)
+    pub current_is_bookmarked: bool,
+    /// Whether the current content scores as sensitive (kept up to date by the main loop)
+    pub current_is_sensitive: bool,
+    /// Set when the user asks to undo the last deletion (handled in main loop)
+    pub undo_requested: bool,
+    /// Content deleted during this session, most recent last, so deletions can be undone
+    pub deleted_history: Vec<ContentUnit>,
+    /// Content shown this session, oldest first, for going back with Left
+    pub history: Vec<ContentUnit>,
+    /// Position of the current content in `history`
+    pub history_pos: usize,
+    /// Whether the current content was brought back from `history`; its interaction
+    /// was recorded when it was first left
+    pub revisiting: bool,
+    /// Set when the user asks for the previous content (handled in main loop)
+    pub back_requested: bool,
+    /// Layout and typography settings
+    pub ui: UiConfig,
+    /// Set when the reveal speed preset changed and should be saved (handled in main loop)
+    pub reveal_speed_changed: bool,
+    /// Reveal speed to go back to when the typewriter is switched on again
+    pub speed_before_instant: RevealSpeed,
+    /// Set when guided topic order was switched and should be saved (handled in main loop)
+    pub guided_order_changed: bool,
+    /// Change to the explore/exploit balance the user asked for (handled in main loop)
+    pub balance_nudge: f64,
+    /// Macro key whose actions are still being run (see `step_macro`)
+    pub running_macro: Option<RunningMacro>,
+    /// When the current status message should disappear, for short-lived messages
+    pub status_expires_at: Option<Instant>,
+    /// Only show content from this topic, if set
+    pub topic_filter: Option<Topic>,
+    /// Set when the topic filter changed and new content should be loaded (handled in main loop)
+    pub topic_filter_changed: bool,
+    /// Set when the active topic filter has no content to show, so loading stops until it changes
+    pub topic_filter_empty: bool,
+    /// Share of picks the topic filter takes from its own topic
+    /// (`recommendation.topic_filter_share`); below 1.0 other topics come through now and then
+    pub topic_filter_share: f64,
+    /// Whether only fun facts are served (quick-bite mode)
+    pub quick_bites: bool,
+    /// In chronological mode, the estimated year and id of the last story served, which
+    /// the next one follows; `(i32::MIN, 0)` before the first
+    pub chronological: Option<(i32, i64)>,
+    /// Whether the topic trends screen is open instead of the reader
+    pub show_trends: bool,
+    /// Bucket size used on the trends screen
+    pub trend_bucket: TrendBucket,
+    /// Set when trend data should be (re)loaded (handled in main loop)
+    pub trend_requested: bool,
+    /// Trend data shown on the trends screen
+    pub trend: Option<TopicTrend>,
+    /// Whether the reading activity calendar is open instead of the reader
+    pub show_calendar: bool,
+    /// Year shown on the activity calendar
+    pub calendar_year: i32,
+    /// Set when calendar data should be (re)loaded (handled in main loop)
+    pub calendar_requested: bool,
+    /// Calendar data shown on the activity calendar screen
+    pub calendar: Option<ActivityCalendar>,
+    /// Whether the key help screen is open instead of the reader
+    pub show_help: bool,
+    /// Whether the about screen is open over the reader
+    pub show_about: bool,
+    /// Set when the about screen's details should be gathered (handled in main loop)
+    pub about_requested: bool,
+    /// Details shown on the about screen
+    pub about: Option<AboutInfo>,
+    /// Whether the recommender preview is open instead of the reader
+    pub show_preview: bool,
+    /// Set when the recommender preview should be (re)computed (handled in main loop)
+    pub preview_requested: bool,
+    /// Stories the recommender would likely serve next, for the preview
+    pub preview: Vec<(Topic, ContentUnit)>,
+    /// Name of the strategy choosing topics, shown with the preview
+    pub strategy: &'static str,
+    /// Focus timer, while a study session is running
+    pub focus: Option<FocusTimer>,
+    /// Whether the fetch progress screen is open instead of the reader
+    pub show_fetch: bool,
+    /// Set when a fetch should be started (handled in main loop)
+    pub fetch_requested: bool,
+    /// Progress of the fetch started this session, if any
+    pub fetch: Option<FetchProgress>,
+    /// Term lookup popup over the current content, while open
+    pub explain: Option<Explain>,
+    /// Highlighted row of the topic picker while it's open (0 is all topics, then `Topic::all()`)
+    pub topic_picker: Option<usize>,
+    /// Term to explain (handled in main loop)
+    pub lookup_requested: Option<String>,
+    /// Search prompt over the library, while open
+    pub search: Option<Search>,
+    /// Set when the search query changed and needs running (handled in main loop)
+    pub search_requested: bool,
+    /// Search result picked to read next (handled in main loop)
+    pub search_choice: Option<ContentUnit>,
+    /// Set when the explanation on show should be saved as a story (handled in main loop)
+    pub save_term_requested: bool,
+    /// Time passed that the typewriter hasn't turned into revealed characters yet
+    reveal_backlog: Duration,
+    /// Titles of the last few stories shown, oldest first
+    recent_titles: VecDeque<String>,
+    /// Whether the current story's title was among `recent_titles` when it was shown
+    title_repeated: bool,
+}
+
+impl Default for App {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl App {
@@ -45,52 +433,321 @@ impl App {
             current_content: None,
             displayed_chars: 0,
             fully_displayed: false,
+            scroll_offset: 0,
+            scroll_follow: true,
+            scroll_limits: Cell::new((0, 0)),
+            content_complete: true,
+            resumed: false,
             start_time: Instant::now(),
             should_quit: false,
             status_message: "Loading content...".to_string(),
+            next_requested: false,
+            delete_requested: false,
+            dismiss_requested: false,
+            hide_requested: false,
+            pin_requested: false,
+            current_is_bookmarked: false,
+            current_is_sensitive: false,
+            undo_requested: false,
+            deleted_history: Vec::new(),
+            history: Vec::new(),
+            history_pos: 0,
+            revisiting: false,
+            back_requested: false,
+            ui: UiConfig::default(),
+            reveal_speed_changed: false,
+            speed_before_instant: RevealSpeed::Normal,
+            guided_order_changed: false,
+            balance_nudge: 0.0,
+            running_macro: None,
+            status_expires_at: None,
+            topic_filter: None,
+            topic_filter_changed: false,
+            topic_filter_empty: false,
+            quick_bites: false,
+            chronological: None,
+            topic_filter_share: 1.0,
+            show_trends: false,
+            trend_bucket: TrendBucket::default(),
+            trend_requested: false,
+            trend: None,
+            show_calendar: false,
+            calendar_year: current_year(),
+            calendar_requested: false,
+            calendar: None,
+            show_help: false,
+            show_about: false,
+            about_requested: false,
+            about: None,
+            show_preview: false,
+            preview_requested: false,
+            preview: Vec::new(),
+            strategy: StrategyKind::default().as_str(),
+            focus: None,
+            show_fetch: false,
+            fetch_requested: false,
+            fetch: None,
+            explain: None,
+            topic_picker: None,
+            lookup_requested: None,
+            search: None,
+            search_requested: false,
+            search_choice: None,
+            save_term_requested: false,
+            reveal_backlog: Duration::ZERO,
+            recent_titles: VecDeque::with_capacity(RECENT_TITLES),
+            title_repeated: false,
         }
     }
 
+    /// Use the given layout and typography settings
+    pub fn with_ui_config(mut self, ui: UiConfig) -> Self {
+        self.ui = ui;
+        self
+    }
+
     /// Set new content to display
     /// This demonstrates method chaining and ownership transfer
     pub fn set_content(&mut self, content: ContentUnit) {
+        self.begin_content(content);
+        self.content_complete = true;
+    }
+
+    /// Show content skipped earlier, with the typewriter starting at `displayed_chars`
+    pub fn resume_content(&mut self, content: ContentUnit, displayed_chars: usize) {
+        let total_chars = content.content.len();
+        self.set_content(content);
+        self.displayed_chars = displayed_chars.min(total_chars);
+        self.resumed = true;
+    }
+
+    /// Start displaying content whose text is still arriving
+    /// Add the rest with `append_content` and call `finish_content` once the source is done
+    pub fn begin_content(&mut self, content: ContentUnit) {
+        self.sync_history();
+        if self.history.len() == HISTORY_LIMIT {
+            self.history.remove(0);
+        }
+        self.history.push(content.clone());
+        self.history_pos = self.history.len() - 1;
+        self.revisiting = false;
+
+        self.title_repeated = self.recent_titles.contains(&content.title);
+        if self.recent_titles.len() == RECENT_TITLES {
+            self.recent_titles.pop_front();
+        }
+        self.recent_titles.push_back(content.title.clone());
         self.current_content = Some(content);
         self.displayed_chars = 0;
+        self.resumed = false;
         self.fully_displayed = false;
+        self.content_complete = false;
+        // Stories shown all at once start from the top rather than following the reveal
+        self.scroll_offset = 0;
+        self.scroll_follow = self.ui.reveal_speed != RevealSpeed::Instant;
         self.start_time = Instant::now();
+        self.reveal_backlog = Duration::ZERO;
         self.status_message.clear();
     }
 
-    /// Update the typewriter effect
+    /// Whether there is earlier content to go back to
+    pub fn can_go_back(&self) -> bool {
+        self.history_pos > 0 && !self.history.is_empty()
+    }
+
+    /// Show the content before the current one again, fully revealed
+    pub fn show_previous(&mut self) {
+        if self.can_go_back() {
+            self.sync_history();
+            self.revisit(self.history_pos - 1);
+        }
+    }
+
+    /// After going back, show the content that came next; false at the newest content
+    pub fn show_following(&mut self) -> bool {
+        if self.history_pos + 1 >= self.history.len() {
+            return false;
+        }
+        self.sync_history();
+        self.revisit(self.history_pos + 1);
+        true
+    }
+
+    /// Drop content from the history, e.g. once it has been deleted
+    pub fn forget_in_history(&mut self, content_id: i64) {
+        if let Some(i) = self.history.iter().position(|c| c.id == content_id) {
+            self.history.remove(i);
+            if self.history_pos >= i {
+                self.history_pos = self.history_pos.saturating_sub(1);
+            }
+        }
+    }
+
+    /// Show `history[pos]` without the typewriter or touching the database
+    fn revisit(&mut self, pos: usize) {
+        let content = self.history[pos].clone();
+        self.history_pos = pos;
+        self.displayed_chars = content.content.len();
+        self.current_content = Some(content);
+        self.revisiting = true;
+        self.title_repeated = false;
+        self.resumed = false;
+        self.fully_displayed = true;
+        self.content_complete = true;
+        self.scroll_offset = 0;
+        self.scroll_follow = false;
+        self.start_time = Instant::now();
+        self.reveal_backlog = Duration::ZERO;
+        self.status_message.clear();
+    }
+
+    /// Copy text that arrived after the current content was shown into its history entry
+    fn sync_history(&mut self) {
+        if let (Some(content), Some(entry)) = (&self.current_content, self.history.get_mut(self.history_pos)) {
+            if entry.id == content.id {
+                entry.clone_from(content);
+            }
+        }
+    }
+
+    /// Title to show above the current content: the stored title, or the first sentence
+    /// when `title_from_first_line` is on and the title was just shown for another story
+    pub fn display_title(&self) -> String {
+        let Some(content) = &self.current_content else {
+            return String::new();
+        };
+        if self.ui.title_from_first_line && self.title_repeated {
+            let heading = content.heading();
+            if !heading.is_empty() {
+                return heading;
+            }
+        }
+        content.title.clone()
+    }
+
+    /// Add text that has just arrived to the end of the current content
+    pub fn append_content(&mut self, text: &str) {
+        if let Some(ref mut content) = self.current_content {
+            content.content.push_str(text);
+            content.word_count = content.content.split_whitespace().count();
+        }
+    }
+
+    /// Note that the current content's source has delivered everything
+    pub fn finish_content(&mut self) {
+        self.content_complete = true;
+    }
+
+    /// Update the typewriter effect for `elapsed` time since the last update
     /// This demonstrates time-based state updates
-    pub fn update_typewriter(&mut self) {
+    pub fn update_typewriter(&mut self, elapsed: Duration) {
         if let Some(ref content) = self.current_content {
             if !self.fully_displayed {
                 let total_chars = content.content.len();
                 if self.displayed_chars < total_chars {
-                    // Display characters gradually (adjust speed here)
-                    let chars_per_update = 2; // Characters to reveal per update
+                    // Display characters gradually at the chosen reveal speed, independent of frame rate
+                    let chars_per_update = match self.ui.reveal_speed.chars_per_second() {
+                        // Manual reveal only moves on key presses
+                        _ if self.ui.reveal_speed == RevealSpeed::Manual => 0,
+                        Some(cps) => {
+                            let per_char = Duration::from_secs(1) / cps;
+                            self.reveal_backlog += elapsed;
+                            let chars = (self.reveal_backlog.as_nanos() / per_char.as_nanos()) as u32;
+                            self.reveal_backlog -= per_char * chars;
+                            chars as usize
+                        }
+                        None => total_chars,
+                    };
                     self.displayed_chars = (self.displayed_chars + chars_per_update).min(total_chars);
-                } else {
+                } else if self.content_complete {
                     self.fully_displayed = true;
+                } else {
+                    // Caught up with the source; don't bank time while waiting for more
+                    self.reveal_backlog = Duration::ZERO;
                 }
             }
         }
     }
 
-    /// Skip to full content display
+    /// Skip to full content display, or to as much as has arrived so far
     pub fn skip_typewriter(&mut self) {
         if let Some(ref content) = self.current_content {
             self.displayed_chars = content.content.len();
-            self.fully_displayed = true;
+            self.fully_displayed = self.content_complete;
+            // Keep the view where the reveal had got to, with the rest below it
+            if self.scroll_follow {
+                self.scroll_offset = self.scroll_limits.get().0;
+                self.scroll_follow = false;
+            }
         }
     }
 
+    /// Scroll the content by `rows` (negative scrolls up), within what was last drawn
+    /// Scrolling back to the end while the typewriter runs follows it again
+    pub fn scroll_content(&mut self, rows: i32) {
+        let (max, _) = self.scroll_limits.get();
+        let from = if self.scroll_follow { max } else { self.scroll_offset.min(max) };
+        let to = (i32::from(from) + rows).clamp(0, i32::from(max));
+        self.scroll_offset = u16::try_from(to).unwrap_or(0);
+        self.scroll_follow = to == i32::from(max) && !self.fully_displayed;
+    }
+
+    /// Scroll the content by a screenful less one row, keeping a line of context
+    pub fn scroll_content_page(&mut self, down: bool) {
+        let rows = i32::from(self.scroll_limits.get().1.saturating_sub(1).max(1));
+        self.scroll_content(if down { rows } else { -rows });
+    }
+
+    /// Reveal up to the end of the next sentence, for stepping through content by hand
+    /// The last step shows everything, like skipping the typewriter
+    pub fn reveal_next_sentence(&mut self) {
+        let Some(content) = &self.current_content else {
+            return;
+        };
+        let total_chars = content.content.chars().count();
+        match sentence_ends(&content.content).into_iter().find(|&end| end > self.displayed_chars) {
+            Some(end) if end < total_chars => self.displayed_chars = end,
+            _ => self.skip_typewriter(),
+        }
+    }
+
+    /// Sentences not yet shown, while content is being revealed by hand
+    pub fn remaining_sentences(&self) -> Option<usize> {
+        if self.ui.reveal_speed != RevealSpeed::Manual || self.fully_displayed {
+            return None;
+        }
+        let content = self.current_content.as_ref()?;
+        let ends = sentence_ends(&content.content);
+        Some(ends.iter().filter(|&&end| end > self.displayed_chars).count())
+    }
+
     /// Get the elapsed reading time in seconds
     pub fn get_reading_time(&self) -> u32 {
         self.start_time.elapsed().as_secs() as u32
     }
 
+    /// Percent of the current content revealed so far, or None when no gauge should show
+    /// (no content, or content too short to need one)
+    pub fn progress_percent(&self) -> Option<u16> {
+        let content = self.current_content.as_ref()?;
+        if content.word_count < PROGRESS_MIN_WORDS {
+            return None;
+        }
+        Some(self.displayed_percent())
+    }
+
+    /// Percent of the current content revealed so far, 0 without content
+    pub fn displayed_percent(&self) -> u16 {
+        let Some(content) = self.current_content.as_ref() else {
+            return 0;
+        };
+        let total = content.content.len();
+        if self.fully_displayed || total == 0 {
+            return 100;
+        }
+        (self.displayed_chars.min(total) * 100 / total) as u16
+    }
+
     /// Check if content is being displayed
     pub fn has_content(&self) -> bool {
         self.current_content.is_some()
@@ -99,9 +756,504 @@ impl App {
     /// Set status message
     pub fn set_status(&mut self, message: String) {
         self.status_message = message;
+        self.status_expires_at = None;
+    }
+
+    /// Show a status message for a couple of seconds only
+    pub fn flash_status(&mut self, message: String) {
+        self.status_message = message;
+        self.status_expires_at = Some(Instant::now() + STATUS_FLASH_DURATION);
+    }
+
+    /// Clear a flashed status message once its time is up
+    pub fn expire_status(&mut self) {
+        if self.status_expires_at.is_some_and(|at| Instant::now() >= at) {
+            self.status_message.clear();
+            self.status_expires_at = None;
+        }
+    }
+
+    /// Open the topic picker with the current filter highlighted
+    pub fn open_topic_picker(&mut self) {
+        let row = self
+            .topic_filter
+            .and_then(|current| Topic::all().iter().position(|&t| t == current))
+            .map_or(0, |i| i + 1);
+        self.topic_picker = Some(row);
+    }
+
+    /// Move the topic picker highlight by `step`, staying within the list
+    pub fn move_topic_selection(&mut self, step: isize) {
+        if let Some(row) = &mut self.topic_picker {
+            *row = row.saturating_add_signed(step).min(Topic::all().len());
+        }
+    }
+
+    /// Filter by the highlighted topic and close the picker
+    pub fn choose_picked_topic(&mut self) {
+        if let Some(row) = self.topic_picker.take() {
+            let topic = row.checked_sub(1).and_then(|i| Topic::all().get(i)).copied();
+            self.set_topic_filter(topic);
+        }
+    }
+
+    /// Show content from `topic` only, or from all topics for `None`
+    pub fn set_topic_filter(&mut self, topic: Option<Topic>) {
+        if topic != self.topic_filter || self.topic_filter_empty {
+            if self.chronological.is_some() {
+                self.chronological = Some(CHRONOLOGICAL_START);
+            }
+            self.topic_filter = topic;
+            self.topic_filter_changed = true;
+            self.topic_filter_empty = false;
+        }
+    }
+
+    /// Jump to the topic on number key `slot` (1-9), or back to all topics for 0
+    pub fn select_topic_slot(&mut self, slot: usize) {
+        let topic = match slot {
+            0 => None,
+            n => match self.ui.topic_slots.iter().take(MAX_TOPIC_SLOTS).nth(n - 1) {
+                Some(&topic) => Some(topic),
+                None => {
+                    self.flash_status(format!("No topic on key {}", slot));
+                    return;
+                }
+            },
+        };
+        self.set_topic_filter(topic);
+    }
+
+    /// Switch between guided and random order for the locked topic
+    /// Takes effect from the next story; the current one stays
+    pub fn toggle_guided_order(&mut self) {
+        if self.topic_filter.is_none() {
+            self.flash_status("Lock a topic first (T or 1-9) to read it in order".to_string());
+            return;
+        }
+        self.ui.guided_topic_order = !self.ui.guided_topic_order;
+        self.guided_order_changed = true;
+        self.flash_status(format!("Topic order: {}", guided_order_label(self.ui.guided_topic_order)));
+    }
+
+    /// Switch between regular reading and fun facts only
+    /// Reuses the topic filter reload, since quick bites narrow what is served the same way
+    pub fn toggle_quick_bites(&mut self) {
+        self.quick_bites = !self.quick_bites;
+        self.chronological = None;
+        self.topic_filter_changed = true;
+        self.topic_filter_empty = false;
+    }
+
+    /// Switch between regular reading and dated stories from the oldest on, within the
+    /// topic filter if one is set
+    pub fn toggle_chronological(&mut self) {
+        self.chronological = match self.chronological {
+            Some(_) => None,
+            None => Some(CHRONOLOGICAL_START),
+        };
+        self.quick_bites = false;
+        self.topic_filter_changed = true;
+        self.topic_filter_empty = false;
+    }
+
+    /// Start a focus session, or stop the timer if one is running
+    pub fn toggle_focus(&mut self) {
+        if self.on_break() {
+            self.end_break();
+        }
+        if self.focus.take().is_some() {
+            self.flash_status("Focus timer stopped".to_string());
+        } else {
+            self.focus = Some(FocusTimer::start());
+            self.flash_status(format!("Focus for {} minutes", self.ui.focus_minutes.max(1)));
+        }
+    }
+
+    /// Whether content is paused for a focus break
+    pub fn on_break(&self) -> bool {
+        self.focus.as_ref().is_some_and(|timer| timer.phase == FocusPhase::Break)
+    }
+
+    /// Time left in the current focus session or break
+    pub fn focus_remaining(&self) -> Option<Duration> {
+        self.focus
+            .as_ref()
+            .map(|timer| timer.remaining(self.ui.focus_duration(), self.ui.break_duration()))
+    }
+
+    /// Move to the break screen once the focus session is up
+    pub fn update_focus(&mut self) {
+        let focus_over = self.focus.as_ref().is_some_and(|timer| timer.phase == FocusPhase::Focus)
+            && self.focus_remaining() == Some(Duration::ZERO);
+        if let (true, Some(timer)) = (focus_over, self.focus.as_mut()) {
+            timer.phase = FocusPhase::Break;
+            timer.phase_started = Instant::now();
+            timer.completed += 1;
+        }
+    }
+
+    /// Leave the break and start the next focus session
+    /// Time spent on the break doesn't count towards reading the current content
+    pub fn end_break(&mut self) {
+        if let Some(timer) = self.focus.as_mut().filter(|timer| timer.phase == FocusPhase::Break) {
+            self.start_time += timer.phase_started.elapsed();
+            timer.phase = FocusPhase::Focus;
+            timer.phase_started = Instant::now();
+        }
+    }
+
+    /// Open or close the key help screen
+    pub fn toggle_help(&mut self) {
+        self.show_help = !self.show_help;
+    }
+
+    /// Open or close the about screen
+    pub fn toggle_about(&mut self) {
+        self.show_about = !self.show_about;
+        self.about_requested = self.show_about;
+    }
+
+    /// Open or close the recommender preview
+    pub fn toggle_preview(&mut self) {
+        self.show_preview = !self.show_preview;
+        self.preview_requested = self.show_preview;
+    }
+
+    /// Open the term lookup over the names found in the current content
+    pub fn open_explain(&mut self) {
+        let Some(content) = &self.current_content else {
+            return;
+        };
+        let terms = explainable_terms(&content.content);
+        if terms.is_empty() {
+            self.flash_status("No names to explain in this story".to_string());
+            return;
+        }
+        self.explain = Some(Explain {
+            terms,
+            selected: 0,
+            state: ExplainState::Choosing,
+        });
+    }
+
+    /// Move the term selection by `step`, staying within the list
+    pub fn move_explain_selection(&mut self, step: isize) {
+        if let Some(explain) = &mut self.explain {
+            explain.selected = explain
+                .selected
+                .saturating_add_signed(step)
+                .min(explain.terms.len().saturating_sub(1));
+        }
+    }
+
+    /// Look up the selected term
+    pub fn explain_selected(&mut self) {
+        if let Some(explain) = &mut self.explain {
+            if let Some(term) = explain.terms.get(explain.selected) {
+                explain.state = ExplainState::LookingUp(term.clone());
+                self.lookup_requested = Some(term.clone());
+            }
+        }
+    }
+
+    /// Go back from an explanation to the list of terms
+    pub fn back_to_terms(&mut self) {
+        if let Some(explain) = &mut self.explain {
+            explain.state = ExplainState::Choosing;
+        }
+    }
+
+    /// Show the outcome of looking up `term`, unless the popup has moved on since
+    /// Failures go back to the list with a short message
+    pub fn apply_term_lookup(&mut self, term: &str, result: anyhow::Result<Option<TermSummary>>) {
+        let Some(explain) = &mut self.explain else {
+            return;
+        };
+        if !matches!(&explain.state, ExplainState::LookingUp(waiting) if waiting == term) {
+            return;
+        }
+
+        match result {
+            Ok(Some(summary)) => explain.state = ExplainState::Found(summary),
+            Ok(None) => explain.state = ExplainState::NotFound(term.to_string()),
+            Err(e) => {
+                explain.state = ExplainState::Choosing;
+                self.flash_status(format!("Couldn't look up {}: {}", term, e));
+            }
+        }
+    }
+
+    /// The explanation on show in the popup, if any
+    pub fn shown_term(&self) -> Option<&TermSummary> {
+        match &self.explain.as_ref()?.state {
+            ExplainState::Found(summary) => Some(summary),
+            _ => None,
+        }
+    }
+
+    /// Open the search prompt, empty
+    pub fn open_search(&mut self) {
+        self.search = Some(Search::default());
+    }
+
+    /// Add a typed character to the search query, or remove the last one for `None`
+    pub fn edit_search(&mut self, typed: Option<char>) {
+        if let Some(search) = &mut self.search {
+            match typed {
+                Some(c) => search.query.push(c),
+                None => {
+                    search.query.pop();
+                }
+            }
+            self.search_requested = true;
+        }
+    }
+
+    /// Show the results of running the current query
+    pub fn show_search_results(&mut self, results: Vec<ContentUnit>) {
+        if let Some(search) = &mut self.search {
+            search.results = results;
+            search.selected = 0;
+        }
+    }
+
+    /// Move the search result highlight by `step`, staying within the list
+    pub fn move_search_selection(&mut self, step: isize) {
+        if let Some(search) = &mut self.search {
+            search.selected = search
+                .selected
+                .saturating_add_signed(step)
+                .min(search.results.len().saturating_sub(1));
+        }
+    }
+
+    /// Close the prompt and ask for the highlighted result to be shown
+    pub fn choose_search_result(&mut self) {
+        if let Some(mut search) = self.search.take() {
+            if search.selected < search.results.len() {
+                self.search_choice = Some(search.results.swap_remove(search.selected));
+            }
+        }
+    }
+
+    /// Open or close the topic trends screen
+    pub fn toggle_trends(&mut self) {
+        self.show_trends = !self.show_trends;
+        self.trend_requested = self.show_trends;
+    }
+
+    /// Switch the trends screen between weekly and monthly buckets
+    pub fn toggle_trend_bucket(&mut self) {
+        self.trend_bucket = match self.trend_bucket {
+            TrendBucket::Weekly => TrendBucket::Monthly,
+            TrendBucket::Monthly => TrendBucket::Weekly,
+        };
+        self.trend_requested = true;
+    }
+
+    /// Open or close the activity calendar, starting on the current year
+    pub fn toggle_calendar(&mut self) {
+        self.show_calendar = !self.show_calendar;
+        if self.show_calendar {
+            self.calendar_year = current_year();
+            self.calendar_requested = true;
+        }
+    }
+
+    /// Move the activity calendar by `delta` years, stopping at the current year
+    pub fn shift_calendar_year(&mut self, delta: i32) {
+        let year = (self.calendar_year + delta).min(current_year());
+        if year != self.calendar_year {
+            self.calendar_year = year;
+            self.calendar_requested = true;
+        }
+    }
+
+    /// Open the fetch progress screen, starting a fetch if none has run this session
+    pub fn open_fetch(&mut self) {
+        self.show_fetch = true;
+        if self.fetch.is_none() {
+            self.fetch_requested = true;
+        }
+    }
+
+    /// Start another fetch once the previous one has ended
+    /// A run that stopped early continues from where it got to
+    pub fn restart_fetch(&mut self) {
+        if self.fetch.as_ref().is_some_and(|fetch| fetch.finished) {
+            self.fetch_requested = true;
+        }
+    }
+
+    /// Whether a fetch is running in the background
+    pub fn fetching(&self) -> bool {
+        self.fetch.as_ref().is_some_and(|fetch| !fetch.finished)
+    }
+
+    /// Pass an event from the background fetch on to its progress
+    pub fn apply_fetch_event(&mut self, event: FetchEvent) {
+        let Some(fetch) = self.fetch.as_mut() else {
+            return;
+        };
+        fetch.apply(event);
+        if fetch.finished && !self.show_fetch {
+            let message = match &fetch.error {
+                Some(error) => format!("Fetch stopped: {}", error),
+                None => format!("Fetch finished: {} new stories", fetch.units_added),
+            };
+            self.flash_status(message);
+        }
+    }
+
+    /// Switch to the next reveal speed preset
+    pub fn cycle_reveal_speed(&mut self) {
+        self.set_reveal_speed(self.ui.reveal_speed.next());
+    }
+
+    /// Type faster (`faster`) or slower; manual reveal isn't a rate, so it's left alone
+    pub fn step_reveal_speed(&mut self, faster: bool) {
+        if self.ui.reveal_speed == RevealSpeed::Manual {
+            self.flash_status("Manual reveal has no speed; press S to change mode".to_string());
+            return;
+        }
+        let speed = if faster { self.ui.reveal_speed.faster() } else { self.ui.reveal_speed.slower() };
+        self.set_reveal_speed(speed);
+    }
+
+    /// Switch the typewriter effect off (show stories at once) or back on at its last speed
+    pub fn toggle_typewriter(&mut self) {
+        if self.ui.reveal_speed == RevealSpeed::Instant {
+            self.set_reveal_speed(self.speed_before_instant);
+        } else {
+            self.speed_before_instant = self.ui.reveal_speed;
+            self.set_reveal_speed(RevealSpeed::Instant);
+        }
+    }
+
+    /// Run one named action as its key would, or say why it can't run right now
+    /// Actions carried out by the main loop are only requested here
+    pub fn run_action(&mut self, action: ReaderAction) -> Result<(), &'static str> {
+        let needs_story = matches!(
+            action,
+            ReaderAction::Next
+                | ReaderAction::Reveal
+                | ReaderAction::Pin
+                | ReaderAction::Delete
+                | ReaderAction::Dismiss
+                | ReaderAction::Hide
+        );
+        if needs_story && !self.has_content() {
+            return Err("no story on screen");
+        }
+
+        match action {
+            ReaderAction::Next => {
+                if !(self.revisiting && self.show_following()) {
+                    self.next_requested = true;
+                }
+            }
+            ReaderAction::Back if !self.can_go_back() => return Err("this is the first story this session"),
+            ReaderAction::Back => self.back_requested = true,
+            ReaderAction::Reveal => self.skip_typewriter(),
+            ReaderAction::Pin => self.pin_requested = true,
+            ReaderAction::Delete => self.delete_requested = true,
+            ReaderAction::Dismiss => self.dismiss_requested = true,
+            ReaderAction::Hide => self.hide_requested = true,
+            ReaderAction::Undo if self.deleted_history.is_empty() => return Err("nothing to undo"),
+            ReaderAction::Undo => self.undo_requested = true,
+            ReaderAction::Faster | ReaderAction::Slower if self.ui.reveal_speed == RevealSpeed::Manual => {
+                return Err("manual reveal has no speed");
+            }
+            ReaderAction::Faster => self.step_reveal_speed(true),
+            ReaderAction::Slower => self.step_reveal_speed(false),
+            ReaderAction::Typewriter => self.toggle_typewriter(),
+            ReaderAction::QuickBites => self.toggle_quick_bites(),
+            ReaderAction::GuidedOrder if self.topic_filter.is_none() => return Err("no topic is locked"),
+            ReaderAction::GuidedOrder => self.toggle_guided_order(),
+            ReaderAction::Focus => self.toggle_focus(),
+        }
+        Ok(())
+    }
+
+    /// Start running a macro key's actions
+    pub fn start_macro(&mut self, actions: &[ReaderAction]) {
+        self.running_macro = Some(RunningMacro {
+            pending: actions.iter().copied().collect(),
+            ran: Vec::new(),
+        });
+    }
+
+    /// Run the next action of the macro in progress, or sum up once all have run
+    /// Called once per turn of the main loop, so whatever an action asked the main loop
+    /// to do (pinning the story, loading the next one) is done before the next action runs
+    pub fn step_macro(&mut self) {
+        let Some(mut running) = self.running_macro.take() else {
+            return;
+        };
+        let Some(action) = running.pending.pop_front() else {
+            self.flash_status(format!("Ran {}", running.ran_summary()));
+            return;
+        };
+
+        match self.run_action(action) {
+            Ok(()) => {
+                running.ran.push(action);
+                self.running_macro = Some(running);
+            }
+            Err(reason) if running.ran.is_empty() => {
+                self.flash_status(format!("Macro stopped at {}: {}", action.as_str(), reason));
+            }
+            Err(reason) => self.flash_status(format!(
+                "Macro stopped at {}: {} (ran {})",
+                action.as_str(),
+                reason,
+                running.ran_summary()
+            )),
+        }
+    }
+
+    /// Drop the rest of the macro in progress, when an action failed in the main loop
+    pub fn abort_macro(&mut self) {
+        self.running_macro = None;
+    }
+
+    /// Use `speed` from now on and save it to the config file
+    fn set_reveal_speed(&mut self, speed: RevealSpeed) {
+        self.ui.reveal_speed = speed;
+        self.reveal_speed_changed = true;
+        self.flash_status(format!("Reveal speed: {}", speed));
+    }
+}
+
+/// How the locked topic is being read, for the status bar
+/// How a topic filter taking the given share of picks is described: "filtered" when it
+/// takes them all, "focused" when other topics come through too, and nothing when it
+/// takes none (a zero share, or one that isn't a number), since then it doesn't filter
+pub fn topic_filter_label(share: f64) -> Option<&'static str> {
+    if share >= 1.0 {
+        Some("filtered")
+    } else if share > 0.0 {
+        Some("focused")
+    } else {
+        None
+    }
+}
+
+fn guided_order_label(guided: bool) -> &'static str {
+    if guided {
+        "in order"
+    } else {
+        "random"
     }
 }
 
+/// The current year in local time
+fn current_year() -> i32 {
+    use chrono::Datelike;
+    chrono::Local::now().year()
+}
+
 /// Initialize the terminal for TUI mode
 /// This demonstrates terminal setup and error handling
 pub fn init_terminal() -> io::Result<Terminal<CrosstermBackend<Stdout>>> {
@@ -121,28 +1273,260 @@ pub fn restore_terminal(terminal: &mut Terminal<CrosstermBackend<Stdout>>) -> io
     Ok(())
 }
 
-/// Handle keyboard input events
+/// Handle keyboard input events, waiting up to `timeout` for one to arrive
 /// This demonstrates event handling and pattern matching
-pub fn handle_events(app: &mut App) -> io::Result<()> {
-    // Non-blocking event polling
-    if event::poll(Duration::from_millis(50))? {
+pub fn handle_events(app: &mut App, timeout: Duration) -> io::Result<()> {
+    // Polling with a timeout paces the main loop while still reacting to keys immediately
+    if event::poll(timeout)? {
         if let Event::Key(key) = event::read()? {
             // Only handle key press events, not release
             if key.kind == KeyEventKind::Press {
+                // Content is paused during a focus break
+                if app.on_break() {
+                    match key.code {
+                        KeyCode::Enter | KeyCode::Char(' ') => app.end_break(),
+                        KeyCode::Char('o') => app.toggle_focus(),
+                        KeyCode::Char('q') => app.should_quit = true,
+                        _ => {}
+                    }
+                    return Ok(());
+                }
+
+                // The trends screen has its own small set of keys
+                if app.show_trends {
+                    match key.code {
+                        KeyCode::Char('g') | KeyCode::Esc => app.toggle_trends(),
+                        KeyCode::Char('w') => app.toggle_trend_bucket(),
+                        KeyCode::Char('q') => app.should_quit = true,
+                        _ => {}
+                    }
+                    return Ok(());
+                }
+
+                if app.show_preview {
+                    match key.code {
+                        KeyCode::Char('v') | KeyCode::Esc => app.toggle_preview(),
+                        KeyCode::Char('r') => app.preview_requested = true,
+                        KeyCode::Char('q') => app.should_quit = true,
+                        _ => {}
+                    }
+                    return Ok(());
+                }
+
+                if app.show_calendar {
+                    match key.code {
+                        KeyCode::Char('c') | KeyCode::Esc => app.toggle_calendar(),
+                        KeyCode::Left => app.shift_calendar_year(-1),
+                        KeyCode::Right => app.shift_calendar_year(1),
+                        KeyCode::Char('q') => app.should_quit = true,
+                        _ => {}
+                    }
+                    return Ok(());
+                }
+
+                if app.show_fetch {
+                    match key.code {
+                        KeyCode::Char('n') | KeyCode::Esc => app.show_fetch = false,
+                        KeyCode::Enter => app.restart_fetch(),
+                        KeyCode::Char('q') => app.should_quit = true,
+                        _ => {}
+                    }
+                    return Ok(());
+                }
+
+                if app.show_help {
+                    match key.code {
+                        KeyCode::Char('q') => app.should_quit = true,
+                        KeyCode::Char('i') => {
+                            app.toggle_help();
+                            app.toggle_about();
+                        }
+                        _ => app.toggle_help(),
+                    }
+                    return Ok(());
+                }
+
+                if app.show_about {
+                    match key.code {
+                        KeyCode::Char('q') => app.should_quit = true,
+                        _ => app.toggle_about(),
+                    }
+                    return Ok(());
+                }
+
+                // Everything typed goes into the search query
+                if app.search.is_some() {
+                    match key.code {
+                        KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                            app.should_quit = true;
+                        }
+                        KeyCode::Char(c) => app.edit_search(Some(c)),
+                        KeyCode::Backspace => app.edit_search(None),
+                        KeyCode::Up => app.move_search_selection(-1),
+                        KeyCode::Down => app.move_search_selection(1),
+                        KeyCode::Enter => app.choose_search_result(),
+                        KeyCode::Esc => app.search = None,
+                        _ => {}
+                    }
+                    return Ok(());
+                }
+
+                if let Some(explain) = &app.explain {
+                    let choosing = matches!(explain.state, ExplainState::Choosing);
+                    match key.code {
+                        KeyCode::Up | KeyCode::Left if choosing => app.move_explain_selection(-1),
+                        KeyCode::Down | KeyCode::Right if choosing => app.move_explain_selection(1),
+                        KeyCode::Enter if choosing => app.explain_selected(),
+                        KeyCode::Char('s') if app.shown_term().is_some() => {
+                            app.save_term_requested = true;
+                        }
+                        KeyCode::Esc if !choosing => app.back_to_terms(),
+                        KeyCode::Char('x') | KeyCode::Esc => app.explain = None,
+                        KeyCode::Char('q') => app.should_quit = true,
+                        _ => {}
+                    }
+                    return Ok(());
+                }
+
+                if app.topic_picker.is_some() {
+                    match key.code {
+                        KeyCode::Up | KeyCode::Left => app.move_topic_selection(-1),
+                        KeyCode::Down | KeyCode::Right => app.move_topic_selection(1),
+                        KeyCode::Enter => app.choose_picked_topic(),
+                        // Esc drops the filter, T leaves it as it was
+                        KeyCode::Esc => {
+                            app.topic_picker = None;
+                            app.set_topic_filter(None);
+                        }
+                        KeyCode::Char('t') => app.topic_picker = None,
+                        KeyCode::Char('q') => app.should_quit = true,
+                        _ => {}
+                    }
+                    return Ok(());
+                }
+
+                // Macro keys come before the reader's own keys
+                let macro_key = match key.code {
+                    KeyCode::F(n) => Some(MacroKey::Function(n)),
+                    KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => Some(MacroKey::Char(c)),
+                    _ => None,
+                };
+                if let Some(key_macro) = macro_key.and_then(|k| app.ui.keys.get(&k)).cloned() {
+                    app.start_macro(key_macro.actions());
+                    return Ok(());
+                }
+
                 match key.code {
+                    KeyCode::Char('z') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                        app.undo_requested = true;
+                    }
+                    // With a topic locked, Esc goes back to all topics rather than quitting
+                    KeyCode::Esc if app.topic_filter.is_some() => {
+                        app.set_topic_filter(None);
+                    }
                     KeyCode::Char('q') | KeyCode::Esc => {
                         app.should_quit = true;
                     }
-                    KeyCode::Right | KeyCode::Enter | KeyCode::Char(' ') => {
-                        if app.has_content() {
-                            if !app.fully_displayed {
-                                // Skip typewriter effect
-                                app.skip_typewriter();
-                            } else {
-                                // Request new content (handled in main loop)
-                                app.current_content = None;
-                            }
-                        }
+                    KeyCode::Right | KeyCode::Enter | KeyCode::Char(' ') if app.has_content() => {
+                        if !app.fully_displayed && app.ui.reveal_speed == RevealSpeed::Manual {
+                            app.reveal_next_sentence();
+                        } else if !app.fully_displayed {
+                            // Skip typewriter effect
+                            app.skip_typewriter();
+                        } else if !(app.revisiting && app.show_following()) {
+                            // Request new content (handled in main loop)
+                            app.next_requested = true;
+                        }
+                    }
+                    KeyCode::Left | KeyCode::Char('h') if app.has_content() => {
+                        app.back_requested = true;
+                    }
+                    KeyCode::Up if app.has_content() => {
+                        app.scroll_content(-1);
+                    }
+                    KeyCode::Down if app.has_content() => {
+                        app.scroll_content(1);
+                    }
+                    KeyCode::PageUp if app.has_content() => {
+                        app.scroll_content_page(false);
+                    }
+                    KeyCode::PageDown if app.has_content() => {
+                        app.scroll_content_page(true);
+                    }
+                    KeyCode::Char('d') | KeyCode::Delete if app.has_content() => {
+                        app.delete_requested = true;
+                    }
+                    KeyCode::Char('e') if app.has_content() => {
+                        app.dismiss_requested = true;
+                    }
+                    KeyCode::Char('b') | KeyCode::Char('p') if app.has_content() => {
+                        app.pin_requested = true;
+                    }
+                    KeyCode::Char('x') if app.has_content() => {
+                        app.open_explain();
+                    }
+                    KeyCode::Char('X') if app.has_content() => {
+                        app.hide_requested = true;
+                    }
+                    KeyCode::Char('/') => {
+                        app.open_search();
+                    }
+                    KeyCode::Char('u') => {
+                        app.undo_requested = true;
+                    }
+                    KeyCode::Char('s') => {
+                        app.cycle_reveal_speed();
+                    }
+                    KeyCode::Char('+') | KeyCode::Char('=') => {
+                        app.step_reveal_speed(true);
+                    }
+                    KeyCode::Char('-') => {
+                        app.step_reveal_speed(false);
+                    }
+                    KeyCode::Char('a') => {
+                        app.toggle_typewriter();
+                    }
+                    KeyCode::Char('t') => {
+                        app.open_topic_picker();
+                    }
+                    KeyCode::Char(c @ '0'..='9') => {
+                        app.select_topic_slot(c as usize - '0' as usize);
+                    }
+                    KeyCode::Char('f') => {
+                        app.toggle_quick_bites();
+                    }
+                    KeyCode::Char('y') => {
+                        app.toggle_chronological();
+                    }
+                    KeyCode::Char('l') => {
+                        app.toggle_guided_order();
+                    }
+                    KeyCode::Char('g') => {
+                        app.toggle_trends();
+                    }
+                    KeyCode::Char('c') => {
+                        app.toggle_calendar();
+                    }
+                    KeyCode::Char('o') => {
+                        app.toggle_focus();
+                    }
+                    KeyCode::Char('n') => {
+                        app.open_fetch();
+                    }
+                    KeyCode::Char('v') => {
+                        app.toggle_preview();
+                    }
+                    KeyCode::Char('[') => {
+                        app.balance_nudge -= BALANCE_STEP;
+                    }
+                    KeyCode::Char(']') => {
+                        app.balance_nudge += BALANCE_STEP;
+                    }
+                    KeyCode::Char('?') => {
+                        app.toggle_help();
+                    }
+                    KeyCode::Char('i') => {
+                        app.toggle_about();
                     }
                     _ => {}
                 }
@@ -156,6 +1540,10 @@ pub fn handle_events(app: &mut App) -> io::Result<()> {
 /// This demonstrates complex layout management and widget composition
 pub fn render_ui(frame: &mut Frame, app: &App) {
     let size = frame.size();
+    if size.width < MIN_TERMINAL_WIDTH || size.height < MIN_TERMINAL_HEIGHT {
+        render_too_small(frame, size);
+        return;
+    }
 
     // Create main layout with margins for a clean look
     let main_area = Layout::default()
@@ -172,27 +1560,127 @@ pub fn render_ui(frame: &mut Frame, app: &App) {
     // Render status bar
     render_status_bar(frame, app, main_area[0]);
 
-    // Render separator line
-    render_separator(frame, main_area[1]);
+    // Render separator line, doubling as a progress gauge for longer content
+    match app.progress_percent() {
+        Some(percent) => render_progress(frame, percent, main_area[1]),
+        None => render_separator(frame, main_area[1]),
+    }
 
-    // Render main content
-    render_content(frame, app, main_area[2]);
+    // Render main content, or the break, help, fetch, trends, calendar or preview screen in its place
+    if app.on_break() {
+        render_break(frame, app, main_area[2]);
+    } else if app.show_fetch {
+        render_fetch(frame, app, main_area[2]);
+    } else if app.show_help {
+        render_key_help(frame, app, main_area[2]);
+    } else if app.show_trends {
+        render_trends(frame, app, main_area[2]);
+    } else if app.show_calendar {
+        render_calendar(frame, app, main_area[2]);
+    } else if app.show_preview {
+        render_preview(frame, app, main_area[2]);
+    } else {
+        render_content(frame, app, main_area[2]);
+        if app.explain.is_some() {
+            render_explain(frame, app, main_area[2]);
+        }
+        if app.topic_picker.is_some() {
+            render_topic_picker(frame, app, main_area[2]);
+        }
+        if app.search.is_some() {
+            render_search(frame, app, main_area[2]);
+        }
+        if app.show_about {
+            render_about(frame, app, main_area[2]);
+        }
+    }
 
     // Render help text
     render_help(frame, app, main_area[3]);
 }
 
+/// Ask for a bigger terminal, in as much of the message as fits
+fn render_too_small(frame: &mut Frame, area: Rect) {
+    let lines = vec![
+        Line::from(Span::styled("Terminal too small", Style::default().fg(Color::Yellow))),
+        Line::from(Span::styled(
+            format!("Needs {}x{}", MIN_TERMINAL_WIDTH, MIN_TERMINAL_HEIGHT),
+            Style::default().fg(Color::DarkGray),
+        )),
+    ];
+    let height = area.height.min(lines.len() as u16);
+    let message = Rect {
+        y: area.y + (area.height - height) / 2,
+        height,
+        ..area
+    };
+    frame.render_widget(
+        Paragraph::new(lines).alignment(Alignment::Center).wrap(Wrap { trim: true }),
+        message,
+    );
+}
+
 /// Render the status bar
 fn render_status_bar(frame: &mut Frame, app: &App, area: ratatui::layout::Rect) {
     let status_text = if app.status_message.is_empty() {
         if let Some(ref content) = app.current_content {
-            format!("Topic: {} | Words: {}", content.topic, content.word_count)
+            let filtered = match app.topic_filter {
+                Some(_) if app.quick_bites => " (filtered)".to_string(),
+                Some(_) => match topic_filter_label(app.topic_filter_share) {
+                    Some(label) => format!(" ({}, {})", label, guided_order_label(app.ui.guided_topic_order)),
+                    None => String::new(),
+                },
+                None => String::new(),
+            };
+            let kind = match content.kind {
+                ContentKind::Article => String::new(),
+                kind => format!(" | {}", kind),
+            };
+            let badge = if app.resumed {
+                "Pick up where you left off? | ".to_string()
+            } else if app.revisiting {
+                format!("Back {}/{} | ", app.history_pos + 1, app.history.len())
+            } else {
+                String::new()
+            };
+            let bookmarked = if app.current_is_bookmarked { " | ★ Bookmarked" } else { "" };
+            let sensitive = if app.current_is_sensitive { " | Sensitive" } else { "" };
+            // Stored when the story was served, so the text isn't parsed again every frame
+            let era = match app.chronological {
+                Some((year, id)) if id == content.id => format!(" | {}", format_year(year)),
+                _ => String::new(),
+            };
+            format!(
+                "{}Topic: {}{}{}{}{}{} | Words: {} | Added {}",
+                badge,
+                content.topic,
+                filtered,
+                kind,
+                era,
+                bookmarked,
+                sensitive,
+                content.word_count,
+                content.created_at_local().format("%-d %b %Y %H:%M")
+            )
         } else {
             "tellme - Random Knowledge from Wikipedia".to_string()
         }
     } else {
         app.status_message.clone()
     };
+    let status_text = match app.remaining_sentences() {
+        Some(1) => format!("{} | 1 sentence left", status_text),
+        Some(left) => format!("{} | {} sentences left", status_text, left),
+        None => status_text,
+    };
+    let status_text = match (app.focus_remaining(), app.on_break()) {
+        (Some(remaining), false) => format!("{} | Focus {}", status_text, format_countdown(remaining)),
+        _ => status_text,
+    };
+    let status_text = match app.fetch.as_ref().filter(|fetch| !fetch.finished && !app.show_fetch) {
+        Some(fetch) => format!("{} | Fetching: {} new", status_text, fetch.units_added),
+        None => status_text,
+    };
 
     let status = Paragraph::new(status_text)
         .style(Style::default().fg(Color::Yellow))
@@ -209,9 +1697,32 @@ fn render_separator(frame: &mut Frame, area: ratatui::layout::Rect) {
     frame.render_widget(separator, area);
 }
 
+/// Render the reading progress gauge as a thin bar with the percentage at the end
+fn render_progress(frame: &mut Frame, percent: u16, area: Rect) {
+    let label = format!(" {:>3}%", percent);
+    let bar_width = (area.width as usize).saturating_sub(label.len());
+    let filled = progress_fill(bar_width, percent);
+
+    let gauge = Paragraph::new(Line::from(vec![
+        Span::styled("━".repeat(filled), Style::default().fg(Color::Cyan)),
+        Span::styled("─".repeat(bar_width - filled), Style::default().fg(Color::DarkGray)),
+        Span::styled(label, Style::default().fg(Color::DarkGray)),
+    ]));
+
+    frame.render_widget(gauge, area);
+}
+
+/// Number of filled cells in a bar `width` cells wide at `percent`
+fn progress_fill(width: usize, percent: u16) -> usize {
+    width * usize::from(percent.min(100)) / 100
+}
+
 /// Render the main content area
 fn render_content(frame: &mut Frame, app: &App, area: ratatui::layout::Rect) {
     if let Some(ref content) = app.current_content {
+        // Keep lines readable on wide terminals by clamping the column width
+        let column = content_column(area, app.ui.max_content_width);
+
         // Create content layout
         let content_layout = Layout::default()
             .direction(Direction::Vertical)
@@ -219,12 +1730,12 @@ fn render_content(frame: &mut Frame, app: &App, area: ratatui::layout::Rect) {
                 Constraint::Length(3), // Title
                 Constraint::Min(0),    // Content
             ])
-            .split(area);
+            .split(column);
 
         // Render title
         let title = Paragraph::new(vec![
             Line::from(Span::styled(
-                &content.title,
+                app.display_title(),
                 Style::default().fg(Color::Cyan),
             )),
         ])
@@ -243,19 +1754,92 @@ fn render_content(frame: &mut Frame, app: &App, area: ratatui::layout::Rect) {
             String::new()
         };
 
-        // Add cursor if still typing
-        let content_text = if !app.fully_displayed && !displayed_content.is_empty() {
-            format!("{}▋", displayed_content) // Add block cursor
+        // One line per paragraph with blank lines between, and a cursor if still typing
+        let typing = !app.fully_displayed && !displayed_content.is_empty();
+        let lines = paragraph_lines(&displayed_content, app.ui.paragraph_spacing, typing);
+
+        // Leave the last column for a scrollbar once the text outgrows the area,
+        // counting the blank lines too
+        let mut text_area = content_layout[1];
+        let mut height = wrapped_height(&lines, text_area.width);
+        let overflowing = height > usize::from(text_area.height) && text_area.width > 1;
+        if overflowing {
+            text_area.width -= 1;
+            height = wrapped_height(&lines, text_area.width);
+        }
+        let max_scroll = u16::try_from(height.saturating_sub(usize::from(text_area.height)))
+            .unwrap_or(u16::MAX);
+        app.scroll_limits.set((max_scroll, text_area.height));
+
+        // Follow the reveal until the reader scrolls by hand
+        let scroll = if app.scroll_follow {
+            max_scroll
         } else {
-            displayed_content
+            app.scroll_offset.min(max_scroll)
+        };
+
+        let alignment = match app.ui.text_alignment {
+            TextAlignment::Left => Alignment::Left,
+            TextAlignment::Center => Alignment::Center,
         };
 
-        let content_paragraph = Paragraph::new(content_text)
+        let content_paragraph = Paragraph::new(lines)
             .style(Style::default().fg(Color::White))
+            .alignment(alignment)
             .wrap(Wrap { trim: true })
+            .scroll((scroll, 0))
             .block(Block::default().borders(Borders::NONE));
 
-        frame.render_widget(content_paragraph, content_layout[1]);
+        frame.render_widget(content_paragraph, text_area);
+
+        if overflowing && max_scroll > 0 {
+            // Thumb sized to the share of the text in view
+            let viewport = usize::from(text_area.height) * usize::from(max_scroll) / height.max(1);
+            let mut state = ScrollbarState::new(usize::from(max_scroll))
+                .viewport_content_length(viewport.max(1))
+                .position(usize::from(scroll));
+            let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
+                .begin_symbol(None)
+                .end_symbol(None)
+                .track_symbol(Some("│"))
+                .track_style(Style::default().fg(Color::DarkGray))
+                .thumb_style(Style::default().fg(Color::Gray));
+            frame.render_stateful_widget(scrollbar, content_layout[1], &mut state);
+        }
+    } else if app.topic_filter_empty
+        && (app.topic_filter.is_some() || app.quick_bites || app.chronological.is_some())
+    {
+        // Say clearly that the filter is the problem instead of showing the welcome screen
+        let (problem, hint) = match (app.quick_bites, app.topic_filter) {
+            _ if app.chronological.is_some() => (
+                "No later dated stories".to_string(),
+                "Press y to leave chronological order, or t to change the topic",
+            ),
+            (true, Some(topic)) => (
+                format!("No fun facts for {}", topic),
+                "Press f to go back to regular reading, or t to change the topic",
+            ),
+            (true, None) => (
+                "No fun facts left".to_string(),
+                "Press f to go back to regular reading",
+            ),
+            (false, topic) => (
+                format!("No content for {}", topic.map(|t| t.to_string()).unwrap_or_default()),
+                "Press t or a number key to change the topic",
+            ),
+        };
+        let message = vec![
+            Line::from(""),
+            Line::from(Span::styled(problem, Style::default().fg(Color::Red))),
+            Line::from(""),
+            Line::from(Span::styled(hint, Style::default().fg(Color::Yellow))),
+        ];
+
+        let notice = Paragraph::new(message)
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true });
+
+        frame.render_widget(notice, area);
     } else {
         // Show loading or instructions
         let loading_text = if app.status_message.contains("Loading") {
@@ -297,16 +1881,716 @@ fn render_content(frame: &mut Frame, app: &App, area: ratatui::layout::Rect) {
     }
 }
 
+/// Width of the topic name column on the trends screen
+const TREND_LABEL_WIDTH: u16 = 20;
+
+/// Render the focus break screen in place of the paused content
+fn render_break(frame: &mut Frame, app: &App, area: Rect) {
+    let Some(timer) = app.focus.as_ref() else {
+        return;
+    };
+    let remaining = app.focus_remaining().unwrap_or_default();
+
+    let (countdown, hint) = if remaining.is_zero() {
+        (
+            "Break's over".to_string(),
+            "Press Enter to start the next focus session",
+        )
+    } else {
+        (
+            format!("{} left", format_countdown(remaining)),
+            "Stand up, stretch, look away from the screen • Enter to skip the break",
+        )
+    };
+    let sessions = match timer.completed {
+        1 => "1 focus session done".to_string(),
+        n => format!("{} focus sessions done", n),
+    };
+
+    let message = vec![
+        Line::from(""),
+        Line::from(Span::styled("Break time", Style::default().fg(Color::Green))),
+        Line::from(""),
+        Line::from(Span::styled(countdown, Style::default().fg(Color::White))),
+        Line::from(Span::styled(sessions, Style::default().fg(Color::DarkGray))),
+        Line::from(""),
+        Line::from(Span::styled(hint, Style::default().fg(Color::Yellow))),
+    ];
+    let notice = Paragraph::new(message)
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true });
+    frame.render_widget(notice, area);
+}
+
+/// Render the fetch progress screen: one bar per topic filling up towards its target
+fn render_fetch(frame: &mut Frame, app: &App, area: Rect) {
+    let column = content_column(area, app.ui.max_content_width);
+    let Some(fetch) = app.fetch.as_ref() else {
+        return;
+    };
+
+    let heading = match (&fetch.error, fetch.finished) {
+        (Some(error), _) => Span::styled(format!("Fetch stopped: {}", error), Style::default().fg(Color::Red)),
+        (None, true) => Span::styled(
+            format!(
+                "Fetch complete: {} new stories, {} fun facts",
+                fetch.units_added, fetch.fun_facts_added
+            ),
+            Style::default().fg(Color::Green),
+        ),
+        (None, false) => Span::styled(
+            format!("Fetching from Wikipedia: {} new stories", fetch.units_added),
+            Style::default().fg(Color::Cyan),
+        ),
+    };
+    let mut lines = vec![Line::from(heading), Line::from("")];
+
+    let label_width = TREND_LABEL_WIDTH as usize;
+    let count_width = 14;
+    let bar_width = (column.width as usize).saturating_sub(label_width + count_width);
+    for progress in &fetch.topics {
+        let percent = (progress.units * 100 / fetch.units_per_topic.max(1)).min(100) as u16;
+        let (label_color, bar_color, count) = match progress.state {
+            TopicFetchState::Waiting => (Color::DarkGray, Color::DarkGray, String::new()),
+            TopicFetchState::Fetching => (
+                Color::Yellow,
+                Color::Yellow,
+                format!("{}/{}", progress.units, fetch.units_per_topic),
+            ),
+            TopicFetchState::Done => (
+                Color::White,
+                Color::Green,
+                format!("{}/{}", progress.units, fetch.units_per_topic),
+            ),
+            TopicFetchState::DoneEarlier => (Color::White, Color::DarkGray, "done earlier".to_string()),
+            TopicFetchState::Failed => (Color::White, Color::Red, "failed".to_string()),
+        };
+        let filled = match progress.state {
+            TopicFetchState::DoneEarlier => 0,
+            _ => progress_fill(bar_width, percent),
+        };
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!("{:<width$}", progress.topic.to_string(), width = label_width),
+                Style::default().fg(label_color),
+            ),
+            Span::styled("━".repeat(filled), Style::default().fg(bar_color)),
+            Span::styled("─".repeat(bar_width - filled), Style::default().fg(Color::DarkGray)),
+            Span::styled(format!(" {}", count), Style::default().fg(Color::DarkGray)),
+        ]));
+    }
+
+    if !fetch.activity.is_empty() {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            fetch.activity.clone(),
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+
+    frame.render_widget(Paragraph::new(lines), column);
+}
+
+/// Render one sparkline per topic showing how many items were read in each bucket
+fn render_trends(frame: &mut Frame, app: &App, area: Rect) {
+    let column = content_column(area, app.ui.max_content_width);
+
+    let Some(trend) = app.trend.as_ref().filter(|t| !t.topics.is_empty()) else {
+        let empty = Paragraph::new(vec![
+            Line::from(""),
+            Line::from(Span::styled(
+                "Nothing read yet — trends appear once you finish a few stories",
+                Style::default().fg(Color::DarkGray),
+            )),
+        ])
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true });
+        frame.render_widget(empty, column);
+        return;
+    };
+
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(
+            std::iter::once(Constraint::Length(2))
+                .chain(trend.topics.iter().map(|_| Constraint::Length(1)))
+                .chain(std::iter::once(Constraint::Min(0)))
+                .collect::<Vec<_>>(),
+        )
+        .split(column);
+
+    // Show only the most recent buckets that fit
+    let chart_width = column.width.saturating_sub(TREND_LABEL_WIDTH) as usize;
+    let shown = trend.labels.len().min(chart_width);
+    if shown == 0 {
+        let narrow = Paragraph::new(Span::styled(
+            "Too narrow for trends",
+            Style::default().fg(Color::DarkGray),
+        ))
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true });
+        frame.render_widget(narrow, column);
+        return;
+    }
+    let first_shown = trend.labels.len() - shown;
+
+    let heading = format!(
+        "Reads per topic, {} ({} → {})",
+        trend.bucket,
+        trend.labels[first_shown],
+        trend.labels[trend.labels.len() - 1]
+    );
+    frame.render_widget(
+        Paragraph::new(heading).style(Style::default().fg(Color::Cyan)),
+        rows[0],
+    );
+
+    for (i, (topic, series)) in trend.topics.iter().enumerate() {
+        let row = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Length(TREND_LABEL_WIDTH), Constraint::Min(0)])
+            .split(rows[i + 1]);
+
+        frame.render_widget(
+            Paragraph::new(topic.to_string()).style(Style::default().fg(Color::White)),
+            row[0],
+        );
+        frame.render_widget(
+            Sparkline::default()
+                .data(&series[first_shown..])
+                .style(Style::default().fg(Color::Yellow)),
+            row[1],
+        );
+    }
+}
+
+/// Weekday labels for the calendar rows, Monday first; every other row is left blank
+const CALENDAR_WEEKDAYS: [&str; 7] = ["Mon", "", "Wed", "", "Fri", "", ""];
+
+/// Width of the weekday label column on the activity calendar
+const CALENDAR_LABEL_WIDTH: usize = 4;
+
+/// Cell characters for shading levels 0-4
+const CALENDAR_SHADES: [char; 5] = ['·', '░', '▒', '▓', '█'];
+
+/// Render a year of full reads as a grid of weeks (columns) by weekdays (rows)
+fn render_calendar(frame: &mut Frame, app: &App, area: Rect) {
+    use chrono::Datelike;
+    let column = content_column(area, app.ui.max_content_width);
+
+    let Some(calendar) = app.calendar.as_ref() else {
+        return;
+    };
+
+    let newer = if calendar.year < current_year() {
+        format!("{} →", calendar.year + 1)
+    } else {
+        String::new()
+    };
+    let heading = format!("← {}  Reading activity  {}", calendar.year - 1, newer);
+    let summary = format!(
+        "{}: {} stories read on {} days",
+        calendar.year,
+        calendar.total(),
+        calendar.active_days()
+    );
+    let mut lines = vec![
+        Line::from(Span::styled(heading, Style::default().fg(Color::Cyan))),
+        Line::from(Span::styled(summary, Style::default().fg(Color::White))),
+        Line::from(""),
+    ];
+
+    // Month names over the week in which each month starts, where there's room
+    let weeks = calendar.weeks();
+    let mut months = vec![' '; weeks + 3];
+    for month in 1..=12 {
+        let Some(first) = chrono::NaiveDate::from_ymd_opt(calendar.year, month, 1) else {
+            continue;
+        };
+        let (week, _) = calendar.cell(first.ordinal0() as usize);
+        let name = first.format("%b").to_string();
+        if months[week..week + 3].iter().all(|c| *c == ' ')
+            && (week == 0 || months[week - 1] == ' ')
+        {
+            for (i, c) in name.chars().enumerate() {
+                months[week + i] = c;
+            }
+        }
+    }
+    lines.push(Line::from(Span::styled(
+        format!(
+            "{}{}",
+            " ".repeat(CALENDAR_LABEL_WIDTH),
+            months.iter().collect::<String>().trim_end()
+        ),
+        Style::default().fg(Color::DarkGray),
+    )));
+
+    // Each row holds one weekday; days outside the year, or still to come, stay blank
+    let today = chrono::Local::now().date_naive();
+    let mut grid = vec![vec![None; weeks]; 7];
+    for (index, count) in calendar.days.iter().enumerate() {
+        if calendar.date(index).is_none_or(|date| date > today) {
+            continue;
+        }
+        let (week, weekday) = calendar.cell(index);
+        grid[weekday][week] = Some(calendar.intensity(*count));
+    }
+    for (weekday, row) in grid.iter().enumerate() {
+        let mut spans = vec![Span::styled(
+            format!("{:<width$}", CALENDAR_WEEKDAYS[weekday], width = CALENDAR_LABEL_WIDTH),
+            Style::default().fg(Color::DarkGray),
+        )];
+        spans.extend(row.iter().map(|level| match level {
+            None => Span::raw(" "),
+            Some(0) => Span::styled(
+                CALENDAR_SHADES[0].to_string(),
+                Style::default().fg(Color::DarkGray),
+            ),
+            Some(level) => Span::styled(
+                CALENDAR_SHADES[*level].to_string(),
+                Style::default().fg(Color::Green),
+            ),
+        }));
+        lines.push(Line::from(spans));
+    }
+
+    lines.push(Line::from(""));
+    let mut legend = vec![Span::styled(
+        format!("{}Less ", " ".repeat(CALENDAR_LABEL_WIDTH)),
+        Style::default().fg(Color::DarkGray),
+    )];
+    for (level, shade) in CALENDAR_SHADES.iter().enumerate() {
+        let color = if level == 0 { Color::DarkGray } else { Color::Green };
+        legend.push(Span::styled(shade.to_string(), Style::default().fg(color)));
+    }
+    legend.push(Span::styled(" More", Style::default().fg(Color::DarkGray)));
+    lines.push(Line::from(legend));
+
+    frame.render_widget(Paragraph::new(lines), column);
+}
+
+/// Render the stories the recommender would likely serve next, for seeing why it picks what it does
+fn render_preview(frame: &mut Frame, app: &App, area: Rect) {
+    let column = content_column(area, app.ui.max_content_width);
+
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled("Likely next picks", Style::default().fg(Color::Cyan)),
+            Span::styled(format!("  ({} strategy)", app.strategy), Style::default().fg(Color::DarkGray)),
+        ]),
+        Line::from(Span::styled(
+            "Drawn the way the recommender draws, so each refresh can differ. Pinned and resumed stories can come up in between.",
+            Style::default().fg(Color::DarkGray),
+        )),
+        Line::from(""),
+    ];
+    if app.preview.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "Nothing left to pick — fetch more stories or change the topic filter",
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+    for (i, (topic, content)) in app.preview.iter().enumerate() {
+        lines.push(Line::from(vec![
+            Span::styled(format!("{:>3}. ", i + 1), Style::default().fg(Color::DarkGray)),
+            Span::styled(
+                format!("{} {:<width$}", topic.icon(), topic.to_string(), width = TREND_LABEL_WIDTH as usize),
+                Style::default().fg(Color::Yellow),
+            ),
+            Span::styled(content.title.clone(), Style::default().fg(Color::White)),
+        ]));
+    }
+
+    frame.render_widget(Paragraph::new(lines).wrap(Wrap { trim: true }), column);
+}
+
+/// Render the term lookup popup over the content
+fn render_explain(frame: &mut Frame, app: &App, area: Rect) {
+    let Some(explain) = &app.explain else {
+        return;
+    };
+    let width = area.width.min(EXPLAIN_POPUP_WIDTH);
+    let height = area.height.min(EXPLAIN_POPUP_HEIGHT);
+    let popup = Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    };
+
+    let dim = Style::default().fg(Color::DarkGray);
+    let lines = match &explain.state {
+        ExplainState::Choosing => {
+            // Keep the selection in view when there are more terms than rows
+            let rows = usize::from(height.saturating_sub(2)).max(1);
+            let first = explain.selected.saturating_sub(rows - 1);
+            explain
+                .terms
+                .iter()
+                .enumerate()
+                .skip(first)
+                .take(rows)
+                .map(|(i, term)| {
+                    if i == explain.selected {
+                        Line::from(Span::styled(format!("▸ {}", term), Style::default().fg(Color::Yellow)))
+                    } else {
+                        Line::from(format!("  {}", term))
+                    }
+                })
+                .collect()
+        }
+        ExplainState::LookingUp(term) => {
+            vec![Line::from(Span::styled(format!("Looking up {}…", term), dim))]
+        }
+        ExplainState::Found(summary) => vec![
+            Line::from(Span::styled(summary.title.clone(), Style::default().fg(Color::Cyan))),
+            Line::from(""),
+            Line::from(summary.extract.clone()),
+            Line::from(""),
+            Line::from(Span::styled(summary.source_url.clone(), dim)),
+        ],
+        ExplainState::NotFound(term) => vec![Line::from(Span::styled(
+            format!("Wikipedia has no article for \"{}\"", term),
+            dim,
+        ))],
+    };
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(dim)
+        .title(" Explain ");
+    frame.render_widget(Clear, popup);
+    frame.render_widget(
+        Paragraph::new(lines)
+            .style(Style::default().fg(Color::White))
+            .wrap(Wrap { trim: false })
+            .block(block),
+        popup,
+    );
+}
+
+/// Render the search prompt and its results over the content
+fn render_search(frame: &mut Frame, app: &App, area: Rect) {
+    let Some(search) = &app.search else {
+        return;
+    };
+    let width = area.width.min(EXPLAIN_POPUP_WIDTH);
+    let height = area.height.min(EXPLAIN_POPUP_HEIGHT);
+    let popup = Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    };
+
+    let dim = Style::default().fg(Color::DarkGray);
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled("/ ", Style::default().fg(Color::Cyan)),
+            Span::raw(search.query.clone()),
+            Span::styled("▏", dim),
+        ]),
+        Line::from(""),
+    ];
+    if search.query.trim().is_empty() {
+        lines.push(Line::from(Span::styled("Type words from a title or story", dim)));
+    } else if search.results.is_empty() {
+        lines.push(Line::from(Span::styled("No stories match", dim)));
+    } else {
+        // Keep the selection in view when there are more results than rows
+        let rows = usize::from(height.saturating_sub(4)).max(1);
+        let first = search.selected.saturating_sub(rows - 1);
+        lines.extend(search.results.iter().enumerate().skip(first).take(rows).map(|(i, content)| {
+            let label = format!("{} {}", content.topic.icon(), content.title);
+            if i == search.selected {
+                Line::from(Span::styled(format!("▸ {}", label), Style::default().fg(Color::Yellow)))
+            } else {
+                Line::from(format!("  {}", label))
+            }
+        }));
+    }
+
+    let block = Block::default().borders(Borders::ALL).border_style(dim).title(" Search ");
+    frame.render_widget(Clear, popup);
+    frame.render_widget(
+        Paragraph::new(lines).style(Style::default().fg(Color::White)).block(block),
+        popup,
+    );
+}
+
+/// Render the topic picker popup over the content
+fn render_topic_picker(frame: &mut Frame, app: &App, area: Rect) {
+    let Some(selected) = app.topic_picker else {
+        return;
+    };
+    let topics = Topic::all();
+    let width = area.width.min(TOPIC_PICKER_WIDTH);
+    let height = area.height.min(topics.len() as u16 + 3);
+    let popup = Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    };
+
+    let rows = std::iter::once(("All topics".to_string(), app.topic_filter.is_none())).chain(
+        topics
+            .iter()
+            .map(|&topic| (format!("{} {}", topic.icon(), topic), app.topic_filter == Some(topic))),
+    );
+
+    // Keep the selection in view when there are more topics than rows
+    let visible = usize::from(height.saturating_sub(2)).max(1);
+    let first = selected.saturating_sub(visible - 1);
+    let lines: Vec<Line> = rows
+        .enumerate()
+        .skip(first)
+        .take(visible)
+        .map(|(i, (label, active))| {
+            let marker = if active { " •" } else { "" };
+            if i == selected {
+                Line::from(Span::styled(format!("▸ {}{}", label, marker), Style::default().fg(Color::Yellow)))
+            } else {
+                Line::from(format!("  {}{}", label, marker))
+            }
+        })
+        .collect();
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::DarkGray))
+        .title(" Topic ");
+    frame.render_widget(Clear, popup);
+    frame.render_widget(
+        Paragraph::new(lines).style(Style::default().fg(Color::White)).block(block),
+        popup,
+    );
+}
+
+/// Render the about screen as a box over the reader
+fn render_about(frame: &mut Frame, app: &App, area: Rect) {
+    let repo_url = format!("https://github.com/{}", GITHUB_REPO);
+    let mut rows = vec![
+        ("Version", env!("CARGO_PKG_VERSION").to_string()),
+        ("Project", repo_url.clone()),
+        ("Issues", format!("{}/issues", repo_url)),
+    ];
+    match &app.about {
+        Some(about) => rows.extend([
+            ("Database", about.db_path.clone()),
+            ("Profile", about.profile.clone()),
+            ("Stories", match about.hidden_count {
+                0 => about.content_count.to_string(),
+                hidden => format!("{} ({} hidden)", about.content_count, hidden),
+            }),
+            ("Updates", about.update.clone()),
+        ]),
+        None => rows.push(("", "Loading…".to_string())),
+    }
+
+    let label_style = Style::default().fg(Color::Yellow);
+    let lines: Vec<Line> = rows
+        .into_iter()
+        .map(|(label, value)| {
+            Line::from(vec![Span::styled(format!("{:>9}  ", label), label_style), Span::raw(value)])
+        })
+        .collect();
+
+    let width = area.width.min(ABOUT_WIDTH);
+    let height = area.height.min(lines.len() as u16 + 2);
+    let popup = Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::DarkGray))
+        .title(" About tellme ");
+    frame.render_widget(Clear, popup);
+    frame.render_widget(
+        Paragraph::new(lines)
+            .style(Style::default().fg(Color::White))
+            .wrap(Wrap { trim: false })
+            .block(block),
+        popup,
+    );
+}
+
+/// Center a column no wider than `max_width` inside `area`
+/// A `max_width` of 0 disables the clamp
+fn content_column(area: Rect, max_width: u16) -> Rect {
+    if max_width == 0 || area.width <= max_width {
+        return area;
+    }
+
+    Rect {
+        x: area.x + (area.width - max_width) / 2,
+        width: max_width,
+        ..area
+    }
+}
+
+/// Split text into paragraphs (separated by blank lines) and lay them out as lines with
+/// `spacing` blank lines between them, ending in a block cursor if `cursor` is set
+fn paragraph_lines(text: &str, spacing: usize, cursor: bool) -> Vec<Line<'static>> {
+    let mut lines = Vec::new();
+    for (i, paragraph) in text.split("\n\n").enumerate() {
+        if i > 0 {
+            lines.extend(std::iter::repeat_with(Line::default).take(spacing));
+        }
+        lines.extend(
+            paragraph
+                .trim_end_matches('\n')
+                .split('\n')
+                .map(|line| Line::from(line.to_string())),
+        );
+    }
+
+    if cursor {
+        if let Some(last) = lines.last_mut() {
+            last.spans.push(Span::raw("▋"));
+        }
+    }
+    lines
+}
+
+/// Rows `lines` take up when word-wrapped to `width` columns
+/// Mirrors the greedy wrapping `Paragraph` does, so it is exact for ordinary prose
+fn wrapped_height(lines: &[Line], width: u16) -> usize {
+    let width = usize::from(width.max(1));
+    lines
+        .iter()
+        .map(|line| {
+            let text: String = line.spans.iter().map(|span| span.content.as_ref()).collect();
+            let mut rows = 1;
+            let mut current = 0;
+            for word in text.split_whitespace() {
+                let len = word.chars().count();
+                if current == 0 {
+                    current = len;
+                } else if current + 1 + len <= width {
+                    current += 1 + len;
+                } else {
+                    rows += 1;
+                    current = len;
+                }
+                // Words longer than the line are broken across rows
+                if current > width {
+                    rows += (current - 1) / width;
+                    current = (current - 1) % width + 1;
+                }
+            }
+            rows
+        })
+        .sum()
+}
+
+/// Render the key help screen, including the number key topic slots
+fn render_key_help(frame: &mut Frame, app: &App, area: Rect) {
+    let column = content_column(area, app.ui.max_content_width);
+    let key_style = Style::default().fg(Color::Yellow);
+    let text_style = Style::default().fg(Color::White);
+    let entry = |key: String, description: String| {
+        Line::from(vec![
+            Span::styled(format!("{:>12}  ", key), key_style),
+            Span::styled(description, text_style),
+        ])
+    };
+
+    let mut lines = vec![
+        Line::from(Span::styled("Keys", Style::default().fg(Color::Cyan))),
+        Line::from(""),
+    ];
+    for (key, description) in [
+        ("→ Space Enter", "Finish typing, then next story"),
+        ("← H", "Back to the previous story (→ goes forward again)"),
+        ("↑ ↓ PgUp PgDn", "Scroll a story taller than the screen"),
+        ("D Delete", "Delete this story"),
+        ("E", "Read it elsewhere: skip it and similar stories"),
+        ("B P", "Bookmark or unbookmark: bookmarks come back now and then and skip the rotation penalties"),
+        ("X", "Explain a name in this story, and save the explanation if you like"),
+        ("Shift+X", "Hide this story for good, e.g. a broken fragment"),
+        ("/", "Search the library by title and text, and read a match"),
+        ("U Ctrl+Z", "Undo the last delete"),
+        ("S", "Cycle reveal speed; manual shows one more sentence per → Space Enter"),
+        ("+ -", "Type faster or slower"),
+        ("A", "Typewriter off (show stories at once), or back on"),
+        ("T", "Pick a topic to read from (Esc goes back to all topics)"),
+        ("L", "With a topic locked: read it in order, or at random"),
+        ("F", "Quick bites: fun facts only, or back to regular reading"),
+        ("Y", "Chronological order: dated stories from the oldest on, or back to regular reading"),
+        ("G", "Topic trends"),
+        ("C", "Reading activity calendar"),
+        ("O", "Start or stop the focus timer"),
+        ("N", "Fetch new stories from Wikipedia and watch the progress"),
+        ("[ ]", "Stick to favourite topics more, or explore more"),
+        ("V", "Preview what the recommender would pick next"),
+        ("I", "About tellme: version, library and where to report problems"),
+        ("?", "This help"),
+        ("Q Esc", "Quit (Esc first clears a topic filter)"),
+    ] {
+        lines.push(entry(key.to_string(), description.to_string()));
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled("Topics", Style::default().fg(Color::Cyan))));
+    lines.push(Line::from(""));
+    lines.push(entry("0".to_string(), "All topics".to_string()));
+    for (i, topic) in app.ui.topic_slots.iter().take(MAX_TOPIC_SLOTS).enumerate() {
+        lines.push(entry((i + 1).to_string(), format!("{} {}", topic.icon(), topic)));
+    }
+
+    frame.render_widget(Paragraph::new(lines), column);
+}
+
 /// Render help text at the bottom
 fn render_help(frame: &mut Frame, app: &App, area: ratatui::layout::Rect) {
-    let help_text = if app.has_content() {
+    let help_text = if app.on_break() {
+        "Enter Next focus session • O Stop timer • Q Quit"
+    } else if app.show_fetch {
+        if app.fetching() {
+            "N Back to reading (the fetch keeps going) • Q Quit"
+        } else {
+            "Enter Fetch more • N Back to reading • Q Quit"
+        }
+    } else if app.show_help {
+        "I About • Any key to go back • Q Quit"
+    } else if app.show_about {
+        "Any key to go back • Q Quit"
+    } else if app.show_trends {
+        "W Weekly/Monthly • G Back • Q Quit"
+    } else if app.show_calendar {
+        "←/→ Year • C Back • Q Quit"
+    } else if app.show_preview {
+        "R Draw again • V Back • Q Quit"
+    } else if let Some(explain) = &app.explain {
+        match explain.state {
+            ExplainState::Choosing => "↑/↓ Choose • Enter Explain • X Close • Q Quit",
+            ExplainState::LookingUp(_) => "Esc Back • X Close • Q Quit",
+            ExplainState::Found(_) => "S Save as a story • Esc Back • X Close • Q Quit",
+            ExplainState::NotFound(_) => "Esc Back • X Close • Q Quit",
+        }
+    } else if app.search.is_some() {
+        "↑/↓ Choose • Enter Read • Esc Close"
+    } else if app.topic_picker.is_some() {
+        "↑/↓ Choose • Enter Filter • Esc All topics • T Close • Q Quit"
+    } else if app.has_content() {
         if app.fully_displayed {
-            "→ Next • Space/Enter Next • Q Quit"
+            "← Back • → Next • D Delete • E Seen elsewhere • U Undo • S +/- Speed • T/1-9 Topic • G Trends • C Calendar • ? Help • Q Quit"
         } else {
-            "→ Skip typing • Q Quit"
+            if app.ui.reveal_speed == RevealSpeed::Manual {
+                "→ Next sentence • D Delete • E Seen elsewhere • S Speed • T/1-9 Topic • ? Help • Q Quit"
+            } else {
+                "→ Skip typing • D Delete • E Seen elsewhere • S +/- Speed • A Instant • T/1-9 Topic • ? Help • Q Quit"
+            }
         }
+    } else if app.topic_filter_empty {
+        "T/0-9 Topic • F Quick bites • Y Chronological • ? Help • Q Quit"
     } else {
-        "Any key to start • Q Quit"
+        "N Fetch new stories • ? Help • Q Quit"
     };
 
     let help = Paragraph::new(help_text)
@@ -316,18 +2600,295 @@ fn render_help(frame: &mut Frame, app: &App, area: ratatui::layout::Rect) {
     frame.render_widget(help, area);
 }
 
-/// Calculate typing speed for the typewriter effect
-/// This demonstrates time-based calculations
-pub fn calculate_typing_delay(content_length: usize) -> Duration {
-    // Adjust typing speed based on content length
-    // Longer content types faster to avoid very long waits
-    let base_delay_ms = if content_length > 1000 {
-        20 // Fast for long content
-    } else if content_length > 500 {
-        35 // Medium for medium content
-    } else {
-        50 // Slower for short content
-    };
-    
-    Duration::from_millis(base_delay_ms)
-} 
\ No newline at end of file
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::backend::TestBackend;
+
+    /// Draw `app` on a `width` x `height` test terminal and return its rows as text
+    fn draw(app: &App, width: u16, height: u16) -> Vec<String> {
+        let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+        terminal.draw(|frame| render_ui(frame, app)).unwrap();
+        let buffer = terminal.backend().buffer();
+        (0..height)
+            .map(|y| (0..width).map(|x| buffer.get(x, y).symbol.as_str()).collect())
+            .collect()
+    }
+
+    /// Leftmost and rightmost columns holding text on the rows that contain `needle`
+    fn text_span(rows: &[String], needle: &str) -> (usize, usize) {
+        let mut span = (usize::MAX, 0);
+        for row in rows.iter().filter(|row| row.contains(needle)) {
+            let cells: Vec<&str> = row.split("").filter(|cell| !cell.is_empty()).collect();
+            let first = cells.iter().position(|cell| *cell != " ").unwrap();
+            let last = cells.iter().rposition(|cell| *cell != " ").unwrap();
+            span = (span.0.min(first), span.1.max(last));
+        }
+        span
+    }
+
+    fn reader(ui: UiConfig, text: &str) -> App {
+        let mut app = App::new().with_ui_config(ui);
+        app.set_content(ContentUnit::new(
+            Topic::AncientRome,
+            "Story".to_string(),
+            text.to_string(),
+            "https://example.org/story".to_string(),
+        ));
+        app.skip_typewriter();
+        app
+    }
+
+    fn long_text() -> String {
+        vec!["alpha"; 120].join(" ")
+    }
+
+    #[test]
+    fn wide_terminals_get_a_centered_column_of_the_configured_width() {
+        let app = reader(UiConfig::default(), &long_text());
+        let (first, last) = text_span(&draw(&app, 200, 40), "alpha");
+
+        // 196 columns inside the margins, so a 90-column band starting at 2 + 53
+        assert!(first >= 55, "text starts at column {first}");
+        assert!(last < 145, "text ends at column {last}");
+        assert!(last > 135, "text should fill the column, ends at {last}");
+    }
+
+    #[test]
+    fn narrow_terminals_use_their_full_width() {
+        let app = reader(UiConfig::default(), &long_text());
+        let (first, last) = text_span(&draw(&app, 60, 40), "alpha");
+
+        assert_eq!(first, 2);
+        assert!(last > 50, "text should reach the right margin, ends at {last}");
+    }
+
+    #[test]
+    fn centered_text_sits_in_the_middle_of_the_column() {
+        let ui = UiConfig {
+            text_alignment: TextAlignment::Center,
+            ..UiConfig::default()
+        };
+        let app = reader(ui, "alpha beta");
+        let (first, last) = text_span(&draw(&app, 200, 40), "alpha");
+
+        assert!((first + last).abs_diff(200) <= 2, "text spans {first}..{last}");
+    }
+
+    #[test]
+    fn content_column_clamps_only_wider_areas() {
+        let area = Rect::new(2, 0, 196, 30);
+        assert_eq!(content_column(area, 90), Rect::new(55, 0, 90, 30));
+        assert_eq!(content_column(Rect::new(2, 0, 56, 30), 90), Rect::new(2, 0, 56, 30));
+        assert_eq!(content_column(area, 0), area, "0 turns the limit off");
+    }
+
+    #[test]
+    fn paragraphs_are_separated_by_the_configured_spacing() {
+        let text = |lines: &[Line]| -> Vec<String> {
+            lines
+                .iter()
+                .map(|line| line.spans.iter().map(|span| span.content.as_ref()).collect())
+                .collect()
+        };
+
+        assert_eq!(text(&paragraph_lines("one\n\ntwo", 0, false)), ["one", "two"]);
+        assert_eq!(text(&paragraph_lines("one\n\ntwo", 2, false)), ["one", "", "", "two"]);
+        assert_eq!(text(&paragraph_lines("one", 1, true)), ["one▋"]);
+    }
+
+    #[test]
+    fn progress_gauge_fills_in_proportion_to_the_text_revealed() {
+        let mut app = reader(UiConfig::default(), &long_text());
+        let total = app.current_content.as_ref().unwrap().content.len();
+
+        for width in [40, 80, 200] {
+            for percent in [0, 25, 50, 99] {
+                app.fully_displayed = false;
+                app.displayed_chars = total * percent / 100 + 1;
+                let shown = usize::from(app.displayed_percent());
+                let rows = draw(&app, width, 20);
+
+                // The gauge row spans the margins, less the " NNN%" label
+                let bar = usize::from(width) - 4 - 5;
+                let gauge = &rows[3];
+                assert!(gauge.contains(&format!("{:>3}%", shown)), "{gauge}");
+                assert_eq!(gauge.matches('━').count(), bar * shown / 100, "{width} columns at {shown}%");
+                assert_eq!(gauge.matches('━').count() + gauge.matches('─').count(), bar);
+            }
+        }
+
+        app.skip_typewriter();
+        let rows = draw(&app, 80, 20);
+        assert_eq!(rows[3].matches('━').count(), 80 - 4 - 5);
+    }
+
+    #[test]
+    fn progress_gauge_is_hidden_for_short_items() {
+        let app = reader(UiConfig::default(), "A short story of a few words.");
+        let rows = draw(&app, 80, 20);
+        assert!(!rows[3].contains('%') && !rows[3].contains('━'), "{}", rows[3]);
+    }
+
+    #[test]
+    fn tiny_terminals_ask_for_more_room_instead_of_panicking() {
+        let mut app = reader(UiConfig::default(), &long_text());
+        app.show_help = true;
+        for (width, height) in [(1, 1), (1, 20), (80, 1), (10, 5), (MIN_TERMINAL_WIDTH - 1, MIN_TERMINAL_HEIGHT)] {
+            let rows = draw(&app, width, height);
+            if width >= 8 && height >= 2 {
+                assert!(rows.iter().any(|row| row.contains("small")), "{width}x{height}: {rows:#?}");
+            }
+            assert!(rows.iter().all(|row| !row.contains("alpha")), "{width}x{height}: {rows:#?}");
+        }
+
+        app.show_help = false;
+        let rows = draw(&app, MIN_TERMINAL_WIDTH, MIN_TERMINAL_HEIGHT);
+        assert!(rows.iter().all(|row| !row.contains("too small")), "{rows:#?}");
+        // Nothing loaded yet
+        draw(&App::new(), 1, 1);
+    }
+
+    #[test]
+    fn a_topic_filter_taking_no_share_of_picks_is_not_labelled_as_one() {
+        let mut app = reader(UiConfig::default(), "A short story of a few words.");
+        app.topic_filter = Some(Topic::AncientRome);
+        for (share, label) in [(1.0, Some("filtered")), (0.5, Some("focused")), (0.0, None), (f64::NAN, None)] {
+            app.topic_filter_share = share;
+            let rows = draw(&app, 120, 20);
+            let status = rows.iter().find(|row| row.contains("Topic:")).unwrap();
+            match label {
+                Some(label) => assert!(status.contains(&format!("({}, random)", label)), "{share}: {status}"),
+                None => assert!(!status.contains("filtered") && !status.contains("focused"), "{share}: {status}"),
+            }
+        }
+
+        // Quick bites stay within the topic whatever the share
+        app.quick_bites = true;
+        let rows = draw(&app, 120, 20);
+        assert!(rows.iter().any(|row| row.contains("(filtered)")), "{rows:#?}");
+    }
+
+    #[test]
+    fn trends_too_narrow_for_a_chart_say_so_instead_of_panicking() {
+        let mut app = App::new();
+        app.trend = Some(TopicTrend {
+            bucket: TrendBucket::Monthly,
+            labels: vec!["2026-09".to_string(), "2026-10".to_string()],
+            topics: vec![(Topic::AncientRome, vec![2, 3])],
+        });
+        app.show_trends = true;
+
+        // The 20-column label leaves no room for the chart inside the margins
+        let rows = draw(&app, MIN_TERMINAL_WIDTH, MIN_TERMINAL_HEIGHT);
+        assert!(rows.iter().any(|row| row.contains("Too narrow")), "{rows:#?}");
+
+        let rows = draw(&app, 80, 20);
+        assert!(rows.iter().any(|row| row.contains("2026-09 → 2026-10")), "{rows:#?}");
+    }
+
+    #[test]
+    fn resumed_stories_continue_the_typewriter_from_the_saved_offset() {
+        let story = ContentUnit::new(Topic::AncientRome, "Story".to_string(), long_text(), String::new());
+        let mut app = App::new();
+        app.resume_content(story.clone(), 300);
+        assert_eq!(app.displayed_chars, 300);
+        assert!(draw(&app, 80, 20).iter().any(|row| row.contains("Pick up where you left off?")));
+
+        // 40 characters a second at the default speed
+        app.update_typewriter(Duration::from_millis(100));
+        assert_eq!(app.displayed_chars, 304);
+
+        // An offset saved against a longer version of the text stops at its end
+        app.resume_content(story.clone(), 10_000);
+        assert_eq!(app.displayed_chars, story.content.len());
+
+        app.begin_content(story);
+        assert_eq!(app.displayed_chars, 0);
+        assert!(!app.resumed);
+    }
+
+    #[test]
+    fn macro_actions_run_in_order_one_per_turn() {
+        let mut app = reader(UiConfig::default(), "alpha beta");
+        let speed = app.ui.reveal_speed;
+        app.start_macro(&[ReaderAction::Pin, ReaderAction::Typewriter, ReaderAction::Next]);
+
+        app.step_macro();
+        assert!(app.pin_requested && !app.next_requested);
+        // The main loop pins the story before the next turn
+        app.pin_requested = false;
+        app.step_macro();
+        assert_ne!(app.ui.reveal_speed, speed);
+        assert!(!app.next_requested);
+        app.step_macro();
+        assert!(app.next_requested && !app.pin_requested);
+        app.step_macro();
+        assert_eq!(app.status_message, "Ran pin → typewriter → next");
+        assert!(app.running_macro.is_none());
+    }
+
+    #[test]
+    fn macros_stop_at_the_first_action_that_cannot_run() {
+        let mut app = App::new();
+        app.start_macro(&[ReaderAction::Typewriter, ReaderAction::Next, ReaderAction::Pin]);
+        app.step_macro();
+        app.step_macro();
+        assert_eq!(app.status_message, "Macro stopped at next: no story on screen (ran typewriter)");
+        assert!(app.running_macro.is_none());
+        app.step_macro();
+        assert!(!app.pin_requested && !app.next_requested);
+
+        let mut app = reader(UiConfig::default(), "alpha beta");
+        app.start_macro(&[ReaderAction::Undo, ReaderAction::Next]);
+        app.step_macro();
+        assert_eq!(app.status_message, "Macro stopped at undo: nothing to undo");
+        app.step_macro();
+        assert!(!app.next_requested);
+
+        // An action that fails in the main loop drops the rest
+        app.start_macro(&[ReaderAction::Pin, ReaderAction::Next]);
+        app.step_macro();
+        app.abort_macro();
+        app.step_macro();
+        assert!(!app.next_requested);
+    }
+
+    #[test]
+    fn actions_say_why_they_cannot_run() {
+        let mut app = App::new();
+        assert_eq!(app.run_action(ReaderAction::Delete), Err("no story on screen"));
+        assert_eq!(app.run_action(ReaderAction::Back), Err("this is the first story this session"));
+        assert_eq!(app.run_action(ReaderAction::GuidedOrder), Err("no topic is locked"));
+        app.ui.reveal_speed = RevealSpeed::Manual;
+        assert_eq!(app.run_action(ReaderAction::Faster), Err("manual reveal has no speed"));
+        assert_eq!(app.run_action(ReaderAction::Typewriter), Ok(()));
+    }
+
+    #[test]
+    fn macro_keys_only_accept_known_actions() {
+        let ui: UiConfig = toml::from_str(r#"keys = { F5 = ["bookmark", "next"], x = ["hide"] }"#).unwrap();
+        assert_eq!(ui.keys[&MacroKey::Function(5)].actions(), [ReaderAction::Pin, ReaderAction::Next]);
+        assert_eq!(ui.keys[&MacroKey::Char('x')].actions(), [ReaderAction::Hide]);
+
+        let unknown = toml::from_str::<UiConfig>(r#"keys = { F5 = ["pin", "fly"] }"#).unwrap_err();
+        assert!(unknown.to_string().contains("fly"), "{}", unknown);
+        assert!(toml::from_str::<UiConfig>(r#"keys = { F5 = [] }"#).is_err());
+        assert!(toml::from_str::<UiConfig>(r#"keys = { F13 = ["next"] }"#).is_err());
+    }
+
+    #[test]
+    fn chronological_reading_shows_the_year_the_story_was_served_under() {
+        let mut app = reader(UiConfig::default(), "The Normans landed in 1066.");
+        app.status_message.clear();
+        let id = app.current_content.as_ref().unwrap().id;
+        let status = |app: &App| draw(app, 120, 20).into_iter().find(|row| row.contains("Topic:")).unwrap();
+        assert!(!status(&app).contains("1066"));
+
+        // The stored estimate is shown as it is, without reading the text again
+        app.chronological = Some((-500, id));
+        assert!(status(&app).contains("| 500 BCE"), "{}", status(&app));
+        app.chronological = Some((1066, id + 1));
+        assert!(!status(&app).contains("1066"));
+    }
+}